@@ -29,15 +29,31 @@ pub const TYPE_SET: u8 = CLASS_UNIVERSAL | CONSTRUCTED | 17;
 /// Does not support:
 ///
 /// - extended tag IDs.
-/// - indefinite lengths (disallowed by DER).
+/// - indefinite lengths (disallowed by DER), unless lenient decoding is enabled.
 /// - INTEGER values not representable by i64.
+///
+/// Lenient decoding (see [`AsnReader::from_bytes_lenient`]) tolerates exactly
+/// two BER deviations seen in the wild:
+///
+/// - non-minimal long-form lengths, e.g. `0x81 0x05` or `0x82 0x00 0x05`
+///   where `0x05` would do. The strict reader rejects these with
+///   `Error::AsnInvalidLen`.
+/// - indefinite lengths (`0x80`) terminated by an end-of-contents marker
+///   (`0x00 0x00`), nested at most `MAX_INDEFINITE_DEPTH` deep.
+///
+/// Everything else (wrong tags, truncated values, oversized integers) is an
+/// error in both modes.
 pub struct AsnReader<'a> {
     inner: &'a [u8],
+    lenient: bool,
 }
 
 impl<'a> Clone for AsnReader<'a> {
     fn clone(&self) -> AsnReader<'a> {
-        AsnReader { inner: self.inner }
+        AsnReader {
+            inner: self.inner,
+            lenient: self.lenient,
+        }
     }
 }
 
@@ -49,7 +65,26 @@ impl fmt::Debug for AsnReader<'_> {
 
 impl<'a> AsnReader<'a> {
     pub fn from_bytes(bytes: &[u8]) -> AsnReader {
-        AsnReader { inner: bytes }
+        AsnReader {
+            inner: bytes,
+            lenient: false,
+        }
+    }
+
+    /// Create a reader which tolerates non-minimal and indefinite lengths.
+    pub fn from_bytes_lenient(bytes: &[u8]) -> AsnReader {
+        AsnReader {
+            inner: bytes,
+            lenient: true,
+        }
+    }
+
+    /// Create a reader over nested contents, keeping this reader's leniency.
+    pub fn nested<'b>(&self, bytes: &'b [u8]) -> AsnReader<'b> {
+        AsnReader {
+            inner: bytes,
+            lenient: self.lenient,
+        }
     }
 
     pub fn peek_byte(&mut self) -> Result<u8> {
@@ -98,6 +133,10 @@ impl<'a> AsnReader<'a> {
                 bytes[(USIZE_LEN - length_len)..].copy_from_slice(&tail[..length_len]);
 
                 o = unsafe { mem::transmute::<[u8; USIZE_LEN], usize>(bytes).to_be() };
+                if !self.lenient && (o < 128 || tail[0] == 0) {
+                    // non-minimal encoding
+                    return Err(Error::AsnInvalidLen);
+                }
                 self.inner = &tail[length_len..];
                 Ok(o)
             }
@@ -111,12 +150,7 @@ impl<'a> AsnReader<'a> {
         if ident != expected_ident {
             return Err(Error::AsnWrongType);
         }
-        let val_len = self.read_length()?;
-        if val_len > self.inner.len() {
-            return Err(Error::AsnInvalidLen);
-        }
-        let (val, remaining) = self.inner.split_at(val_len);
-        self.inner = remaining;
+        let val = self.read_contents()?;
         decode_i64(val)
     }
 
@@ -125,14 +159,7 @@ impl<'a> AsnReader<'a> {
         if ident != expected_ident {
             return Err(Error::AsnWrongType);
         }
-        let val_len = self.read_length()?;
-        if val_len > self.inner.len() {
-            return Err(Error::AsnInvalidLen);
-        }
-        //dbg!(self.inner.len());
-        let (val, remaining) = self.inner.split_at(val_len);
-        self.inner = remaining;
-        Ok(val)
+        self.read_contents()
     }
 
     pub fn read_constructed<F>(&mut self, expected_ident: u8, f: F) -> Result<()>
@@ -143,14 +170,28 @@ impl<'a> AsnReader<'a> {
         if ident != expected_ident {
             return Err(Error::AsnWrongType);
         }
-        let seq_len = self.read_length()?;
-        if seq_len > self.inner.len() {
+        let seq_bytes = self.read_contents()?;
+        let mut reader = self.nested(seq_bytes);
+        f(&mut reader)
+    }
+
+    /// Read the length octets and return the contents they describe.
+    fn read_contents(&mut self) -> Result<&'a [u8]> {
+        if self.lenient && self.inner.first() == Some(&0x80) {
+            let contents = &self.inner[1..];
+            let len = indefinite_len(contents)?;
+            let (val, remaining) = contents.split_at(len);
+            // skip the end-of-contents marker
+            self.inner = &remaining[2..];
+            return Ok(val);
+        }
+        let val_len = self.read_length()?;
+        if val_len > self.inner.len() {
             return Err(Error::AsnInvalidLen);
         }
-        let (seq_bytes, remaining) = self.inner.split_at(seq_len);
-        let mut reader = AsnReader::from_bytes(seq_bytes);
+        let (val, remaining) = self.inner.split_at(val_len);
         self.inner = remaining;
-        f(&mut reader)
+        Ok(val)
     }
 
     //
@@ -199,12 +240,7 @@ impl<'a> AsnReader<'a> {
         if ident != TYPE_OBJECTIDENTIFIER {
             return Err(Error::AsnWrongType);
         }
-        let val_len = self.read_length()?;
-        if val_len > self.inner.len() {
-            return Err(Error::AsnInvalidLen);
-        }
-        let (input, remaining) = self.inner.split_at(val_len);
-        self.inner = remaining;
+        let input = self.read_contents()?;
 
         Ok(Oid::new(input.into()))
     }
@@ -233,7 +269,14 @@ impl<'a> AsnReader<'a> {
 
     #[allow(clippy::cast_sign_loss)]
     pub fn read_snmp_counter64(&mut self) -> Result<u64> {
-        self.read_i64_type(snmp::TYPE_COUNTER64).map(|v| v as u64)
+        let val = self.read_raw(snmp::TYPE_COUNTER64)?;
+        // 2^63 and above take a ninth byte to keep the high bit clear
+        if let [0, rest @ ..] = val {
+            if let Ok(bytes) = <[u8; 8]>::try_from(rest) {
+                return Ok(u64::from_be_bytes(bytes));
+            }
+        }
+        decode_i64(val).map(|v| v as u64)
     }
 
     pub fn read_snmp_opaque(&mut self) -> Result<&'a [u8]> {
@@ -253,6 +296,39 @@ impl<'a> AsnReader<'a> {
     }
 }
 
+/// Deepest nesting of indefinite lengths the lenient reader follows.
+const MAX_INDEFINITE_DEPTH: usize = 16;
+
+/// Length of indefinite-length contents, up to (not including) the
+/// end-of-contents marker.
+///
+/// Scans the TLVs iteratively, so hostile nesting cannot exhaust the stack;
+/// more than `MAX_INDEFINITE_DEPTH` levels is `Error::AsnInvalidLen`.
+fn indefinite_len(bytes: &[u8]) -> Result<usize> {
+    let mut rdr = AsnReader::from_bytes_lenient(bytes);
+    let mut depth = 0;
+    loop {
+        if rdr.inner.starts_with(&[0, 0]) {
+            if depth == 0 {
+                return Ok(bytes.len() - rdr.inner.len());
+            }
+            depth -= 1;
+            rdr.inner = &rdr.inner[2..];
+            continue;
+        }
+        rdr.read_byte()?;
+        if rdr.inner.first() == Some(&0x80) {
+            depth += 1;
+            if depth > MAX_INDEFINITE_DEPTH {
+                return Err(Error::AsnInvalidLen);
+            }
+            rdr.inner = &rdr.inner[1..];
+            continue;
+        }
+        rdr.read_contents()?;
+    }
+}
+
 fn decode_i64(i: &[u8]) -> Result<i64> {
     if i.len() > mem::size_of::<i64>() {
         return Err(Error::AsnIntOverflow);
@@ -21,6 +21,7 @@ pub struct AsyncSession {
     req_id: Wrapping<i32>,
     send_pdu: pdu::Buf,
     recv_buf: [u8; BUFFER_SIZE],
+    lenient_decoding: bool,
     #[cfg(feature = "v3")]
     security: Option<v3::Security>,
 }
@@ -97,11 +98,23 @@ impl AsyncSession {
             req_id: Wrapping(starting_req_id),
             send_pdu: pdu::Buf::default(),
             recv_buf: [0; BUFFER_SIZE],
+            lenient_decoding: true,
             #[cfg(feature = "v3")]
             security: None,
         })
     }
 
+    /// Tolerate non-minimal and indefinite BER lengths in responses.
+    ///
+    /// On by default, since older releases already accepted non-minimal
+    /// lengths; pass `false` for strict decoding. See
+    /// [`AsnReader`](crate::AsnReader) for exactly which deviations are
+    /// accepted.
+    pub fn with_lenient_decoding(mut self, lenient: bool) -> Self {
+        self.lenient_decoding = lenient;
+        self
+    }
+
     #[cfg(not(feature = "v3"))]
     #[allow(clippy::unused_self, clippy::unused_async)]
     pub async fn init(&mut self) -> Result<()> {
@@ -119,6 +132,7 @@ impl AsyncSession {
             self.req_id += Wrapping(1);
             if let Err(e) = Pdu::from_bytes_inner(
                 Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf).await?,
+                self.lenient_decoding,
                 Some(security),
             ) {
                 if e != Error::AuthUpdated {
@@ -143,11 +157,24 @@ impl AsyncSession {
         }
     }
 
+    /// Discard any datagrams already queued on the socket, without waiting.
+    ///
+    /// Every request does this before sending, so a late reply to an earlier,
+    /// timed-out request is not read as the answer to the next one.
+    pub fn drain_stale(&mut self) {
+        Self::drain(&self.socket, &mut self.recv_buf);
+    }
+
+    fn drain(socket: &UdpSocket, buf: &mut [u8]) {
+        while socket.try_recv(buf).is_ok() {}
+    }
+
     async fn send_and_recv<'a>(
         socket: &UdpSocket,
         pdu: &pdu::Buf,
         out: &'a mut [u8],
     ) -> Result<&'a [u8]> {
+        Self::drain(socket, out);
         if let Ok(_pdu_len) = socket.send(pdu).await {
             match socket.recv(out).await {
                 Ok(len) => Ok(&out[..len]),
@@ -172,6 +199,7 @@ impl AsyncSession {
         )?;
         let resp = Pdu::from_bytes_inner(
             Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf).await?,
+            self.lenient_decoding,
             #[cfg(feature = "v3")]
             self.security.as_mut(),
         )?;
@@ -194,6 +222,7 @@ impl AsyncSession {
         )?;
         let resp = Pdu::from_bytes_inner(
             Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf).await?,
+            self.lenient_decoding,
             #[cfg(feature = "v3")]
             self.security.as_mut(),
         )?;
@@ -223,6 +252,7 @@ impl AsyncSession {
         )?;
         let resp = Pdu::from_bytes_inner(
             Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf).await?,
+            self.lenient_decoding,
             #[cfg(feature = "v3")]
             self.security.as_mut(),
         )?;
@@ -245,6 +275,7 @@ impl AsyncSession {
         )?;
         let resp = Pdu::from_bytes_inner(
             Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf).await?,
+            self.lenient_decoding,
             #[cfg(feature = "v3")]
             self.security.as_mut(),
         )?;
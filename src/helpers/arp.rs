@@ -0,0 +1,196 @@
+//! ARP / neighbor table helpers (IP-MIB).
+
+use std::{collections::HashMap, net::IpAddr};
+
+use crate::{Result, SyncSession};
+
+use super::net::{parse_mac, MacFormat};
+use super::oid::{index_to_ip, oid_arcs, oid_from_arcs};
+use super::session::SessionExt;
+use super::value::OwnedValue;
+
+const IP_NET_TO_PHYSICAL_PHYS_ADDRESS: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 35, 1, 4];
+const IP_NET_TO_PHYSICAL_TYPE: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 35, 1, 6];
+const IP_NET_TO_MEDIA_PHYS_ADDRESS: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 22, 1, 2];
+const IP_NET_TO_MEDIA_TYPE: &[u64] = &[1, 3, 6, 1, 2, 1, 4, 22, 1, 4];
+
+/// One entry of the agent's ARP (IPv4) or neighbor (IPv6) cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArpEntry {
+    pub if_index: u32,
+    pub ip: IpAddr,
+    /// MAC (lowercase, colon-separated unless another `MacFormat` was
+    /// asked for), `None` for incomplete entries.
+    pub mac: Option<String>,
+    /// 1 other, 2 invalid, 3 dynamic, 4 static, 5 local (`ipNetToPhysicalTable` only).
+    pub entry_type: Option<i64>,
+}
+
+/// Walk `ipNetToPhysicalTable`, or `ipNetToMediaTable` on agents that only
+/// have the deprecated table.
+pub(super) fn arp_table(session: &mut SyncSession, mac_format: MacFormat) -> Result<Vec<ArpEntry>> {
+    let entries = walk_table(
+        session,
+        IP_NET_TO_PHYSICAL_PHYS_ADDRESS,
+        IP_NET_TO_PHYSICAL_TYPE,
+        physical_index,
+        mac_format,
+    )?;
+    if !entries.is_empty() {
+        return Ok(entries);
+    }
+    walk_table(
+        session,
+        IP_NET_TO_MEDIA_PHYS_ADDRESS,
+        IP_NET_TO_MEDIA_TYPE,
+        media_index,
+        mac_format,
+    )
+}
+
+fn walk_table(
+    session: &mut SyncSession,
+    phys_address: &[u64],
+    entry_type: &[u64],
+    parse_index: fn(&[u64]) -> Option<(u32, IpAddr)>,
+    mac_format: MacFormat,
+) -> Result<Vec<ArpEntry>> {
+    let types: HashMap<Vec<u64>, i64> = session
+        .walk_values(&oid_from_arcs(entry_type)?)?
+        .into_iter()
+        .filter_map(|(oid, value)| {
+            let index = oid_arcs(&oid).get(entry_type.len()..)?.to_vec();
+            Some((index, value.as_i64()?))
+        })
+        .collect();
+    Ok(session
+        .walk_values(&oid_from_arcs(phys_address)?)?
+        .into_iter()
+        .filter_map(|(oid, value)| {
+            let index = oid_arcs(&oid).get(phys_address.len()..)?.to_vec();
+            let (if_index, ip) = parse_index(&index)?;
+            Some(ArpEntry {
+                if_index,
+                ip,
+                mac: match value {
+                    OwnedValue::OctetString(bytes) if !bytes.is_empty() => {
+                        parse_mac(&bytes).map(|mac| mac_format.reformat(&mac))
+                    }
+                    _ => None,
+                },
+                entry_type: types.get(&index).copied(),
+            })
+        })
+        .collect())
+}
+
+/// `ifIndex.addressType.length.address...`
+fn physical_index(index: &[u64]) -> Option<(u32, IpAddr)> {
+    let (&if_index, rest) = index.split_first()?;
+    let (_address_type, rest) = rest.split_first()?;
+    let (&len, address) = rest.split_first()?;
+    if usize::try_from(len).ok()? != address.len() {
+        return None;
+    }
+    Some((u32::try_from(if_index).ok()?, index_to_ip(address)?))
+}
+
+/// `ifIndex.a.b.c.d`
+fn media_index(index: &[u64]) -> Option<(u32, IpAddr)> {
+    let (&if_index, address) = index.split_first()?;
+    Some((u32::try_from(if_index).ok()?, index_to_ip(address)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+    use crate::helpers::mock::{cell, MockAgent};
+
+    const MAC: [u8; 6] = [0x00, 0x27, 0x22, 0xaa, 0xbb, 0xcc];
+
+    #[test]
+    fn test_arp_table_physical() {
+        let v4 = [2, 1, 4, 10, 0, 0, 1];
+        let mut v6 = vec![3, 2, 16, 0xfe, 0x80];
+        v6.extend([0; 13]);
+        v6.push(1);
+        let agent = MockAgent::start(vec![
+            cell(
+                IP_NET_TO_PHYSICAL_PHYS_ADDRESS,
+                &v4,
+                OwnedValue::OctetString(MAC.to_vec()),
+            ),
+            cell(
+                IP_NET_TO_PHYSICAL_PHYS_ADDRESS,
+                &v6,
+                OwnedValue::OctetString(Vec::new()),
+            ),
+            cell(IP_NET_TO_PHYSICAL_TYPE, &v4, OwnedValue::Integer(3)),
+            cell(IP_NET_TO_PHYSICAL_TYPE, &v6, OwnedValue::Integer(2)),
+        ]);
+        let mut session = agent.session();
+        assert_eq!(
+            session.arp_table().unwrap(),
+            vec![
+                ArpEntry {
+                    if_index: 2,
+                    ip: Ipv4Addr::new(10, 0, 0, 1).into(),
+                    mac: Some("00:27:22:aa:bb:cc".to_string()),
+                    entry_type: Some(3),
+                },
+                ArpEntry {
+                    if_index: 3,
+                    ip: "fe80::1".parse().unwrap(),
+                    mac: None,
+                    entry_type: Some(2),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_arp_table_media_fallback() {
+        let agent = MockAgent::start(vec![
+            cell(
+                IP_NET_TO_MEDIA_PHYS_ADDRESS,
+                &[1, 192, 168, 1, 20],
+                OwnedValue::OctetString(MAC.to_vec()),
+            ),
+            cell(
+                IP_NET_TO_MEDIA_PHYS_ADDRESS,
+                &[1, 192, 168, 1, 30],
+                OwnedValue::OctetString(vec![0x00, 0x27, 0x22, 0x11, 0x22, 0x33]),
+            ),
+            cell(
+                IP_NET_TO_MEDIA_TYPE,
+                &[1, 192, 168, 1, 20],
+                OwnedValue::Integer(4),
+            ),
+        ]);
+        let mut session = agent.session();
+        let entries = session.arp_table().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].ip, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)));
+        assert_eq!(entries[0].entry_type, Some(4));
+        assert_eq!(entries[1].mac.as_deref(), Some("00:27:22:11:22:33"));
+        assert_eq!(entries[1].entry_type, None);
+    }
+
+    #[test]
+    fn test_arp_table_mac_format() {
+        let agent = MockAgent::start(vec![cell(
+            IP_NET_TO_MEDIA_PHYS_ADDRESS,
+            &[1, 192, 168, 1, 20],
+            OwnedValue::OctetString(MAC.to_vec()),
+        )]);
+        let mut session = agent.session();
+        let upper_dash = MacFormat {
+            uppercase: true,
+            separator: '-',
+        };
+        let entries = session.arp_table_with_mac_format(upper_dash).unwrap();
+        assert_eq!(entries[0].mac.as_deref(), Some("00-27-22-AA-BB-CC"));
+    }
+}
@@ -0,0 +1,185 @@
+//! Bridge forwarding database helpers (BRIDGE-MIB).
+
+use std::collections::HashMap;
+
+use crate::{Result, SyncSession};
+
+use super::net::MacFormat;
+use super::oid::{oid_arcs, oid_from_arcs};
+use super::session::SessionExt;
+
+const DOT1D_TP_FDB_PORT: &[u64] = &[1, 3, 6, 1, 2, 1, 17, 4, 3, 1, 2];
+const DOT1D_TP_FDB_STATUS: &[u64] = &[1, 3, 6, 1, 2, 1, 17, 4, 3, 1, 3];
+
+/// `dot1dTpFdbStatus`: how the bridge came to know an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FdbStatus {
+    /// other(1): none of the below, e.g. a static entry.
+    Other,
+    /// invalid(2): no longer valid but not yet flushed.
+    Invalid,
+    /// learned(3): learned from traffic; the usual case.
+    Learned,
+    /// self(4): one of the bridge's own addresses.
+    SelfAddress,
+    /// mgmt(5): also present in `dot1dStaticTable`.
+    Mgmt,
+    /// A value outside the MIB's enumeration.
+    Unknown(i64),
+}
+
+impl From<i64> for FdbStatus {
+    fn from(value: i64) -> Self {
+        match value {
+            1 => FdbStatus::Other,
+            2 => FdbStatus::Invalid,
+            3 => FdbStatus::Learned,
+            4 => FdbStatus::SelfAddress,
+            5 => FdbStatus::Mgmt,
+            other => FdbStatus::Unknown(other),
+        }
+    }
+}
+
+/// One entry of the bridge forwarding database (`dot1dTpFdbTable`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdbEntry {
+    /// MAC decoded from the row index (lowercase, colon-separated unless
+    /// another `MacFormat` was asked for).
+    pub mac: String,
+    /// Bridge port (`dot1dBasePort`, not ifIndex); 0 if unknown.
+    pub port: i64,
+    /// `None` if the agent does not report `dot1dTpFdbStatus` for the row.
+    pub status: Option<FdbStatus>,
+}
+
+/// Walk the port and status columns of `dot1dTpFdbTable`.
+///
+/// The table is indexed by the MAC itself, so the `dot1dTpFdbAddress` column
+/// adds nothing and is not walked.
+pub(super) fn bridge_fdb(
+    session: &mut SyncSession,
+    mac_format: MacFormat,
+) -> Result<Vec<FdbEntry>> {
+    let statuses: HashMap<Vec<u64>, i64> = session
+        .walk_values(&oid_from_arcs(DOT1D_TP_FDB_STATUS)?)?
+        .into_iter()
+        .filter_map(|(oid, value)| {
+            let index = oid_arcs(&oid).get(DOT1D_TP_FDB_STATUS.len()..)?.to_vec();
+            Some((index, value.as_i64()?))
+        })
+        .collect();
+    Ok(session
+        .walk_values(&oid_from_arcs(DOT1D_TP_FDB_PORT)?)?
+        .into_iter()
+        .filter_map(|(oid, value)| {
+            let index = oid_arcs(&oid).get(DOT1D_TP_FDB_PORT.len()..)?.to_vec();
+            Some(FdbEntry {
+                mac: index_to_mac(&index, mac_format)?,
+                port: value.as_i64()?,
+                status: statuses.get(&index).map(|&status| FdbStatus::from(status)),
+            })
+        })
+        .collect())
+}
+
+/// `a.b.c.d.e.f`, one arc per MAC byte.
+fn index_to_mac(index: &[u64], mac_format: MacFormat) -> Option<String> {
+    if index.len() != 6 {
+        return None;
+    }
+    let bytes = index
+        .iter()
+        .map(|&arc| u8::try_from(arc).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(mac_format.format(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mock::{cell, MockAgent};
+    use crate::helpers::OwnedValue;
+
+    #[test]
+    fn test_bridge_fdb() {
+        let learned = [0x00, 0x27, 0x22, 0xaa, 0xbb, 0xcc];
+        let own = [0x04, 0x18, 0xd6, 0x01, 0x02, 0x03];
+        let no_status = [0xf0, 0x9f, 0xc2, 0x10, 0x20, 0x30];
+        let agent = MockAgent::start(vec![
+            cell(DOT1D_TP_FDB_PORT, &learned, OwnedValue::Integer(3)),
+            cell(DOT1D_TP_FDB_PORT, &own, OwnedValue::Integer(0)),
+            cell(DOT1D_TP_FDB_PORT, &no_status, OwnedValue::Integer(7)),
+            cell(DOT1D_TP_FDB_STATUS, &learned, OwnedValue::Integer(3)),
+            cell(DOT1D_TP_FDB_STATUS, &own, OwnedValue::Integer(4)),
+        ]);
+        let mut session = agent.session();
+        assert_eq!(
+            session.bridge_fdb().unwrap(),
+            vec![
+                FdbEntry {
+                    mac: "00:27:22:aa:bb:cc".to_string(),
+                    port: 3,
+                    status: Some(FdbStatus::Learned),
+                },
+                FdbEntry {
+                    mac: "04:18:d6:01:02:03".to_string(),
+                    port: 0,
+                    status: Some(FdbStatus::SelfAddress),
+                },
+                FdbEntry {
+                    mac: "f0:9f:c2:10:20:30".to_string(),
+                    port: 7,
+                    status: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bridge_fdb_uppercase_dash() {
+        let agent = MockAgent::start(vec![
+            cell(
+                DOT1D_TP_FDB_PORT,
+                &[0x00, 0x27, 0x22, 0xaa, 0xbb, 0xcc],
+                OwnedValue::Integer(3),
+            ),
+            cell(
+                DOT1D_TP_FDB_PORT,
+                &[0xf0, 0x9f, 0xc2, 0x10, 0x20, 0x3f],
+                OwnedValue::Integer(4),
+            ),
+        ]);
+        let mut session = agent.session();
+        let upper_dash = MacFormat {
+            uppercase: true,
+            separator: '-',
+        };
+        let macs: Vec<String> = session
+            .bridge_fdb_with_mac_format(upper_dash)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.mac)
+            .collect();
+        assert_eq!(macs, ["00-27-22-AA-BB-CC", "F0-9F-C2-10-20-3F"]);
+    }
+
+    #[test]
+    fn test_fdb_status_from() {
+        assert_eq!(FdbStatus::from(1), FdbStatus::Other);
+        assert_eq!(FdbStatus::from(2), FdbStatus::Invalid);
+        assert_eq!(FdbStatus::from(5), FdbStatus::Mgmt);
+        assert_eq!(FdbStatus::from(9), FdbStatus::Unknown(9));
+    }
+
+    #[test]
+    fn test_index_to_mac() {
+        let colon = MacFormat::default();
+        assert_eq!(
+            index_to_mac(&[0, 1, 2, 3, 4, 255], colon).as_deref(),
+            Some("00:01:02:03:04:ff")
+        );
+        assert_eq!(index_to_mac(&[0, 1, 2, 3, 4], colon), None);
+        assert_eq!(index_to_mac(&[0, 1, 2, 3, 4, 256], colon), None);
+    }
+}
@@ -1,15 +1,35 @@
-use std::time::Duration;
+use std::{
+    cell::Cell,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    net::{IpAddr, UdpSocket},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::{Oid, Result, SyncSession, Error};
+use crate::{Error, Oid, Result, SyncSession, Version};
 
+use super::clock::{Clock, SystemClock};
 use super::session::SessionExt;
 use super::value::OwnedValue;
 
+/// Default soft limit on the encoded size of a request PDU, in bytes.
+///
+/// A 1500-byte Ethernet MTU minus the IPv4 and UDP headers.
+const DEFAULT_MAX_PDU_SIZE: usize = 1472;
+
+/// Port assumed by `same_target` for hosts given without one.
+const DEFAULT_PORT: u16 = 161;
+
+/// Retry delays are scaled by a random factor in `1 ± RETRY_JITTER`.
+const RETRY_JITTER: f64 = 0.25;
+
 /// SNMP client with version fallback (v2c -> v1) and configurable retries.
 ///
 /// This client simplifies SNMP operations by:
 /// - trying SNMPv2c first, falling back to SNMPv1
-/// - Retrying failed operations with exponential backoff
+/// - Retrying failed operations with jittered exponential backoff; a retry
+///   resends the same request ID (see [`SyncSession`])
 /// - Providing both typed and string-based return values
 ///
 /// # Examples
@@ -28,10 +48,19 @@ use super::value::OwnedValue;
 pub struct SnmpClient {
     host: String,
     community: Vec<u8>,
-    timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
     starting_req_id: i32,
     retries: u32,
     max_backoff_secs: u64,
+    retry_jitter: bool,
+    deadline: Option<Duration>,
+    v1_fallback: bool,
+    lenient_decoding: bool,
+    max_pdu_size: usize,
+    max_response_bytes: Option<usize>,
+    clock: Arc<dyn Clock>,
+    socket: Option<UdpSocket>,
 }
 
 impl SnmpClient {
@@ -40,22 +69,126 @@ impl SnmpClient {
         Self {
             host: host.to_string(),
             community: community.to_vec(),
-            timeout: Some(Duration::from_secs(2)),
+            read_timeout: Some(Duration::from_secs(2)),
+            write_timeout: Some(Duration::from_secs(2)),
             starting_req_id: 0,
             retries: 3,
             max_backoff_secs: 8,
+            retry_jitter: true,
+            deadline: None,
+            v1_fallback: true,
+            lenient_decoding: true,
+            max_pdu_size: DEFAULT_MAX_PDU_SIZE,
+            max_response_bytes: None,
+            clock: Arc::new(SystemClock),
+            socket: None,
+        }
+    }
+
+    /// Create a new SNMP client from a UTF-8 community, as read from config.
+    ///
+    /// Same as `new(host, community.as_bytes())`; use `new` for binary
+    /// communities.
+    ///
+    /// # Examples
+    /// ```
+    /// use snmp2::SnmpClient;
+    ///
+    /// let community = String::from("public");
+    /// let client = SnmpClient::new_str("192.168.1.1:161", &community);
+    /// ```
+    pub fn new_str(host: &str, community: &str) -> Self {
+        Self::new(host, community.as_bytes())
+    }
+
+    /// Create a client that sends every request from a caller-provided socket.
+    ///
+    /// The client takes ownership of `socket`; each session it opens works on
+    /// a duplicate handle (`try_clone()`) of the same socket, so the local
+    /// address and any socket options you set are kept, and version fallback
+    /// (v2c, then v1) still applies on that socket. Opening a session
+    /// connects the socket to `host` and sets its read/write timeouts to the
+    /// client timeouts. The socket is closed once the client and all sessions
+    /// from `connect()` are dropped.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::UdpSocket;
+    /// use snmp2::{oid, SnmpClient};
+    ///
+    /// let socket = UdpSocket::bind("10.0.0.5:0")?;
+    /// let client = SnmpClient::with_socket(socket, "192.168.1.1:161", b"public");
+    /// let descr = client.get(&oid!("1.3.6.1.2.1.1.1.0"))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_socket(socket: UdpSocket, host: &str, community: &[u8]) -> Self {
+        Self {
+            socket: Some(socket),
+            ..Self::new(host, community)
         }
     }
 
-    /// Set a custom timeout per SNMP operation.
+    /// Create a new SNMP client, rejecting suspicious community strings.
+    ///
+    /// A NUL byte or ASCII control character in a community almost always
+    /// means a misread config. Bytes above 0x7f are allowed, since some agents
+    /// use binary communities.
+    ///
+    /// # Examples
+    /// ```
+    /// use snmp2::{Error, SnmpClient};
+    ///
+    /// assert!(SnmpClient::try_new("192.168.1.1:161", b"public").is_ok());
+    /// assert_eq!(
+    ///     SnmpClient::try_new("192.168.1.1:161", b"pub\0lic").err(),
+    ///     Some(Error::InvalidCommunity)
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidCommunity` if the community contains a control character.
+    pub fn try_new(host: &str, community: &[u8]) -> Result<Self> {
+        if community.iter().any(u8::is_ascii_control) {
+            return Err(Error::InvalidCommunity);
+        }
+        Ok(Self::new(host, community))
+    }
+
+    /// Set a custom timeout per SNMP operation (default: 2 seconds).
+    ///
+    /// Sets both the read and the write timeout of the UDP socket; see
+    /// `with_read_timeout` and `with_write_timeout` to set them separately.
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
-        self.timeout = Some(timeout);
+        self.read_timeout = Some(timeout);
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long each attempt waits for a reply, leaving the write
+    /// timeout alone.
+    ///
+    /// The builder calls apply in order, so a `with_timeout` after this one
+    /// overrides it. `with_deadline` can still cut the wait shorter.
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set how long sending a request may block, leaving the read timeout
+    /// alone.
+    ///
+    /// UDP sends rarely block, so a short value mostly guards against a full
+    /// local socket buffer. Like `with_read_timeout`, a later `with_timeout`
+    /// overrides it.
+    pub fn with_write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
         self
     }
 
     /// Set the number of retry attempts (default: 3).
     ///
-    /// Each retry uses exponential backoff: sleep(min(2^attempt, max_backoff)).
+    /// Each retry uses exponential backoff: sleep(min(2^attempt, max_backoff)),
+    /// randomized by `with_retry_jitter`.
     pub fn with_retries(mut self, retries: u32) -> Self {
         self.retries = retries;
         self
@@ -67,75 +200,230 @@ impl SnmpClient {
         self
     }
 
+    /// Randomize retry delays by ±25% (default: on).
+    ///
+    /// Pollers on a fixed schedule that lose packets at the same moment, e.g.
+    /// when a shared uplink flaps, would otherwise all retry in lockstep and
+    /// hit the network and the agents in bursts. Jitter spreads the retries
+    /// out. Turn it off for reproducible timing in tests.
+    pub fn with_retry_jitter(mut self, jitter: bool) -> Self {
+        self.retry_jitter = jitter;
+        self
+    }
+
+    /// Bound the total time of each call, across retries and v1 fallback
+    /// (default: none).
+    ///
+    /// The deadline wins over the retry count: no attempt starts once it has
+    /// passed, each request (a walk sends many) waits for a reply only as
+    /// long as the deadline allows (the per-operation timeout still applies
+    /// when it is shorter), and backoff sleeps are cut short. A call that runs out of time returns
+    /// the same "nothing found" result as one that used up its retries.
+    pub fn with_deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Fall back to SNMPv1 when v2c fails (default: on).
+    ///
+    /// With the fallback off the client never puts a v1 packet on the wire:
+    /// a v2c failure is final, and `connect()` fails with `Error::Send` as
+    /// soon as the v2c session cannot be opened, without trying v1. Use this
+    /// on networks where SNMPv1 is prohibited.
+    pub fn with_fallback(mut self, fallback: bool) -> Self {
+        self.v1_fallback = fallback;
+        self
+    }
+
     /// Set a custom starting request ID.
     pub fn with_req_id(mut self, req_id: i32) -> Self {
         self.starting_req_id = req_id;
         self
     }
 
+    /// Tolerate non-minimal and indefinite BER lengths from sloppy agents
+    /// (default: lenient, matching older releases; pass `false` for strict).
+    ///
+    /// See [`AsnReader`](crate::AsnReader) for exactly which deviations are accepted.
+    pub fn with_lenient_decoding(mut self, lenient: bool) -> Self {
+        self.lenient_decoding = lenient;
+        self
+    }
+
+    /// Set a soft limit on the encoded size of request PDUs (default: 1472 bytes).
+    ///
+    /// `get_many()` packs as many OIDs per request as fit under this size and
+    /// splits the rest into further requests, which keeps agents with small
+    /// buffers from answering `tooBig`. A single OID that alone exceeds the
+    /// limit is still sent on its own. The client speaks v1/v2c, which have no
+    /// max-message-size field, so the limit only affects packing.
+    pub fn with_max_pdu_size(mut self, size: usize) -> Self {
+        self.max_pdu_size = size;
+        self
+    }
+
+    /// Cap the total size of the responses received during one call
+    /// (default: unlimited).
+    ///
+    /// A call whose responses add up to more than `max` bytes fails with
+    /// `Error::ResponseTooLarge` instead of being retried or falling back to
+    /// v1. Retries count toward the same total. Sessions from `connect()`
+    /// keep counting for their whole lifetime; see
+    /// [`SyncSession::with_max_response_bytes`].
+    pub fn with_max_response_bytes(mut self, max: usize) -> Self {
+        self.max_response_bytes = Some(max);
+        self
+    }
+
+    /// Use `clock` for deadlines and backoff sleeps (default: [`SystemClock`]).
+    ///
+    /// Socket timeouts are still real time; only the deadline bookkeeping and
+    /// the sleeps between retries go through the clock.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Calculate backoff sleep duration for a given attempt number.
     fn backoff_duration(&self, attempt: u32) -> Duration {
         let secs = (1u64 << attempt).min(self.max_backoff_secs);
         Duration::from_secs(secs)
     }
 
+    /// Backoff for `attempt` with jitter applied if enabled.
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        let delay = self.backoff_duration(attempt);
+        if self.retry_jitter {
+            delay.mul_f64(1.0 - RETRY_JITTER + 2.0 * RETRY_JITTER * random_unit())
+        } else {
+            delay
+        }
+    }
+
+    /// When the call started now must be finished, if a deadline is set.
+    fn call_deadline(&self) -> Option<Instant> {
+        self.deadline.map(|deadline| self.clock.now() + deadline)
+    }
+
+    /// Check the deadline before an attempt and hand the time left to the
+    /// session, which re-checks it before every request the attempt sends.
+    /// Returns false if no time is left.
+    fn begin_attempt(&self, session: &mut SyncSession, deadline: Option<Instant>) -> bool {
+        let Some(deadline) = deadline else {
+            return true;
+        };
+        let remaining = deadline.saturating_duration_since(self.clock.now());
+        if remaining.is_zero() {
+            return false;
+        }
+        session.set_deadline(Some(Instant::now() + remaining));
+        true
+    }
+
+    /// Sleep before retrying, no longer than the deadline allows.
+    fn sleep_before_retry(&self, attempt: u32, deadline: Option<Instant>) {
+        let mut delay = self.retry_delay(attempt);
+        if let Some(deadline) = deadline {
+            delay = delay.min(deadline.saturating_duration_since(self.clock.now()));
+        }
+        self.clock.sleep(delay);
+    }
+
     /// Try to establish a v2c session, falling back to v1.
     fn connect_v2c(&self) -> std::result::Result<SyncSession, std::io::Error> {
-        SyncSession::new_v2c(
-            &self.host,
-            &self.community,
-            self.timeout,
-            self.starting_req_id,
-        )
+        self.open_session(Version::V2C)
     }
 
     fn connect_v1(&self) -> std::result::Result<SyncSession, std::io::Error> {
-        SyncSession::new_v1(
-            &self.host,
-            &self.community,
-            self.timeout,
-            self.starting_req_id,
-        )
+        if !self.v1_fallback {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "SNMPv1 fallback disabled",
+            ));
+        }
+        self.open_session(Version::V1)
     }
 
-    /// Establish a session, trying v2c first, falling back to v1.
+    fn open_session(&self, version: Version) -> std::result::Result<SyncSession, std::io::Error> {
+        let session = match (&self.socket, version) {
+            (Some(socket), _) => SyncSession::from_socket(
+                socket.try_clone()?,
+                version,
+                &self.host,
+                &self.community,
+                self.read_timeout,
+                self.starting_req_id,
+            ),
+            (None, Version::V1) => SyncSession::new_v1(
+                &self.host,
+                &self.community,
+                self.read_timeout,
+                self.starting_req_id,
+            ),
+            (None, _) => SyncSession::new_v2c(
+                &self.host,
+                &self.community,
+                self.read_timeout,
+                self.starting_req_id,
+            ),
+        }?;
+        session.socket().set_write_timeout(self.write_timeout)?;
+        let session = session.with_lenient_decoding(self.lenient_decoding);
+        Ok(match self.max_response_bytes {
+            Some(max) => session.with_max_response_bytes(max),
+            None => session,
+        })
+    }
+
+    /// Establish a session, trying v2c first, falling back to v1 unless
+    /// disabled with `with_fallback(false)`.
     ///
     /// If you need to perform multiple operations on the same device,
     /// use this to get a session and call methods on it directly.
     pub fn connect(&self) -> Result<SyncSession> {
         match self.connect_v2c() {
             Ok(session) => Ok(session),
+            Err(_) if !self.v1_fallback => Err(Error::Send),
             Err(_) => self.connect_v1().map_err(|_| Error::Send),
         }
     }
 
     /// Get a single OID value as a string, with retries and version fallback.
     ///
-    /// retries with backoff, then tries v1 if v2c fails.
+    /// retries with backoff, then tries v1 if v2c fails. An empty OctetString
+    /// is returned as `""` right away; only errors, including responses
+    /// without varbinds, are retried. Returns `""` when all attempts fail.
     pub fn get(&self, oid: &Oid) -> Result<String> {
+        let deadline = self.call_deadline();
         // Try v2c with retries
         if let Ok(mut session) = self.connect_v2c() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.get_string(oid) {
-                    Ok(val) if !val.is_empty() => return Ok(val),
-                    Ok(_) => {}
+                    Ok(val) => return Ok(val),
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
         // Fall back to v1 with retries
         if let Ok(mut session) = self.connect_v1() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.get_string(oid) {
-                    Ok(val) if !val.is_empty() => return Ok(val),
-                    Ok(_) => {}
+                    Ok(val) => return Ok(val),
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
@@ -144,60 +432,130 @@ impl SnmpClient {
 
     /// Get a single OID value preserving type information, with retries.
     pub fn get_value(&self, oid: &Oid) -> Result<OwnedValue> {
+        let deadline = self.call_deadline();
         if let Ok(mut session) = self.connect_v2c() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.get_value(oid) {
                     Ok(val) if !val.is_error() && val != OwnedValue::Null => return Ok(val),
                     Ok(_) => {}
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
         if let Ok(mut session) = self.connect_v1() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.get_value(oid) {
                     Ok(val) if !val.is_error() && val != OwnedValue::Null => return Ok(val),
                     Ok(_) => {}
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
         Ok(OwnedValue::Null)
     }
 
+    /// Get several OIDs, packing as many per request as the max PDU size allows.
+    ///
+    /// Returns the varbinds of all responses in request order. Each request is
+    /// retried with backoff; a request that still fails aborts the whole call.
+    /// A response without varbinds counts as failed (`Error::EmptyResponse`).
+    /// Running out of time set by `with_deadline` fails with `Error::Receive`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SnmpClient};
+    ///
+    /// let client = SnmpClient::new("192.168.1.1:161", b"public").with_max_pdu_size(484);
+    /// let descr = oid!("1.3.6.1.2.1.1.1.0");
+    /// let name = oid!("1.3.6.1.2.1.1.5.0");
+    /// for (oid, value) in client.get_many(&[&descr, &name])? {
+    ///     println!("{} = {}", oid, value.to_string_lossy());
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    pub fn get_many(&self, oids: &[&Oid]) -> Result<Vec<(Oid<'static>, OwnedValue)>> {
+        let deadline = self.call_deadline();
+        let mut session = self.connect()?;
+        let mut results = Vec::with_capacity(oids.len());
+        for chunk in pack_oids(oids, self.community.len(), self.max_pdu_size) {
+            let mut attempt = 0;
+            let varbinds = loop {
+                if !self.begin_attempt(&mut session, deadline) {
+                    return Err(Error::Receive);
+                }
+                let result = session.get_multi(chunk).and_then(|response| {
+                    let varbinds = response.into_owned_varbinds();
+                    if varbinds.is_empty() {
+                        Err(Error::EmptyResponse)
+                    } else {
+                        Ok(varbinds)
+                    }
+                });
+                match result {
+                    Ok(varbinds) => break varbinds,
+                    Err(e @ Error::ResponseTooLarge) => return Err(e),
+                    Err(e) if attempt + 1 >= self.retries => return Err(e),
+                    Err(_) => {
+                        self.sleep_before_retry(attempt, deadline);
+                        attempt += 1;
+                    }
+                }
+            };
+            results.extend(varbinds.into_iter().map(|vb| (vb.oid, vb.value)));
+        }
+        Ok(results)
+    }
+
     /// Walk an OID tree returning string values, with retries and version fallback.
     ///
     /// Mirrors the behavior of Python `snmpwalkNext()`.
     pub fn walk(&self, oid: &Oid) -> Result<Vec<String>> {
+        let deadline = self.call_deadline();
         // Try v2c with retries
         if let Ok(mut session) = self.connect_v2c() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.walk_strings(oid) {
                     Ok(results) if !results.is_empty() => return Ok(results),
                     Ok(_) => {}
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
         // Fall back to v1 with retries
         if let Ok(mut session) = self.connect_v1() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.walk_strings(oid) {
                     Ok(results) if !results.is_empty() => return Ok(results),
                     Ok(_) => {}
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
@@ -209,27 +567,36 @@ impl SnmpClient {
     /// This is the preferred method for walking tables that contain binary data
     /// like MAC addresses.
     pub fn walk_bytes(&self, oid: &Oid) -> Result<Vec<Vec<u8>>> {
+        let deadline = self.call_deadline();
         if let Ok(mut session) = self.connect_v2c() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.walk_bytes(oid) {
                     Ok(results) if !results.is_empty() => return Ok(results),
                     Ok(_) => {}
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
         if let Ok(mut session) = self.connect_v1() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.walk_bytes(oid) {
                     Ok(results) if !results.is_empty() => return Ok(results),
                     Ok(_) => {}
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
@@ -238,27 +605,36 @@ impl SnmpClient {
 
     /// Walk an OID tree returning typed OwnedValues, with retries and version fallback.
     pub fn walk_values(&self, oid: &Oid) -> Result<Vec<(Oid<'static>, OwnedValue)>> {
+        let deadline = self.call_deadline();
         if let Ok(mut session) = self.connect_v2c() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.walk_values(oid) {
                     Ok(results) if !results.is_empty() => return Ok(results),
                     Ok(_) => {}
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
         if let Ok(mut session) = self.connect_v1() {
             for attempt in 0..self.retries {
+                if !self.begin_attempt(&mut session, deadline) {
+                    break;
+                }
                 match session.walk_values(oid) {
                     Ok(results) if !results.is_empty() => return Ok(results),
                     Ok(_) => {}
+                    Err(Error::ResponseTooLarge) => return Err(Error::ResponseTooLarge),
                     Err(_) => {}
                 }
                 if attempt < self.retries - 1 {
-                    std::thread::sleep(self.backoff_duration(attempt));
+                    self.sleep_before_retry(attempt, deadline);
                 }
             }
         }
@@ -266,24 +642,294 @@ impl SnmpClient {
     }
 }
 
+/// Community string that selects `context` on a proxy, using the common
+/// `community@context` convention.
+///
+/// Proxies and agents that map communities to contexts (Cisco's
+/// community string indexing, net-snmp proxies, ...) split the string at the
+/// `@` and serve `context`, e.g. the bridge MIB of one VLAN. Agents without
+/// such a mapping see a different, most likely unknown, community; point
+/// them at the plain community instead. An empty `context` leaves
+/// `base_community` unchanged, so one config can drive both kinds of device.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{community_for_context, SnmpClient};
+///
+/// assert_eq!(community_for_context("public", "vlan10"), "public@vlan10");
+/// assert_eq!(community_for_context("public", ""), "public");
+///
+/// let client = SnmpClient::new_str("192.168.1.1:161", &community_for_context("public", "10"));
+/// ```
+pub fn community_for_context(base_community: &str, context: &str) -> String {
+    if context.is_empty() {
+        base_community.to_string()
+    } else {
+        format!("{}@{}", base_community, context)
+    }
+}
+
+/// Whether two clients poll the same agent with the same community, so a
+/// scheduler can drop one of them.
+///
+/// Hosts are compared after normalizing the forms a fleet config tends to
+/// mix: a missing port means 161, hostnames are compared without case or
+/// trailing dot, and IP addresses by value (`[::1]:161` matches `::1`).
+/// Names are not resolved, so a hostname never matches its address.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{same_target, SnmpClient};
+///
+/// let a = SnmpClient::new("core-sw1.example.net.", b"public");
+/// let b = SnmpClient::new("Core-SW1.example.net:161", b"public");
+/// assert!(same_target(&a, &b));
+/// assert!(!same_target(&a, &SnmpClient::new("core-sw1.example.net:1161", b"public")));
+/// ```
+pub fn same_target(a: &SnmpClient, b: &SnmpClient) -> bool {
+    a.community == b.community && target_key(&a.host) == target_key(&b.host)
+}
+
+/// `host` split into a normalized host and a port, for `same_target`.
+fn target_key(host: &str) -> (String, u16) {
+    let host = host.trim();
+    let (name, port) = if let Some(rest) = host.strip_prefix('[') {
+        // [IPv6]:port
+        match rest.split_once(']') {
+            Some((name, "")) => (name, DEFAULT_PORT),
+            Some((name, port)) => match port.strip_prefix(':').map(str::parse) {
+                Some(Ok(port)) => (name, port),
+                _ => (host, DEFAULT_PORT),
+            },
+            None => (host, DEFAULT_PORT),
+        }
+    } else if host.parse::<IpAddr>().is_ok() {
+        // a bare IPv6 address has colons but no port
+        (host, DEFAULT_PORT)
+    } else {
+        match host.rsplit_once(':') {
+            Some((name, port)) => port
+                .parse()
+                .map_or((host, DEFAULT_PORT), |port| (name, port)),
+            None => (host, DEFAULT_PORT),
+        }
+    };
+    let name = match name.parse::<IpAddr>() {
+        Ok(ip) => ip.to_string(),
+        Err(_) => name.trim_end_matches('.').to_ascii_lowercase(),
+    };
+    (name, port)
+}
+
+/// Number of polling workers that keeps a fleet poll within a request budget
+/// of `pps_budget` packets per second.
+///
+/// Each worker polls one target at a time and waits for every response
+/// before sending the next request, so it sends about one request per
+/// round trip. `N` workers therefore send `N / avg_rtt` requests per second,
+/// and the budget allows
+///
+/// ```text
+/// workers = floor(pps_budget * avg_rtt_seconds)
+/// ```
+///
+/// clamped to at least 1 and at most `targets`, since extra workers would
+/// have nothing to poll. `per_target_requests` makes each target take longer
+/// but does not change the rate, so it only matters when it is 0: with no
+/// targets or no requests there is no work and the result is 0.
+///
+/// Retransmits after timeouts count against the budget too; leave some
+/// headroom on lossy networks. A budget below what one worker sends
+/// (`pps_budget < 1 / avg_rtt`) cannot be met by sizing the pool alone; the
+/// result is still 1 and the worker has to pace itself.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use snmp2::helpers::recommended_concurrency;
+///
+/// // 500 packets/s at 40 ms round trips: 20 workers
+/// assert_eq!(recommended_concurrency(5000, 12, 500, Duration::from_millis(40)), 20);
+/// // never more workers than targets
+/// assert_eq!(recommended_concurrency(8, 12, 500, Duration::from_millis(40)), 8);
+/// ```
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn recommended_concurrency(
+    targets: usize,
+    per_target_requests: usize,
+    pps_budget: u32,
+    avg_rtt: Duration,
+) -> usize {
+    if targets == 0 || per_target_requests == 0 {
+        return 0;
+    }
+    let workers = (f64::from(pps_budget) * avg_rtt.as_secs_f64()).floor();
+    // the float-to-int cast saturates, so huge budgets just hit `targets`
+    (workers as usize).clamp(1, targets)
+}
+
+thread_local! {
+    static RNG_STATE: Cell<u64> = Cell::new(RandomState::new().build_hasher().finish() | 1);
+}
+
+/// Uniform sample from `[0, 1)`, from a per-thread xorshift64* generator
+/// seeded by std's hash randomization. Good enough for jitter, not for
+/// anything security related.
+fn random_unit() -> f64 {
+    RNG_STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        // top 53 bits fill the f64 mantissa
+        (x.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
+
+/// Bytes taken by a BER TLV with `content_len` bytes of content.
+fn tlv_len(content_len: usize) -> usize {
+    let length_octets = if content_len < 128 {
+        1
+    } else {
+        1 + (usize::BITS - content_len.leading_zeros() + 7) as usize / 8
+    };
+    1 + length_octets + content_len
+}
+
+/// Encoded size of a v1/v2c request carrying `varbinds_len` bytes of varbinds.
+fn request_len(community_len: usize, varbinds_len: usize) -> usize {
+    // request-id (at most 4 bytes), error-status, error-index
+    let pdu = tlv_len(4) + tlv_len(1) + tlv_len(1) + tlv_len(varbinds_len);
+    // version, community, PDU
+    tlv_len(tlv_len(1) + tlv_len(community_len) + tlv_len(pdu))
+}
+
+/// Split `oids` into runs whose GET request stays within `max_pdu_size`.
+fn pack_oids<'a, 'b, 'c>(
+    oids: &'a [&'b Oid<'c>],
+    community_len: usize,
+    max_pdu_size: usize,
+) -> Vec<&'a [&'b Oid<'c>]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut varbinds_len = 0;
+    for (i, oid) in oids.iter().enumerate() {
+        // OID TLV plus a NULL value, wrapped in a SEQUENCE
+        let varbind_len = tlv_len(tlv_len(oid.as_bytes().len()) + 2);
+        if i > start && request_len(community_len, varbinds_len + varbind_len) > max_pdu_size {
+            chunks.push(&oids[start..i]);
+            start = i;
+            varbinds_len = 0;
+        }
+        varbinds_len += varbind_len;
+    }
+    if start < oids.len() {
+        chunks.push(&oids[start..]);
+    }
+    chunks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::clock::FakeClock;
+    use crate::helpers::mock::{if_in_octets, MockAgent, MockOptions};
+    use crate::helpers::oid::oid_from_arcs;
+
+    #[test]
+    fn test_with_socket_uses_provided_socket() {
+        let agent = MockAgent::start(vec![(
+            crate::oid!("1.3.6.1.2.1.1.5.0"),
+            OwnedValue::OctetString(b"ap-01".to_vec()),
+        )]);
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let local = socket.local_addr().unwrap();
+        let client = SnmpClient::with_socket(socket, &agent.addr().to_string(), b"public")
+            .with_timeout(Duration::from_secs(1));
+        assert_eq!(client.retries, 3);
+
+        let session = client.connect().unwrap();
+        assert_eq!(session.socket().local_addr().unwrap(), local);
+        assert_eq!(session.socket().peer_addr().unwrap(), agent.addr());
+        drop(session);
+
+        assert_eq!(
+            client.get(&crate::oid!("1.3.6.1.2.1.1.5.0")).unwrap(),
+            "ap-01"
+        );
+    }
 
     #[test]
     fn test_client_creation() {
         let client = SnmpClient::new("192.168.1.1:161", b"public");
         assert_eq!(client.host, "192.168.1.1:161");
         assert_eq!(client.community, b"public");
-        assert_eq!(client.timeout, Some(Duration::from_secs(2)));
+        assert_eq!(client.read_timeout, Some(Duration::from_secs(2)));
+        assert_eq!(client.write_timeout, Some(Duration::from_secs(2)));
+        assert_eq!(client.retries, 3);
+    }
+
+    #[test]
+    fn test_client_new_str() {
+        let client = SnmpClient::new_str("192.168.1.1:161", "pública");
+        assert_eq!(client.host, "192.168.1.1:161");
+        assert_eq!(client.community, "pública".as_bytes());
         assert_eq!(client.retries, 3);
     }
 
+    #[test]
+    fn test_client_try_new() {
+        assert!(SnmpClient::try_new("192.168.1.1:161", b"public").is_ok());
+        assert!(SnmpClient::try_new("192.168.1.1:161", &[0xde, 0xad, 0xbe, 0xef]).is_ok());
+        assert_eq!(
+            SnmpClient::try_new("192.168.1.1:161", b"pub\0lic").err(),
+            Some(Error::InvalidCommunity)
+        );
+        assert_eq!(
+            SnmpClient::try_new("192.168.1.1:161", b"public\n").err(),
+            Some(Error::InvalidCommunity)
+        );
+    }
+
     #[test]
     fn test_client_with_timeout() {
+        let client =
+            SnmpClient::new("192.168.1.1:161", b"public").with_timeout(Duration::from_secs(5));
+        assert_eq!(client.read_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(client.write_timeout, Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_client_independent_timeouts() {
         let client = SnmpClient::new("192.168.1.1:161", b"public")
-            .with_timeout(Duration::from_secs(5));
-        assert_eq!(client.timeout, Some(Duration::from_secs(5)));
+            .with_read_timeout(Duration::from_secs(10))
+            .with_write_timeout(Duration::from_millis(100));
+        assert_eq!(client.read_timeout, Some(Duration::from_secs(10)));
+        assert_eq!(client.write_timeout, Some(Duration::from_millis(100)));
+
+        // a later with_timeout overrides both
+        let client = client.with_timeout(Duration::from_secs(3));
+        assert_eq!(client.read_timeout, Some(Duration::from_secs(3)));
+        assert_eq!(client.write_timeout, Some(Duration::from_secs(3)));
+        let client = client.with_write_timeout(Duration::from_secs(1));
+        assert_eq!(client.read_timeout, Some(Duration::from_secs(3)));
+        assert_eq!(client.write_timeout, Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_connect_applies_independent_timeouts() {
+        let agent = MockAgent::start(Vec::new());
+        let client = SnmpClient::new(&agent.addr().to_string(), b"public")
+            .with_read_timeout(Duration::from_secs(7))
+            .with_write_timeout(Duration::from_millis(300));
+        let session = client.connect().unwrap();
+        let socket = session.socket();
+        assert_eq!(socket.read_timeout().unwrap(), Some(Duration::from_secs(7)));
+        assert_eq!(
+            socket.write_timeout().unwrap(),
+            Some(Duration::from_millis(300))
+        );
     }
 
     #[test]
@@ -308,6 +954,389 @@ mod tests {
         assert_eq!(client.backoff_duration(4), Duration::from_secs(8)); // capped
     }
 
+    #[test]
+    fn test_retry_delay_jitter() {
+        let client = SnmpClient::new("192.168.1.1:161", b"public");
+        assert!(client.retry_jitter);
+        let delays: Vec<Duration> = (0..200).map(|_| client.retry_delay(2)).collect();
+        for delay in &delays {
+            assert!(*delay >= Duration::from_secs(3), "{:?}", delay);
+            assert!(*delay <= Duration::from_secs(5), "{:?}", delay);
+        }
+        assert!(delays.iter().any(|delay| *delay != delays[0]));
+
+        let client = client.with_retry_jitter(false);
+        assert_eq!(client.retry_delay(2), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn test_client_lenient_decoding() {
+        let client = SnmpClient::new("192.168.1.1:161", b"public");
+        assert!(client.lenient_decoding);
+        let client = client.with_lenient_decoding(false);
+        assert!(!client.lenient_decoding);
+    }
+
+    #[test]
+    fn test_pack_oids_splits_under_small_size() {
+        let oids = if_in_octets(10);
+        let refs: Vec<&Oid> = oids.iter().collect();
+
+        let chunks = pack_oids(&refs, 6, 100);
+        assert!(chunks.len() > 1);
+        assert_eq!(chunks.concat(), refs);
+        for chunk in &chunks {
+            let varbinds_len: usize = chunk
+                .iter()
+                .map(|oid| tlv_len(tlv_len(oid.as_bytes().len()) + 2))
+                .sum();
+            assert!(request_len(6, varbinds_len) <= 100);
+        }
+
+        assert_eq!(pack_oids(&refs, 6, DEFAULT_MAX_PDU_SIZE).len(), 1);
+        assert_eq!(pack_oids(&refs, 6, 1).len(), 10);
+        assert!(pack_oids(&[], 6, 100).is_empty());
+    }
+
+    #[test]
+    fn test_request_len_matches_encoder() {
+        let oids = if_in_octets(3);
+        let refs: Vec<&Oid> = oids.iter().collect();
+        let mut buf = crate::pdu::Buf::default();
+        crate::pdu::build_get_multi(
+            crate::Version::V2C,
+            b"public",
+            i32::MAX,
+            &refs,
+            &mut buf,
+            #[cfg(feature = "v3")]
+            None,
+        )
+        .unwrap();
+        let varbinds_len: usize = refs
+            .iter()
+            .map(|oid| tlv_len(tlv_len(oid.as_bytes().len()) + 2))
+            .sum();
+        assert_eq!(request_len(6, varbinds_len), buf.len());
+    }
+
+    #[test]
+    fn test_get_many_splits_requests() {
+        let oids = if_in_octets(10);
+        let table = oids
+            .iter()
+            .zip(1..)
+            .map(|(oid, i)| (oid.clone(), OwnedValue::Counter32(i * 100)))
+            .collect();
+        let agent = MockAgent::start(table);
+        let client = SnmpClient::new(&agent.addr().to_string(), b"public").with_max_pdu_size(100);
+        let refs: Vec<&Oid> = oids.iter().collect();
+
+        let results = client.get_many(&refs).unwrap();
+        assert_eq!(results.len(), 10);
+        assert_eq!(results[0], (oids[0].clone(), OwnedValue::Counter32(100)));
+        assert_eq!(results[9], (oids[9].clone(), OwnedValue::Counter32(1000)));
+        assert_eq!(agent.requests(), pack_oids(&refs, 6, 100).len());
+        assert!(agent.requests() > 1);
+    }
+
+    #[test]
+    fn test_get_empty_octet_string_not_retried() {
+        let agent = MockAgent::start(vec![(
+            crate::oid!("1.3.6.1.2.1.1.6.0"),
+            OwnedValue::OctetString(Vec::new()),
+        )]);
+        let client = SnmpClient::new(&agent.addr().to_string(), b"public");
+        assert_eq!(client.get(&crate::oid!("1.3.6.1.2.1.1.6.0")).unwrap(), "");
+        assert_eq!(agent.requests(), 1);
+    }
+
+    #[test]
+    fn test_get_many_empty_response() {
+        let agent = MockAgent::start_with(
+            Vec::new(),
+            MockOptions {
+                get_reply: Some(Vec::new()),
+                ..MockOptions::default()
+            },
+        );
+        let client = SnmpClient::new(&agent.addr().to_string(), b"public").with_retries(1);
+        let oid = crate::oid!("1.3.6.1.2.1.1.6.0");
+        assert_eq!(client.get_many(&[&oid]).unwrap_err(), Error::EmptyResponse);
+    }
+
+    #[test]
+    fn test_deadline_cuts_retries_short() {
+        let agent = MockAgent::start(Vec::new());
+        // the mock ignores unknown communities, so every attempt times out
+        let client = SnmpClient::new(&agent.addr().to_string(), b"private")
+            .with_timeout(Duration::from_millis(200))
+            .with_retry_jitter(false)
+            .with_deadline(Duration::from_millis(300));
+
+        let start = Instant::now();
+        let value = client.get_value(&crate::oid!("1.3.6.1.2.1.1.5.0")).unwrap();
+        let elapsed = start.elapsed();
+        assert_eq!(value, OwnedValue::Null);
+        // 200 ms attempt, backoff cut from 1 s to 100 ms, then no time left
+        assert!(elapsed < Duration::from_millis(600), "{:?}", elapsed);
+        assert!(agent.requests() <= 2, "{}", agent.requests());
+    }
+
+    #[test]
+    fn test_deadline_caps_attempt_timeout() {
+        let agent = MockAgent::start(Vec::new());
+        let client = SnmpClient::new(&agent.addr().to_string(), b"private")
+            .with_timeout(Duration::from_secs(5))
+            .with_deadline(Duration::from_millis(200));
+
+        let start = Instant::now();
+        let oid = crate::oid!("1.3.6.1.2.1.1.5.0");
+        assert_eq!(client.get_many(&[&oid]).unwrap_err(), Error::Receive);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_deadline_stops_walk_between_requests() {
+        let column = [1, 3, 6, 1, 2, 1, 2, 2, 1, 2];
+        let table = (1..=10)
+            .map(|i| {
+                let oid = oid_from_arcs(&[&column[..], &[i]].concat()).unwrap();
+                (oid, OwnedValue::Integer(0))
+            })
+            .collect();
+        let agent = MockAgent::start_with(
+            table,
+            MockOptions {
+                response_delay: Duration::from_millis(60),
+                ..MockOptions::default()
+            },
+        );
+        let client = SnmpClient::new(&agent.addr().to_string(), b"public")
+            .with_timeout(Duration::from_secs(1))
+            .with_retries(1)
+            .with_fallback(false)
+            .with_deadline(Duration::from_millis(200));
+
+        let start = Instant::now();
+        let rows = client.walk_values(&oid_from_arcs(&column).unwrap());
+        let elapsed = start.elapsed();
+        // ten GETNEXTs at 60 ms each would take 600 ms
+        assert!(rows.unwrap().is_empty());
+        assert!(elapsed < Duration::from_millis(400), "{:?}", elapsed);
+        assert!(agent.requests() <= 5, "{}", agent.requests());
+    }
+
+    #[test]
+    fn test_retransmits_reuse_req_id() {
+        let agent = MockAgent::start_with(
+            vec![(
+                crate::oid!("1.3.6.1.2.1.1.5.0"),
+                OwnedValue::OctetString(b"ap".to_vec()),
+            )],
+            MockOptions {
+                drop_first: 2,
+                ..MockOptions::default()
+            },
+        );
+        let client = SnmpClient::new(&agent.addr().to_string(), b"public")
+            .with_timeout(Duration::from_millis(50))
+            .with_retries(3)
+            .with_retry_jitter(false)
+            .with_max_backoff(0)
+            .with_req_id(40);
+        let oid = crate::oid!("1.3.6.1.2.1.1.5.0");
+        assert_eq!(client.get(&oid).unwrap(), "ap");
+        assert_eq!(agent.req_ids(), [40, 40, 40]);
+
+        // a new logical request on the same session gets a new ID
+        let mut session = client.connect().unwrap();
+        session.get(&oid).unwrap();
+        session.get(&oid).unwrap();
+        assert_eq!(agent.req_ids()[3..], [40, 41]);
+    }
+
+    #[test]
+    fn test_max_response_bytes_not_retried() {
+        let agent = MockAgent::start(vec![(
+            crate::oid!("1.3.6.1.2.1.1.1.0"),
+            OwnedValue::OctetString(vec![b'x'; 1000]),
+        )]);
+        let client = SnmpClient::new(&agent.addr().to_string(), b"public")
+            .with_max_response_bytes(500)
+            .with_retries(3);
+        let oid = crate::oid!("1.3.6.1.2.1.1.1.0");
+        assert_eq!(client.get_value(&oid).unwrap_err(), Error::ResponseTooLarge);
+        assert_eq!(
+            client.get_many(&[&oid]).unwrap_err(),
+            Error::ResponseTooLarge
+        );
+        assert_eq!(agent.requests(), 2);
+
+        let client = client.with_max_response_bytes(2000);
+        assert_eq!(
+            client.get_value(&oid).unwrap().as_bytes().unwrap().len(),
+            1000
+        );
+    }
+
+    #[test]
+    fn test_backoff_sleeps_use_clock() {
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let clock = Arc::new(FakeClock::new());
+        let client = SnmpClient::new(&listener.local_addr().unwrap().to_string(), b"public")
+            .with_timeout(Duration::from_millis(20))
+            .with_retries(3)
+            .with_retry_jitter(false)
+            .with_fallback(false)
+            .with_clock(clock.clone());
+        assert_eq!(client.get(&crate::oid!("1.3.6.1.2.1.1.5.0")).unwrap(), "");
+        assert_eq!(
+            clock.sleeps(),
+            [Duration::from_secs(1), Duration::from_secs(2)]
+        );
+    }
+
+    #[test]
+    fn test_deadline_uses_clock() {
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let clock = Arc::new(FakeClock::new());
+        let client = SnmpClient::new(&listener.local_addr().unwrap().to_string(), b"public")
+            .with_timeout(Duration::from_millis(20))
+            .with_retries(5)
+            .with_retry_jitter(false)
+            .with_deadline(Duration::from_millis(1500))
+            .with_clock(clock.clone());
+        assert_eq!(client.get(&crate::oid!("1.3.6.1.2.1.1.5.0")).unwrap(), "");
+        // 1 s backoff, then the second one is cut to the 500 ms left
+        assert_eq!(
+            clock.sleeps(),
+            [Duration::from_secs(1), Duration::from_millis(500)]
+        );
+        // no v1 attempts either, the deadline has passed
+        assert_eq!(received_versions(&listener), [Version::V2C; 2]);
+    }
+
+    /// Versions of all requests that reached `listener`, once the client is done.
+    fn received_versions(listener: &UdpSocket) -> Vec<Version> {
+        listener.set_nonblocking(true).unwrap();
+        let mut buf = [0u8; 1500];
+        let mut versions = Vec::new();
+        while let Ok(len) = listener.recv(&mut buf) {
+            versions.push(
+                crate::Pdu::from_bytes(&buf[..len])
+                    .unwrap()
+                    .version()
+                    .unwrap(),
+            );
+        }
+        versions
+    }
+
+    #[test]
+    fn test_fallback_sends_v1_after_v2c_fails() {
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = SnmpClient::new(&listener.local_addr().unwrap().to_string(), b"public")
+            .with_timeout(Duration::from_millis(50))
+            .with_retries(1);
+        assert_eq!(client.get(&crate::oid!("1.3.6.1.2.1.1.5.0")).unwrap(), "");
+        assert_eq!(received_versions(&listener), [Version::V2C, Version::V1]);
+    }
+
+    #[test]
+    fn test_fallback_disabled_never_sends_v1() {
+        let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let client = SnmpClient::new(&listener.local_addr().unwrap().to_string(), b"public")
+            .with_timeout(Duration::from_millis(50))
+            .with_retries(2)
+            .with_retry_jitter(false)
+            .with_max_backoff(0)
+            .with_fallback(false);
+        assert!(!client.v1_fallback);
+        assert_eq!(client.get(&crate::oid!("1.3.6.1.2.1.1.5.0")).unwrap(), "");
+        assert_eq!(received_versions(&listener), [Version::V2C, Version::V2C]);
+    }
+
+    #[test]
+    fn test_fallback_disabled_connect_error() {
+        // no port, so the v2c session cannot even be opened
+        let client = SnmpClient::new("192.168.1.1", b"public").with_fallback(false);
+        assert_eq!(client.connect().unwrap_err(), Error::Send);
+    }
+
+    #[test]
+    fn test_community_for_context() {
+        assert_eq!(community_for_context("public", "vlan10"), "public@vlan10");
+        assert_eq!(community_for_context("public", "10"), "public@10");
+        assert_eq!(community_for_context("public", ""), "public");
+        assert_eq!(community_for_context("", "ctx"), "@ctx");
+    }
+
+    #[test]
+    fn test_same_target() {
+        let client = |host: &str| SnmpClient::new(host, b"public");
+        assert!(same_target(&client("10.0.0.1"), &client("10.0.0.1:161")));
+        assert!(same_target(
+            &client("core-sw1.example.net."),
+            &client("CORE-sw1.example.net:161")
+        ));
+        assert!(same_target(&client("[::1]:161"), &client("0:0::1")));
+        assert!(same_target(
+            &client("[2001:db8::1]"),
+            &client("2001:db8::1")
+        ));
+
+        assert!(!same_target(&client("10.0.0.1"), &client("10.0.0.1:1161")));
+        assert!(!same_target(&client("10.0.0.1"), &client("10.0.0.2")));
+        assert!(!same_target(&client("[::1]:1161"), &client("::1")));
+        assert!(!same_target(
+            &client("10.0.0.1"),
+            &SnmpClient::new("10.0.0.1", b"private")
+        ));
+    }
+
+    #[test]
+    fn test_recommended_concurrency() {
+        let rtt = Duration::from_millis(40);
+        assert_eq!(recommended_concurrency(5000, 12, 500, rtt), 20);
+        assert_eq!(
+            recommended_concurrency(5000, 1, 1000, Duration::from_millis(250)),
+            250
+        );
+        // 500 pps * 35 ms = 17.5, rounded down to stay under budget
+        assert_eq!(
+            recommended_concurrency(5000, 3, 500, Duration::from_millis(35)),
+            17
+        );
+        assert_eq!(recommended_concurrency(8, 12, 500, rtt), 8);
+        assert_eq!(
+            recommended_concurrency(5000, 12, u32::MAX, Duration::from_secs(60)),
+            5000
+        );
+    }
+
+    #[test]
+    fn test_recommended_concurrency_edges() {
+        // a budget below one worker's rate still needs one worker
+        assert_eq!(
+            recommended_concurrency(100, 4, 10, Duration::from_millis(20)),
+            1
+        );
+        assert_eq!(
+            recommended_concurrency(100, 4, 0, Duration::from_millis(20)),
+            1
+        );
+        assert_eq!(recommended_concurrency(100, 4, 500, Duration::ZERO), 1);
+        assert_eq!(
+            recommended_concurrency(0, 4, 500, Duration::from_millis(20)),
+            0
+        );
+        assert_eq!(
+            recommended_concurrency(100, 0, 500, Duration::from_millis(20)),
+            0
+        );
+    }
+
     #[test]
     fn test_client_builder_chain() {
         let client = SnmpClient::new("192.168.1.1:161", b"public")
@@ -316,7 +1345,7 @@ mod tests {
             .with_max_backoff(16)
             .with_req_id(999);
 
-        assert_eq!(client.timeout, Some(Duration::from_secs(10)));
+        assert_eq!(client.read_timeout, Some(Duration::from_secs(10)));
         assert_eq!(client.retries, 5);
         assert_eq!(client.max_backoff_secs, 16);
         assert_eq!(client.starting_req_id, 999);
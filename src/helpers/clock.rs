@@ -0,0 +1,92 @@
+//! Time source for the timing-dependent helpers.
+
+use std::time::{Duration, Instant};
+
+/// Source of the current time and of blocking waits.
+///
+/// Everything that measures elapsed time or sleeps between retries goes
+/// through a clock, so tests can substitute one that advances only when told
+/// to and make timing logic deterministic.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The real clock: `Instant::now()` and `thread::sleep`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Clock for tests: time stands still except when `sleep` or `advance` move
+/// it, and every sleep is recorded instead of blocking.
+#[cfg(test)]
+pub(crate) struct FakeClock {
+    start: Instant,
+    elapsed: std::sync::Mutex<Duration>,
+    sleeps: std::sync::Mutex<Vec<Duration>>,
+}
+
+#[cfg(test)]
+impl FakeClock {
+    pub(crate) fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: std::sync::Mutex::new(Duration::ZERO),
+            sleeps: std::sync::Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+
+    /// Sleeps requested so far, in order.
+    pub(crate) fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.start + *self.elapsed.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.sleeps.lock().unwrap().push(duration);
+        self.advance(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fake_clock_advances_on_sleep() {
+        let clock = FakeClock::new();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+        clock.sleep(Duration::from_secs(2));
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(clock.now() - start, Duration::from_millis(2500));
+        assert_eq!(clock.sleeps(), [Duration::from_secs(2)]);
+    }
+
+    #[test]
+    fn test_system_clock_sleeps() {
+        let clock = SystemClock;
+        let start = clock.now();
+        clock.sleep(Duration::from_millis(10));
+        assert!(clock.now() - start >= Duration::from_millis(10));
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    pdu::{self, Pdu},
+    Error, MessageType, Oid, Result, Version, BUFFER_SIZE,
+};
+
+use super::value::value_to_string;
+
+/// Send a single SNMPv2c GET to a broadcast (or multicast) address and collect
+/// every reply that arrives within `wait`, tagged by its source address.
+///
+/// This is a best-effort discovery aid, not an inventory tool:
+/// - many agents ignore requests sent to a broadcast address,
+/// - routers do not forward subnet broadcasts, so only the local segment is reached,
+/// - replies are plain UDP and can be dropped under load; there are no retries.
+///
+/// Treat an empty result as "nothing answered", not "nothing is there".
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use snmp2::{oid, helpers::discover_broadcast};
+///
+/// let replies = discover_broadcast(
+///     "192.168.1.255:161",
+///     "public",
+///     &oid!("1.3.6.1.2.1.1.5.0"),
+///     Duration::from_secs(2),
+/// )?;
+/// for (source, sys_name) in replies {
+///     println!("{}: {}", source, sys_name);
+/// }
+/// # Ok::<(), snmp2::Error>(())
+/// ```
+///
+/// # Errors
+/// Returns `Error::Send` if the socket cannot be set up or the request cannot
+/// be sent, and `Error::Receive` on socket errors other than the timeout.
+pub fn discover_broadcast(
+    broadcast: &str,
+    community: &str,
+    oid: &Oid,
+    wait: Duration,
+) -> Result<Vec<(SocketAddr, String)>> {
+    let destination = broadcast
+        .to_socket_addrs()
+        .map_err(|_| Error::Send)?
+        .next()
+        .ok_or(Error::Send)?;
+    let socket = match destination {
+        SocketAddr::V4(_) => UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)),
+        SocketAddr::V6(_) => UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0)),
+    }
+    .map_err(|_| Error::Send)?;
+    socket.set_broadcast(true).map_err(|_| Error::Send)?;
+
+    let req_id = 1;
+    let mut send_pdu = pdu::Buf::default();
+    pdu::build_get(
+        Version::V2C,
+        community.as_bytes(),
+        req_id,
+        oid,
+        &mut send_pdu,
+        #[cfg(feature = "v3")]
+        None,
+    )?;
+    socket
+        .send_to(&send_pdu, destination)
+        .map_err(|_| Error::Send)?;
+
+    let deadline = Instant::now() + wait;
+    let mut recv_buf = vec![0u8; BUFFER_SIZE];
+    let mut replies = Vec::new();
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        socket
+            .set_read_timeout(Some(remaining))
+            .map_err(|_| Error::Receive)?;
+        match socket.recv_from(&mut recv_buf) {
+            Ok((len, source)) => {
+                // ignore anything that is not a reply to our request
+                let Ok(mut resp) = Pdu::from_bytes_lenient(&recv_buf[..len]) else {
+                    continue;
+                };
+                if resp
+                    .validate(MessageType::Response, req_id, community.as_bytes())
+                    .is_err()
+                {
+                    continue;
+                }
+                if let Some((_, value)) = resp.varbinds.next() {
+                    replies.push((source, value_to_string(&value)));
+                }
+            }
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+                ) =>
+            {
+                break;
+            }
+            Err(_) => return Err(Error::Receive),
+        }
+    }
+    Ok(replies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mock::MockAgent;
+    use crate::helpers::OwnedValue;
+    use crate::oid;
+
+    #[test]
+    fn test_discover_unicast_reply() {
+        let agent = MockAgent::start(vec![(
+            oid!("1.3.6.1.2.1.1.5.0"),
+            OwnedValue::OctetString(b"ap-1".to_vec()),
+        )]);
+        let replies = discover_broadcast(
+            &agent.addr().to_string(),
+            "public",
+            &oid!("1.3.6.1.2.1.1.5.0"),
+            Duration::from_millis(300),
+        )
+        .unwrap();
+        assert_eq!(replies, vec![(agent.addr(), "ap-1".to_string())]);
+    }
+
+    #[test]
+    fn test_discover_wrong_community_collects_nothing() {
+        let agent = MockAgent::start(vec![(
+            oid!("1.3.6.1.2.1.1.5.0"),
+            OwnedValue::OctetString(b"ap-1".to_vec()),
+        )]);
+        let replies = discover_broadcast(
+            &agent.addr().to_string(),
+            "private",
+            &oid!("1.3.6.1.2.1.1.5.0"),
+            Duration::from_millis(100),
+        )
+        .unwrap();
+        assert!(replies.is_empty());
+    }
+
+    #[test]
+    #[ignore]
+    fn test_discover_broadcast_integration() {
+        // Requires SNMP agents on the local segment answering broadcasts
+        let replies = discover_broadcast(
+            "255.255.255.255:161",
+            "public",
+            &oid!("1.3.6.1.2.1.1.5.0"),
+            Duration::from_secs(2),
+        )
+        .unwrap();
+        println!("{:?}", replies);
+    }
+}
@@ -0,0 +1,203 @@
+//! Minimal reverse (PTR) DNS lookup against the system resolver.
+//!
+//! Only what `format_source` needs: one UDP query to the first `nameserver`
+//! in `/etc/resolv.conf`, no retries, no TCP fallback, no caching.
+
+use std::{
+    collections::hash_map::RandomState,
+    fs,
+    hash::{BuildHasher, Hasher},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, UdpSocket},
+    time::Duration,
+};
+
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+const TYPE_PTR: u16 = 12;
+const CLASS_IN: u16 = 1;
+const MAX_POINTER_JUMPS: usize = 16;
+
+/// Resolve `ip` to a host name, or `None` on any failure or timeout.
+pub(super) fn reverse_lookup(ip: IpAddr, timeout: Duration) -> Option<String> {
+    let server = nameserver()?;
+    let socket = match server {
+        IpAddr::V4(_) => UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)),
+        IpAddr::V6(_) => UdpSocket::bind((Ipv6Addr::UNSPECIFIED, 0)),
+    }
+    .ok()?;
+    socket.set_read_timeout(Some(timeout)).ok()?;
+    socket.set_write_timeout(Some(timeout)).ok()?;
+    socket.connect((server, 53)).ok()?;
+    // randomly keyed, so the id can't be guessed from the clock
+    #[allow(clippy::cast_possible_truncation)]
+    let id = RandomState::new().build_hasher().finish() as u16;
+    socket.send(&build_query(id, &ptr_name(ip))).ok()?;
+    let mut buf = [0u8; 512];
+    let len = socket.recv(&mut buf).ok()?;
+    parse_ptr_answer(&buf[..len], id)
+}
+
+fn nameserver() -> Option<IpAddr> {
+    fs::read_to_string(RESOLV_CONF)
+        .ok()?
+        .lines()
+        .find_map(|line| {
+            let mut parts = line.split_whitespace();
+            if parts.next() == Some("nameserver") {
+                parts.next()?.parse().ok()
+            } else {
+                None
+            }
+        })
+}
+
+/// The `in-addr.arpa` / `ip6.arpa` name queried for `ip`.
+fn ptr_name(ip: IpAddr) -> String {
+    match ip {
+        IpAddr::V4(v4) => {
+            let o = v4.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", o[3], o[2], o[1], o[0])
+        }
+        IpAddr::V6(v6) => {
+            let mut name = String::with_capacity(72);
+            for byte in v6.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0x0f, byte >> 4));
+            }
+            name.push_str("ip6.arpa");
+            name
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn build_query(id: u16, name: &str) -> Vec<u8> {
+    let mut query = Vec::with_capacity(12 + name.len() + 6);
+    query.extend_from_slice(&id.to_be_bytes());
+    // recursion desired, one question
+    query.extend_from_slice(&[0x01, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+    for label in name.split('.') {
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_PTR.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+    query
+}
+
+fn read_u16(msg: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*msg.get(pos)?, *msg.get(pos + 1)?]))
+}
+
+/// Position just after the (possibly compressed) name starting at `pos`.
+fn skip_name(msg: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *msg.get(pos)?;
+        if len == 0 {
+            return Some(pos + 1);
+        }
+        if len & 0xc0 == 0xc0 {
+            return Some(pos + 2);
+        }
+        pos += 1 + usize::from(len);
+    }
+}
+
+fn read_name(msg: &[u8], mut pos: usize) -> Option<String> {
+    let mut labels = Vec::new();
+    let mut jumps = 0;
+    loop {
+        let len = *msg.get(pos)?;
+        if len == 0 {
+            break;
+        }
+        if len & 0xc0 == 0xc0 {
+            jumps += 1;
+            if jumps > MAX_POINTER_JUMPS {
+                return None;
+            }
+            pos = usize::from(read_u16(msg, pos)? & 0x3fff);
+            continue;
+        }
+        let label = msg.get(pos + 1..pos + 1 + usize::from(len))?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        pos += 1 + usize::from(len);
+    }
+    if labels.is_empty() {
+        None
+    } else {
+        Some(labels.join("."))
+    }
+}
+
+fn parse_ptr_answer(msg: &[u8], id: u16) -> Option<String> {
+    let flags = read_u16(msg, 2)?;
+    // must be a response with rcode NOERROR
+    if read_u16(msg, 0)? != id || flags & 0x8000 == 0 || flags & 0x000f != 0 {
+        return None;
+    }
+    let questions = read_u16(msg, 4)?;
+    let answers = read_u16(msg, 6)?;
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(msg, pos)? + 4;
+    }
+    for _ in 0..answers {
+        pos = skip_name(msg, pos)?;
+        let rtype = read_u16(msg, pos)?;
+        let rdlen = usize::from(read_u16(msg, pos + 8)?);
+        let rdata = pos + 10;
+        if rtype == TYPE_PTR {
+            return read_name(msg, rdata);
+        }
+        pos = rdata + rdlen;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ptr_name_v4() {
+        assert_eq!(
+            ptr_name(IpAddr::V4(Ipv4Addr::new(10, 56, 27, 13))),
+            "13.27.56.10.in-addr.arpa"
+        );
+    }
+
+    #[test]
+    fn test_ptr_name_v6() {
+        let name = ptr_name(IpAddr::V6(Ipv6Addr::LOCALHOST));
+        assert!(name.starts_with("1.0.0.0."));
+        assert!(name.ends_with(".0.ip6.arpa"));
+        assert_eq!(name.split('.').count(), 34);
+    }
+
+    #[test]
+    fn test_parse_ptr_answer() {
+        let mut msg = build_query(0x1234, "13.27.56.10.in-addr.arpa");
+        // turn the query into a response with one answer
+        msg[2] = 0x81;
+        msg[3] = 0x80;
+        msg[7] = 1;
+        // answer name: pointer to the question name at offset 12
+        msg.extend_from_slice(&[0xc0, 0x0c]);
+        msg.extend_from_slice(&TYPE_PTR.to_be_bytes());
+        msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+        msg.extend_from_slice(&[0, 0, 0x0e, 0x10]);
+        let rdata = [2, b'a', b'p', 3, b'l', b'a', b'n', 0];
+        msg.extend_from_slice(&[0, 8]);
+        msg.extend_from_slice(&rdata);
+        assert_eq!(parse_ptr_answer(&msg, 0x1234), Some("ap.lan".to_string()));
+        assert_eq!(parse_ptr_answer(&msg, 0x4321), None);
+    }
+
+    #[test]
+    fn test_parse_ptr_answer_nxdomain() {
+        let mut msg = build_query(7, "1.0.0.127.in-addr.arpa");
+        msg[2] = 0x81;
+        msg[3] = 0x83;
+        assert_eq!(parse_ptr_answer(&msg, 7), None);
+    }
+}
@@ -0,0 +1,152 @@
+//! Physical inventory helpers (ENTITY-MIB).
+
+use std::collections::BTreeMap;
+
+use crate::{Result, SyncSession};
+
+use super::interfaces::{text, walk_column};
+
+const ENT_PHYSICAL_DESCR: &[u64] = &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 2];
+const ENT_PHYSICAL_CLASS: &[u64] = &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 5];
+const ENT_PHYSICAL_NAME: &[u64] = &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 7];
+const ENT_PHYSICAL_SERIAL_NUM: &[u64] = &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 11];
+const ENT_PHYSICAL_MODEL_NAME: &[u64] = &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1, 1, 13];
+
+/// `entPhysicalClass`: the general kind of a physical component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysicalClass {
+    Other,
+    Unknown,
+    Chassis,
+    Backplane,
+    Container,
+    PowerSupply,
+    Fan,
+    Sensor,
+    Module,
+    Port,
+    Stack,
+    Cpu,
+    EnergyObject,
+    Battery,
+    StorageDrive,
+    /// A value outside the MIB's enumeration.
+    Unrecognized(i64),
+}
+
+impl From<i64> for PhysicalClass {
+    fn from(value: i64) -> Self {
+        match value {
+            1 => PhysicalClass::Other,
+            2 => PhysicalClass::Unknown,
+            3 => PhysicalClass::Chassis,
+            4 => PhysicalClass::Backplane,
+            5 => PhysicalClass::Container,
+            6 => PhysicalClass::PowerSupply,
+            7 => PhysicalClass::Fan,
+            8 => PhysicalClass::Sensor,
+            9 => PhysicalClass::Module,
+            10 => PhysicalClass::Port,
+            11 => PhysicalClass::Stack,
+            12 => PhysicalClass::Cpu,
+            13 => PhysicalClass::EnergyObject,
+            14 => PhysicalClass::Battery,
+            15 => PhysicalClass::StorageDrive,
+            other => PhysicalClass::Unrecognized(other),
+        }
+    }
+}
+
+/// One component of the agent's physical inventory (`entPhysicalTable`).
+///
+/// Agents report an empty string for columns that do not apply (a fan has
+/// no serial number); those, like missing columns, are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PhysicalEntity {
+    /// `entPhysicalIndex`.
+    pub index: u32,
+    pub descr: Option<String>,
+    pub name: Option<String>,
+    pub serial: Option<String>,
+    pub model: Option<String>,
+    pub class: Option<PhysicalClass>,
+}
+
+/// Walk the inventory columns of `entPhysicalTable`, in index order.
+pub(super) fn entity_inventory(session: &mut SyncSession) -> Result<Vec<PhysicalEntity>> {
+    let mut entities: BTreeMap<u32, PhysicalEntity> = BTreeMap::new();
+    let text_columns: [(&[u64], fn(&mut PhysicalEntity) -> &mut Option<String>); 4] = [
+        (ENT_PHYSICAL_DESCR, |entity| &mut entity.descr),
+        (ENT_PHYSICAL_NAME, |entity| &mut entity.name),
+        (ENT_PHYSICAL_SERIAL_NUM, |entity| &mut entity.serial),
+        (ENT_PHYSICAL_MODEL_NAME, |entity| &mut entity.model),
+    ];
+    for (column, field) in text_columns {
+        for (index, value) in walk_column(session, column)? {
+            *field(entities.entry(index).or_default()) = text(&value).filter(|s| !s.is_empty());
+        }
+    }
+    for (index, value) in walk_column(session, ENT_PHYSICAL_CLASS)? {
+        entities.entry(index).or_default().class = value.as_i64().map(PhysicalClass::from);
+    }
+    Ok(entities
+        .into_iter()
+        .map(|(index, entity)| PhysicalEntity { index, ..entity })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mock::{cell, octets, MockAgent};
+    use crate::helpers::value::OwnedValue;
+
+    #[test]
+    fn test_entity_inventory() {
+        let agent = MockAgent::start(vec![
+            cell(
+                ENT_PHYSICAL_DESCR,
+                &[1],
+                octets("Cisco Catalyst 9300 chassis"),
+            ),
+            cell(ENT_PHYSICAL_DESCR, &[1000], octets("48-port GE module")),
+            cell(ENT_PHYSICAL_CLASS, &[1], OwnedValue::Integer(3)),
+            cell(ENT_PHYSICAL_CLASS, &[1000], OwnedValue::Integer(9)),
+            cell(ENT_PHYSICAL_NAME, &[1], octets("Switch 1")),
+            cell(ENT_PHYSICAL_NAME, &[1000], octets("Switch 1 - Slot 1")),
+            cell(ENT_PHYSICAL_SERIAL_NUM, &[1], octets("FOC2233X0AB")),
+            cell(ENT_PHYSICAL_SERIAL_NUM, &[1000], octets("")),
+            cell(ENT_PHYSICAL_MODEL_NAME, &[1], octets("C9300-48P")),
+        ]);
+        let mut session = agent.session();
+        assert_eq!(
+            session.entity_inventory().unwrap(),
+            vec![
+                PhysicalEntity {
+                    index: 1,
+                    descr: Some("Cisco Catalyst 9300 chassis".to_string()),
+                    name: Some("Switch 1".to_string()),
+                    serial: Some("FOC2233X0AB".to_string()),
+                    model: Some("C9300-48P".to_string()),
+                    class: Some(PhysicalClass::Chassis),
+                },
+                PhysicalEntity {
+                    index: 1000,
+                    descr: Some("48-port GE module".to_string()),
+                    name: Some("Switch 1 - Slot 1".to_string()),
+                    serial: None,
+                    model: None,
+                    class: Some(PhysicalClass::Module),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_physical_class_from() {
+        assert_eq!(PhysicalClass::from(1), PhysicalClass::Other);
+        assert_eq!(PhysicalClass::from(6), PhysicalClass::PowerSupply);
+        assert_eq!(PhysicalClass::from(15), PhysicalClass::StorageDrive);
+        assert_eq!(PhysicalClass::from(0), PhysicalClass::Unrecognized(0));
+    }
+}
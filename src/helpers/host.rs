@@ -0,0 +1,132 @@
+//! Host metrics helpers (HOST-RESOURCES-MIB).
+
+use std::collections::BTreeMap;
+
+use crate::{Result, SyncSession};
+
+use super::interfaces::{text, walk_column};
+use super::value::OwnedValue;
+
+const HR_STORAGE_TYPE: &[u64] = &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 2];
+const HR_STORAGE_DESCR: &[u64] = &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 3];
+const HR_STORAGE_ALLOCATION_UNITS: &[u64] = &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 4];
+const HR_STORAGE_SIZE: &[u64] = &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 5];
+const HR_STORAGE_USED: &[u64] = &[1, 3, 6, 1, 2, 1, 25, 2, 3, 1, 6];
+const HR_PROCESSOR_LOAD: &[u64] = &[1, 3, 6, 1, 2, 1, 25, 3, 3, 1, 2];
+
+/// One storage area of a host (`hrStorageTable` row): a filesystem, RAM,
+/// swap, ...
+///
+/// `size` and `used` count allocation units of `units` bytes each. Missing
+/// columns and negative numbers are `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageEntry {
+    /// `hrStorageIndex`.
+    pub index: u32,
+    pub descr: Option<String>,
+    /// `hrStorageAllocationUnits`, in bytes.
+    pub units: Option<u64>,
+    pub size: Option<u64>,
+    pub used: Option<u64>,
+    /// `hrStorageType`, e.g. `1.3.6.1.2.1.25.2.1.4` (hrStorageFixedDisk).
+    pub type_oid: Option<String>,
+}
+
+/// Walk the columns of `hrStorageTable`, in index order.
+pub(super) fn host_storage(session: &mut SyncSession) -> Result<Vec<StorageEntry>> {
+    let mut entries: BTreeMap<u32, StorageEntry> = BTreeMap::new();
+    for (index, value) in walk_column(session, HR_STORAGE_TYPE)? {
+        if let OwnedValue::ObjectIdentifier(oid) = value {
+            entries.entry(index).or_default().type_oid = Some(oid);
+        }
+    }
+    for (index, value) in walk_column(session, HR_STORAGE_DESCR)? {
+        entries.entry(index).or_default().descr = text(&value).filter(|s| !s.is_empty());
+    }
+    let number_columns: [(&[u64], fn(&mut StorageEntry) -> &mut Option<u64>); 3] = [
+        (HR_STORAGE_ALLOCATION_UNITS, |entry| &mut entry.units),
+        (HR_STORAGE_SIZE, |entry| &mut entry.size),
+        (HR_STORAGE_USED, |entry| &mut entry.used),
+    ];
+    for (column, field) in number_columns {
+        for (index, value) in walk_column(session, column)? {
+            *field(entries.entry(index).or_default()) =
+                value.as_i64().and_then(|n| u64::try_from(n).ok());
+        }
+    }
+    Ok(entries
+        .into_iter()
+        .map(|(index, entry)| StorageEntry { index, ..entry })
+        .collect())
+}
+
+/// Walk `hrProcessorLoad`, one percentage per processor in index order.
+pub(super) fn cpu_load(session: &mut SyncSession) -> Result<Vec<u8>> {
+    Ok(walk_column(session, HR_PROCESSOR_LOAD)?
+        .into_iter()
+        .filter_map(|(_, value)| value.as_i64())
+        .map(|load| load.clamp(0, 100) as u8)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mock::{cell, octets, MockAgent};
+
+    fn storage_type(s: &str) -> OwnedValue {
+        OwnedValue::ObjectIdentifier(s.to_string())
+    }
+
+    #[test]
+    fn test_host_storage() {
+        let agent = MockAgent::start(vec![
+            cell(HR_STORAGE_TYPE, &[1], storage_type("1.3.6.1.2.1.25.2.1.2")),
+            cell(HR_STORAGE_TYPE, &[31], storage_type("1.3.6.1.2.1.25.2.1.4")),
+            cell(HR_STORAGE_DESCR, &[1], octets("Physical memory")),
+            cell(HR_STORAGE_DESCR, &[31], octets("/")),
+            cell(HR_STORAGE_ALLOCATION_UNITS, &[1], OwnedValue::Integer(1024)),
+            cell(
+                HR_STORAGE_ALLOCATION_UNITS,
+                &[31],
+                OwnedValue::Integer(4096),
+            ),
+            cell(HR_STORAGE_SIZE, &[1], OwnedValue::Integer(16_318_412)),
+            cell(HR_STORAGE_SIZE, &[31], OwnedValue::Integer(25_656_347)),
+            cell(HR_STORAGE_USED, &[1], OwnedValue::Integer(9_876_543)),
+            cell(HR_STORAGE_USED, &[31], OwnedValue::Integer(-1)),
+        ]);
+        let mut session = agent.session();
+        assert_eq!(
+            session.host_storage().unwrap(),
+            vec![
+                StorageEntry {
+                    index: 1,
+                    descr: Some("Physical memory".to_string()),
+                    units: Some(1024),
+                    size: Some(16_318_412),
+                    used: Some(9_876_543),
+                    type_oid: Some("1.3.6.1.2.1.25.2.1.2".to_string()),
+                },
+                StorageEntry {
+                    index: 31,
+                    descr: Some("/".to_string()),
+                    units: Some(4096),
+                    size: Some(25_656_347),
+                    used: None,
+                    type_oid: Some("1.3.6.1.2.1.25.2.1.4".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cpu_load() {
+        let agent = MockAgent::start(vec![
+            cell(HR_PROCESSOR_LOAD, &[196608], OwnedValue::Integer(12)),
+            cell(HR_PROCESSOR_LOAD, &[196609], OwnedValue::Integer(87)),
+        ]);
+        let mut session = agent.session();
+        assert_eq!(session.cpu_load().unwrap(), vec![12, 87]);
+    }
+}
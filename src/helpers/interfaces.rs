@@ -0,0 +1,489 @@
+//! Interface (IF-MIB) helpers.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{Error, Result, SyncSession};
+
+use super::oid::{oid_arcs, oid_from_arcs};
+use super::session::SessionExt;
+use super::value::OwnedValue;
+
+const IF_ENTRY: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1];
+const IF_X_ENTRY: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1];
+const IF_DESCR: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 2];
+const IF_SPEED: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 5];
+const IF_ADMIN_STATUS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 7];
+const IF_OPER_STATUS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 8];
+const IF_IN_OCTETS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 10];
+const IF_IN_ERRORS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 14];
+const IF_OUT_OCTETS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 16];
+const IF_OUT_ERRORS: &[u64] = &[1, 3, 6, 1, 2, 1, 2, 2, 1, 20];
+const IF_NAME: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 1];
+const IF_HC_IN_OCTETS: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6];
+const IF_HC_OUT_OCTETS: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 10];
+const IF_HIGH_SPEED: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 15];
+const IF_ALIAS: &[u64] = &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 18];
+
+/// One interface assembled from the IF-MIB `ifTable` and `ifXTable`.
+///
+/// Every column is optional because agents routinely leave some out (no
+/// `ifXTable` on older gear, no 64-bit counters on slow ports).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterfaceRow {
+    pub if_index: u32,
+    pub descr: Option<String>,
+    pub name: Option<String>,
+    pub alias: Option<String>,
+    /// `ifAdminStatus`: 1 up, 2 down, 3 testing.
+    pub admin_status: Option<i64>,
+    /// `ifOperStatus`: 1 up, 2 down, 3 testing, 4 unknown, 5 dormant, ...
+    pub oper_status: Option<i64>,
+    /// Bits per second, from `ifHighSpeed` when present, else `ifSpeed`.
+    pub speed_bps: Option<u64>,
+    /// `ifHCInOctets` when present, else the 32-bit `ifInOctets`.
+    pub in_octets: Option<u64>,
+    /// `ifHCOutOctets` when present, else the 32-bit `ifOutOctets`.
+    pub out_octets: Option<u64>,
+    pub in_errors: Option<u64>,
+    pub out_errors: Option<u64>,
+}
+
+impl InterfaceRow {
+    /// Human-readable label, see [`interface_label`].
+    pub fn label(&self) -> String {
+        interface_label(
+            self.if_index,
+            self.descr.as_deref(),
+            self.name.as_deref(),
+            self.alias.as_deref(),
+        )
+    }
+}
+
+/// Find the interface whose `ifName`, or failing that `ifDescr`, equals
+/// `name` (trimmed, ASCII case-insensitive) and fetch its row.
+pub(super) fn interface_by_name(
+    session: &mut SyncSession,
+    name: &str,
+) -> Result<Option<InterfaceRow>> {
+    let wanted = name.trim();
+    for column in [IF_NAME, IF_DESCR] {
+        let found = session
+            .walk_values(&oid_from_arcs(column)?)?
+            .into_iter()
+            .find(|(_, value)| text(value).map_or(false, |s| s.trim().eq_ignore_ascii_case(wanted)))
+            .and_then(|(oid, _)| oid_arcs(&oid).last().copied())
+            .and_then(|index| u32::try_from(index).ok());
+        if let Some(if_index) = found {
+            return fetch_row(session, if_index).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// GET `ifName.<if_index>`, then `ifDescr.<if_index>` if the name is missing
+/// or blank.
+pub(super) fn if_name_for_index(
+    session: &mut SyncSession,
+    if_index: i64,
+) -> Result<Option<String>> {
+    let Ok(index) = u64::try_from(if_index) else {
+        return Ok(None);
+    };
+    for column in [IF_NAME, IF_DESCR] {
+        let oid = oid_from_arcs(&[column, &[index][..]].concat())?;
+        let value = match session.get_value(&oid) {
+            Ok(value) => value,
+            Err(Error::EmptyResponse) => continue,
+            Err(e) => return Err(e),
+        };
+        if let Some(name) = text(&value).filter(|name| !name.trim().is_empty()) {
+            return Ok(Some(name));
+        }
+    }
+    Ok(None)
+}
+
+/// Walk `ifDescr` and `ifName` and map each ifIndex to its name, or to its
+/// description where the name is missing or blank.
+pub(super) fn interface_stable_keys(session: &mut SyncSession) -> Result<HashMap<i64, String>> {
+    let mut keys = HashMap::new();
+    // names walked last so they replace descriptions
+    for column in [IF_DESCR, IF_NAME] {
+        for (oid, value) in session.walk_values(&oid_from_arcs(column)?)? {
+            let Some(if_index) = oid_arcs(&oid)
+                .strip_prefix(column)
+                .and_then(|index| match index {
+                    &[if_index] => i64::try_from(if_index).ok(),
+                    _ => None,
+                })
+            else {
+                continue;
+            };
+            if let Some(name) = text(&value).filter(|name| !name.trim().is_empty()) {
+                keys.insert(if_index, name);
+            }
+        }
+    }
+    Ok(keys)
+}
+
+/// Bulk-walk `ifTable` and `ifXTable` and assemble every interface, in
+/// `ifIndex` order.
+pub(super) fn interface_table(session: &mut SyncSession) -> Result<Vec<InterfaceRow>> {
+    let mut cells: HashMap<(Vec<u64>, u32), OwnedValue> = HashMap::new();
+    for entry in [IF_ENTRY, IF_X_ENTRY] {
+        for (oid, value) in session.bulk_walk(&oid_from_arcs(entry)?, None)? {
+            let mut arcs = oid_arcs(&oid);
+            let Some(if_index) = arcs.pop().and_then(|index| u32::try_from(index).ok()) else {
+                continue;
+            };
+            if value != OwnedValue::Null {
+                cells.insert((arcs, if_index), value);
+            }
+        }
+    }
+    let indexes: BTreeSet<u32> = cells.keys().map(|(_, if_index)| *if_index).collect();
+    indexes
+        .into_iter()
+        .map(|if_index| {
+            build_row(if_index, |column| {
+                Ok(cells.get(&(column.to_vec(), if_index)).cloned())
+            })
+        })
+        .collect()
+}
+
+/// GET each column of one row. One request per column keeps a missing
+/// column from hiding the others.
+fn fetch_row(session: &mut SyncSession, if_index: u32) -> Result<InterfaceRow> {
+    build_row(if_index, |column| {
+        let mut arcs = column.to_vec();
+        arcs.push(u64::from(if_index));
+        let value = session.get_value(&oid_from_arcs(&arcs)?)?;
+        Ok(if value.is_error() || value == OwnedValue::Null {
+            None
+        } else {
+            Some(value)
+        })
+    })
+}
+
+/// Assemble a row from `get(column)`, asking for the 32-bit and `ifSpeed`
+/// fallbacks only when the 64-bit columns are missing.
+fn build_row<F>(if_index: u32, mut get: F) -> Result<InterfaceRow>
+where
+    F: FnMut(&[u64]) -> Result<Option<OwnedValue>>,
+{
+    let number = |value: Option<OwnedValue>| value.as_ref().and_then(OwnedValue::as_u64);
+
+    let high_speed = number(get(IF_HIGH_SPEED)?).filter(|&mbps| mbps > 0);
+    let speed_bps = match high_speed {
+        Some(mbps) => Some(mbps * 1_000_000),
+        None => number(get(IF_SPEED)?),
+    };
+    let in_octets = match number(get(IF_HC_IN_OCTETS)?) {
+        Some(octets) => Some(octets),
+        None => number(get(IF_IN_OCTETS)?),
+    };
+    let out_octets = match number(get(IF_HC_OUT_OCTETS)?) {
+        Some(octets) => Some(octets),
+        None => number(get(IF_OUT_OCTETS)?),
+    };
+    Ok(InterfaceRow {
+        if_index,
+        descr: get(IF_DESCR)?.as_ref().and_then(text),
+        name: get(IF_NAME)?.as_ref().and_then(text),
+        alias: get(IF_ALIAS)?.as_ref().and_then(text),
+        admin_status: get(IF_ADMIN_STATUS)?.as_ref().and_then(OwnedValue::as_i64),
+        oper_status: get(IF_OPER_STATUS)?.as_ref().and_then(OwnedValue::as_i64),
+        speed_bps,
+        in_octets,
+        out_octets,
+        in_errors: number(get(IF_IN_ERRORS)?),
+        out_errors: number(get(IF_OUT_ERRORS)?),
+    })
+}
+
+pub(super) fn text(value: &OwnedValue) -> Option<String> {
+    match value {
+        OwnedValue::OctetString(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        _ => None,
+    }
+}
+
+/// Walk a table `column` indexed by a single arc, keyed by that index.
+///
+/// Cells with a longer index or one that does not fit a `u32` are skipped.
+pub(super) fn walk_column(
+    session: &mut SyncSession,
+    column: &[u64],
+) -> Result<Vec<(u32, OwnedValue)>> {
+    Ok(session
+        .walk_values(&oid_from_arcs(column)?)?
+        .into_iter()
+        .filter_map(|(oid, value)| Some((single_index(&oid_arcs(&oid), column)?, value)))
+        .collect())
+}
+
+fn single_index(arcs: &[u64], column: &[u64]) -> Option<u32> {
+    match arcs.strip_prefix(column)? {
+        [index] => u32::try_from(*index).ok(),
+        _ => None,
+    }
+}
+
+/// Pick a human-readable label for an interface.
+///
+/// Vendors disagree on where the useful name lives: Cisco puts it in
+/// `ifDescr`, most others in `ifName`, and `ifAlias` holds the operator's
+/// description. Precedence is alias, then name, then descr, then
+/// `"ifIndex N"`. Values are trimmed and blank ones are skipped.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::interface_label;
+///
+/// assert_eq!(interface_label(3, Some("GigabitEthernet0/1"), Some("Gi0/1"), Some("uplink")), "uplink");
+/// assert_eq!(interface_label(3, Some("GigabitEthernet0/1"), Some("Gi0/1"), Some("")), "Gi0/1");
+/// assert_eq!(interface_label(3, None, None, None), "ifIndex 3");
+/// ```
+pub fn interface_label(
+    if_index: u32,
+    descr: Option<&str>,
+    name: Option<&str>,
+    alias: Option<&str>,
+) -> String {
+    [alias, name, descr]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map_or_else(|| format!("ifIndex {}", if_index), str::to_string)
+}
+
+/// Traffic rate of one interface over a polling interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterfaceRate {
+    pub if_index: u32,
+    /// `ifType` (IANAifType), e.g. 6 ethernetCsmacd, 24 softwareLoopback.
+    pub if_type: Option<i64>,
+    pub in_bps: u64,
+    pub out_bps: u64,
+}
+
+/// Sum the in/out rates of all interfaces whose `ifType` is not in
+/// `exclude_if_types`, as `(in_bps, out_bps)`.
+///
+/// Excluding loopbacks (24) and virtual interfaces keeps traffic from being
+/// counted twice in a device total. Interfaces with an unknown type are
+/// always counted. The sums saturate at `u64::MAX`.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{total_throughput, InterfaceRate};
+///
+/// let rates = [
+///     InterfaceRate { if_index: 1, if_type: Some(24), in_bps: 500, out_bps: 500 },
+///     InterfaceRate { if_index: 2, if_type: Some(6), in_bps: 8_000, out_bps: 2_000 },
+/// ];
+/// assert_eq!(total_throughput(&rates, &[24]), (8_000, 2_000));
+/// ```
+pub fn total_throughput(rates: &[InterfaceRate], exclude_if_types: &[i64]) -> (u64, u64) {
+    rates
+        .iter()
+        .filter(|rate| {
+            rate.if_type
+                .map_or(true, |if_type| !exclude_if_types.contains(&if_type))
+        })
+        .fold((0, 0), |(total_in, total_out), rate| {
+            (
+                total_in.saturating_add(rate.in_bps),
+                total_out.saturating_add(rate.out_bps),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mock::{cell, octets, MockAgent};
+
+    fn switch() -> MockAgent {
+        MockAgent::start(vec![
+            cell(IF_DESCR, &[1], octets("lo")),
+            cell(IF_DESCR, &[2], octets("GigabitEthernet0/1")),
+            cell(IF_DESCR, &[3], octets("GigabitEthernet0/2")),
+            cell(IF_DESCR, &[4], octets("Radio0")),
+            cell(IF_SPEED, &[2], OwnedValue::Unsigned32(1_000_000_000)),
+            cell(IF_ADMIN_STATUS, &[2], OwnedValue::Integer(1)),
+            cell(IF_OPER_STATUS, &[2], OwnedValue::Integer(2)),
+            cell(IF_IN_OCTETS, &[2], OwnedValue::Counter32(1000)),
+            cell(IF_IN_ERRORS, &[2], OwnedValue::Counter32(3)),
+            cell(IF_OUT_OCTETS, &[2], OwnedValue::Counter32(2000)),
+            cell(IF_OUT_ERRORS, &[2], OwnedValue::Counter32(0)),
+            cell(IF_IN_OCTETS, &[4], OwnedValue::Counter32(10)),
+            cell(IF_NAME, &[1], octets("lo")),
+            cell(IF_NAME, &[2], octets("Gi0/1")),
+            cell(IF_NAME, &[3], octets("Gi0/2")),
+            cell(IF_HC_IN_OCTETS, &[2], OwnedValue::Counter64(5_000_000_000)),
+            cell(IF_HIGH_SPEED, &[2], OwnedValue::Unsigned32(10_000)),
+            cell(IF_ALIAS, &[2], octets("uplink")),
+        ])
+    }
+
+    #[test]
+    fn test_total_throughput_excludes_loopback() {
+        let rate = |if_index, if_type, in_bps, out_bps| InterfaceRate {
+            if_index,
+            if_type,
+            in_bps,
+            out_bps,
+        };
+        let rates = [
+            rate(1, Some(24), 1_000_000, 1_000_000),
+            rate(2, Some(6), 40_000_000, 5_000_000),
+            rate(3, Some(6), 2_000_000, 30_000_000),
+            rate(4, None, 100, 200),
+        ];
+        assert_eq!(total_throughput(&rates, &[24]), (42_000_100, 35_000_200));
+        assert_eq!(total_throughput(&rates, &[]), (43_000_100, 36_000_200));
+        assert_eq!(total_throughput(&rates, &[6, 24]), (100, 200));
+        assert_eq!(total_throughput(&[], &[24]), (0, 0));
+        let huge = [rate(1, Some(6), u64::MAX, 1), rate(2, Some(6), 1, 1)];
+        assert_eq!(total_throughput(&huge, &[]), (u64::MAX, 2));
+    }
+
+    #[test]
+    fn test_interface_by_name() {
+        let agent = switch();
+        let mut session = agent.session();
+
+        let row = session.interface_by_name("  gi0/1 ").unwrap().unwrap();
+        assert_eq!(
+            row,
+            InterfaceRow {
+                if_index: 2,
+                descr: Some("GigabitEthernet0/1".to_string()),
+                name: Some("Gi0/1".to_string()),
+                alias: Some("uplink".to_string()),
+                admin_status: Some(1),
+                oper_status: Some(2),
+                speed_bps: Some(10_000_000_000),
+                in_octets: Some(5_000_000_000),
+                out_octets: Some(2000),
+                in_errors: Some(3),
+                out_errors: Some(0),
+            }
+        );
+        assert_eq!(row.label(), "uplink");
+    }
+
+    #[test]
+    fn test_interface_by_name_falls_back_to_descr() {
+        let agent = switch();
+        let mut session = agent.session();
+
+        // GigabitEthernet0/2 only matches ifDescr
+        let row = session
+            .interface_by_name("GIGABITETHERNET0/2")
+            .unwrap()
+            .unwrap();
+        assert_eq!(row.if_index, 3);
+        assert_eq!(row.name.as_deref(), Some("Gi0/2"));
+        assert_eq!(row.in_octets, None);
+
+        // no ifXTable row at all
+        let row = session.interface_by_name("radio0").unwrap().unwrap();
+        assert_eq!(row.if_index, 4);
+        assert_eq!(row.name, None);
+        assert_eq!(row.in_octets, Some(10));
+        assert_eq!(row.label(), "Radio0");
+    }
+
+    #[test]
+    fn test_interface_by_name_not_found() {
+        let agent = switch();
+        let mut session = agent.session();
+        assert_eq!(session.interface_by_name("Gi0/9").unwrap(), None);
+    }
+
+    #[test]
+    fn test_if_name_for_index() {
+        let agent = switch();
+        let mut session = agent.session();
+        assert_eq!(
+            session.if_name_for_index(2).unwrap().as_deref(),
+            Some("Gi0/1")
+        );
+        // no ifName, so ifDescr
+        assert_eq!(
+            session.if_name_for_index(4).unwrap().as_deref(),
+            Some("Radio0")
+        );
+        assert_eq!(session.if_name_for_index(9).unwrap(), None);
+        assert_eq!(session.if_name_for_index(-1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_interface_stable_keys() {
+        let agent = switch();
+        let mut session = agent.session();
+        let keys = session.interface_stable_keys().unwrap();
+        assert_eq!(keys.len(), 4);
+        assert_eq!(keys[&1], "lo");
+        assert_eq!(keys[&2], "Gi0/1");
+        assert_eq!(keys[&3], "Gi0/2");
+        // no ifName, so ifDescr
+        assert_eq!(keys[&4], "Radio0");
+    }
+
+    #[test]
+    fn test_interface_table() {
+        let agent = switch();
+        let mut session = agent.session();
+
+        let rows = interface_table(&mut session).unwrap();
+        let labels: Vec<String> = rows.iter().map(InterfaceRow::label).collect();
+        assert_eq!(labels, vec!["lo", "uplink", "Gi0/2", "Radio0"]);
+        assert_eq!(rows[1], fetch_row(&mut session, 2).unwrap());
+        assert_eq!(rows[3].in_octets, Some(10));
+    }
+
+    #[test]
+    fn test_interface_label_alias() {
+        assert_eq!(
+            interface_label(1, Some("eth0 descr"), Some("eth0"), Some("to core")),
+            "to core"
+        );
+    }
+
+    #[test]
+    fn test_interface_label_name() {
+        assert_eq!(
+            interface_label(1, Some("eth0 descr"), Some("eth0"), Some("  ")),
+            "eth0"
+        );
+        assert_eq!(
+            interface_label(1, Some("eth0 descr"), Some("eth0"), None),
+            "eth0"
+        );
+    }
+
+    #[test]
+    fn test_interface_label_descr() {
+        assert_eq!(
+            interface_label(1, Some("GigabitEthernet0/1"), Some(""), None),
+            "GigabitEthernet0/1"
+        );
+    }
+
+    #[test]
+    fn test_interface_label_all_empty() {
+        assert_eq!(
+            interface_label(7, Some(""), Some(" "), Some("")),
+            "ifIndex 7"
+        );
+        assert_eq!(interface_label(7, None, None, None), "ifIndex 7");
+    }
+}
@@ -0,0 +1,143 @@
+//! JSON rendering of walk results (`json` feature).
+
+use serde_json::{Map, Value as Json};
+
+use super::oid::oid_arcs;
+use super::value::{hex_bytes, OwnedValue, Varbind};
+
+/// Nest walk results into a JSON tree mirroring the OID hierarchy.
+///
+/// Every arc becomes an object key, so `1.3.6` is found at
+/// `tree["1"]["3"]["6"]`. The node of each varbind also gets a `"type"` key
+/// with the SNMP type name and a `"value"` key with its typed value: numbers
+/// for the integer types, text for UTF-8 OctetStrings, OIDs and IP
+/// addresses, space-separated hex for binary OctetStrings and Opaque, and
+/// `null` for NULL and the exceptions. Arc keys are numeric, so they never
+/// clash with `"type"`/`"value"`, and a node can carry a value and children
+/// at the same time.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::{walk_to_json_tree, OwnedValue, Varbind}};
+///
+/// let uptime = OwnedValue::Timeticks(1000);
+/// let name = OwnedValue::OctetString(b"ap-01".to_vec());
+/// let varbinds = [
+///     Varbind { oid: oid!("1.3.6.1.2.1.1.3.0"), value: uptime },
+///     Varbind { oid: oid!("1.3.6.1.2.1.1.5.0"), value: name },
+/// ];
+/// let tree = walk_to_json_tree(&varbinds);
+/// let system = &tree["1"]["3"]["6"]["1"]["2"]["1"]["1"];
+/// assert_eq!(system["3"]["0"]["value"], 1000);
+/// assert_eq!(system["5"]["0"]["type"], "OctetString");
+/// assert_eq!(system["5"]["0"]["value"], "ap-01");
+/// ```
+pub fn walk_to_json_tree(varbinds: &[Varbind]) -> Json {
+    let mut root = Map::new();
+    for varbind in varbinds {
+        let mut node = &mut root;
+        for arc in oid_arcs(&varbind.oid) {
+            node = match node
+                .entry(arc.to_string())
+                .or_insert_with(|| Json::Object(Map::new()))
+            {
+                Json::Object(children) => children,
+                _ => unreachable!("arc keys only ever hold objects"),
+            };
+        }
+        let (type_name, value) = typed_json(&varbind.value);
+        node.insert("type".to_string(), Json::from(type_name));
+        node.insert("value".to_string(), value);
+    }
+    Json::Object(root)
+}
+
+/// SNMP type name and JSON value of `value`.
+fn typed_json(value: &OwnedValue) -> (&'static str, Json) {
+    let json = match value {
+        OwnedValue::Boolean(b) => Json::from(*b),
+        OwnedValue::Integer(i) => Json::from(*i),
+        OwnedValue::OctetString(bytes) => match std::str::from_utf8(bytes) {
+            Ok(text) => Json::from(text),
+            Err(_) => Json::from(hex_bytes(bytes)),
+        },
+        OwnedValue::ObjectIdentifier(oid) => Json::from(oid.as_str()),
+        OwnedValue::IpAddress(_) => Json::from(value.to_string_lossy()),
+        OwnedValue::Counter32(c) | OwnedValue::Unsigned32(c) | OwnedValue::Timeticks(c) => {
+            Json::from(*c)
+        }
+        OwnedValue::Opaque(bytes) => Json::from(hex_bytes(bytes)),
+        OwnedValue::Counter64(c) => Json::from(*c),
+        OwnedValue::Null
+        | OwnedValue::EndOfMibView
+        | OwnedValue::NoSuchObject
+        | OwnedValue::NoSuchInstance => Json::Null,
+    };
+    (value.type_name(), json)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::helpers::parse_oid;
+
+    fn varbind(oid: &str, value: OwnedValue) -> Varbind {
+        Varbind {
+            oid: parse_oid(oid).unwrap(),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_walk_to_json_tree_nesting() {
+        let tree = walk_to_json_tree(&[
+            varbind(
+                "1.3.6.1.2.1.2.2.1.2.1",
+                OwnedValue::OctetString(b"eth0".to_vec()),
+            ),
+            varbind(
+                "1.3.6.1.2.1.2.2.1.2.2",
+                OwnedValue::OctetString(b"ath0".to_vec()),
+            ),
+            varbind(
+                "1.3.6.1.2.1.2.2.1.6.1",
+                OwnedValue::OctetString(vec![0x00, 0x27, 0xaa]),
+            ),
+            varbind("1.3.6.1.2.1.2.2.1.10.2", OwnedValue::Counter32(42)),
+        ]);
+        assert_eq!(
+            tree["1"]["3"]["6"]["1"]["2"]["1"]["2"]["2"]["1"],
+            json!({
+                "2": {
+                    "1": {"type": "OctetString", "value": "eth0"},
+                    "2": {"type": "OctetString", "value": "ath0"},
+                },
+                "6": {"1": {"type": "OctetString", "value": "00 27 aa"}},
+                "10": {"2": {"type": "Counter32", "value": 42}},
+            })
+        );
+    }
+
+    #[test]
+    fn test_walk_to_json_tree_value_and_children() {
+        let tree = walk_to_json_tree(&[
+            varbind("1.3.6.1", OwnedValue::IpAddress([10, 0, 0, 1])),
+            varbind("1.3.6.1.4", OwnedValue::Counter64(u64::MAX)),
+            varbind("1.3.6.2", OwnedValue::NoSuchInstance),
+        ]);
+        assert_eq!(
+            tree,
+            json!({"1": {"3": {"6": {
+                "1": {
+                    "type": "IpAddress",
+                    "value": "10.0.0.1",
+                    "4": {"type": "Counter64", "value": u64::MAX},
+                },
+                "2": {"type": "NoSuchInstance", "value": null},
+            }}}})
+        );
+        assert_eq!(walk_to_json_tree(&[]), json!({}));
+    }
+}
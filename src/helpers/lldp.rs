@@ -0,0 +1,248 @@
+//! LLDP neighbor helpers (LLDP-MIB).
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+};
+
+use crate::{Result, SyncSession};
+
+use super::interfaces::text;
+use super::net::format_mac;
+use super::oid::{oid_arcs, oid_from_arcs};
+use super::session::SessionExt;
+use super::value::OwnedValue;
+
+const LLDP_REM_CHASSIS_ID_SUBTYPE: &[u64] = &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 4];
+const LLDP_REM_CHASSIS_ID: &[u64] = &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 5];
+const LLDP_REM_PORT_ID_SUBTYPE: &[u64] = &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 6];
+const LLDP_REM_PORT_ID: &[u64] = &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 7];
+const LLDP_REM_PORT_DESC: &[u64] = &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 8];
+const LLDP_REM_SYS_NAME: &[u64] = &[1, 0, 8802, 1, 1, 2, 1, 4, 1, 1, 9];
+
+/// A neighbor's chassis or port ID, decoded according to its subtype.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LldpId {
+    ChassisComponent(String),
+    InterfaceAlias(String),
+    PortComponent(String),
+    /// MAC, lowercase and colon-separated.
+    Mac(String),
+    NetworkAddress(IpAddr),
+    InterfaceName(String),
+    /// Port IDs only (RFC 3046 agent circuit ID), binary.
+    AgentCircuitId(Vec<u8>),
+    Local(String),
+    /// An unknown subtype, or a MAC or address of the wrong length.
+    Other {
+        subtype: i64,
+        bytes: Vec<u8>,
+    },
+}
+
+impl LldpId {
+    /// Decode an `lldpRemChassisId` with its `LldpChassisIdSubtype`.
+    fn chassis(subtype: i64, bytes: &[u8]) -> Self {
+        let lossy = || String::from_utf8_lossy(bytes).into_owned();
+        match subtype {
+            1 => LldpId::ChassisComponent(lossy()),
+            2 => LldpId::InterfaceAlias(lossy()),
+            3 => LldpId::PortComponent(lossy()),
+            4 => mac(bytes).unwrap_or_else(|| other(subtype, bytes)),
+            5 => network_address(bytes).unwrap_or_else(|| other(subtype, bytes)),
+            6 => LldpId::InterfaceName(lossy()),
+            7 => LldpId::Local(lossy()),
+            _ => other(subtype, bytes),
+        }
+    }
+
+    /// Decode an `lldpRemPortId` with its `LldpPortIdSubtype`, which numbers
+    /// the same kinds differently.
+    fn port(subtype: i64, bytes: &[u8]) -> Self {
+        let lossy = || String::from_utf8_lossy(bytes).into_owned();
+        match subtype {
+            1 => LldpId::InterfaceAlias(lossy()),
+            2 => LldpId::PortComponent(lossy()),
+            3 => mac(bytes).unwrap_or_else(|| other(subtype, bytes)),
+            4 => network_address(bytes).unwrap_or_else(|| other(subtype, bytes)),
+            5 => LldpId::InterfaceName(lossy()),
+            6 => LldpId::AgentCircuitId(bytes.to_vec()),
+            7 => LldpId::Local(lossy()),
+            _ => other(subtype, bytes),
+        }
+    }
+}
+
+fn other(subtype: i64, bytes: &[u8]) -> LldpId {
+    LldpId::Other {
+        subtype,
+        bytes: bytes.to_vec(),
+    }
+}
+
+fn mac(bytes: &[u8]) -> Option<LldpId> {
+    (bytes.len() == 6).then(|| LldpId::Mac(format_mac(bytes)))
+}
+
+/// An IANA address family octet (1 IPv4, 2 IPv6) followed by the address.
+fn network_address(bytes: &[u8]) -> Option<LldpId> {
+    let ip = match bytes {
+        [1, rest @ ..] => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(rest).ok()?)),
+        [2, rest @ ..] => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(rest).ok()?)),
+        _ => return None,
+    };
+    Some(LldpId::NetworkAddress(ip))
+}
+
+/// One directly connected neighbor (`lldpRemTable` row).
+///
+/// Columns the agent does not report, and empty descriptions or names, are
+/// `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LldpNeighbor {
+    /// `lldpRemLocalPortNum`: the local port the neighbor was heard on. This
+    /// is an `lldpLocPortNum`, which most agents set to the ifIndex.
+    pub local_port: u32,
+    /// `lldpRemIndex`: tells several neighbors on one port apart.
+    pub remote_index: u32,
+    pub chassis_id: Option<LldpId>,
+    pub port_id: Option<LldpId>,
+    pub port_desc: Option<String>,
+    pub sys_name: Option<String>,
+}
+
+/// `lldpRemTable` index reordered to (local port, remote index, time mark),
+/// so rows sort by local port.
+type RemoteKey = (u32, u32, u32);
+
+/// Walk the ID, port description and system name columns of `lldpRemTable`.
+pub(super) fn lldp_neighbors(session: &mut SyncSession) -> Result<Vec<LldpNeighbor>> {
+    let chassis_subtypes = walk_column(session, LLDP_REM_CHASSIS_ID_SUBTYPE)?;
+    let chassis_ids = walk_column(session, LLDP_REM_CHASSIS_ID)?;
+    let port_subtypes = walk_column(session, LLDP_REM_PORT_ID_SUBTYPE)?;
+    let port_ids = walk_column(session, LLDP_REM_PORT_ID)?;
+    let port_descs = walk_column(session, LLDP_REM_PORT_DESC)?;
+    let sys_names = walk_column(session, LLDP_REM_SYS_NAME)?;
+
+    let keys: BTreeSet<RemoteKey> = [&chassis_ids, &port_ids, &port_descs, &sys_names]
+        .into_iter()
+        .flat_map(|column| column.keys().copied())
+        .collect();
+    let non_empty_text = |column: &BTreeMap<RemoteKey, OwnedValue>, key: &RemoteKey| {
+        column.get(key).and_then(text).filter(|s| !s.is_empty())
+    };
+    Ok(keys
+        .iter()
+        .map(|key @ &(local_port, remote_index, _)| LldpNeighbor {
+            local_port,
+            remote_index,
+            chassis_id: decode_id(&chassis_subtypes, &chassis_ids, key, LldpId::chassis),
+            port_id: decode_id(&port_subtypes, &port_ids, key, LldpId::port),
+            port_desc: non_empty_text(&port_descs, key),
+            sys_name: non_empty_text(&sys_names, key),
+        })
+        .collect())
+}
+
+/// Decode the ID in `ids` at `key`; a missing subtype decodes as `Other`.
+fn decode_id(
+    subtypes: &BTreeMap<RemoteKey, OwnedValue>,
+    ids: &BTreeMap<RemoteKey, OwnedValue>,
+    key: &RemoteKey,
+    decode: fn(i64, &[u8]) -> LldpId,
+) -> Option<LldpId> {
+    let OwnedValue::OctetString(bytes) = ids.get(key)? else {
+        return None;
+    };
+    let subtype = subtypes.get(key).and_then(OwnedValue::as_i64).unwrap_or(0);
+    Some(decode(subtype, bytes))
+}
+
+/// Walk `column`, keyed by its `lldpRemTable` index.
+fn walk_column(
+    session: &mut SyncSession,
+    column: &[u64],
+) -> Result<BTreeMap<RemoteKey, OwnedValue>> {
+    Ok(session
+        .walk_values(&oid_from_arcs(column)?)?
+        .into_iter()
+        .filter_map(|(oid, value)| Some((remote_key(&oid_arcs(&oid), column)?, value)))
+        .collect())
+}
+
+/// Index of a cell in `column`: `lldpRemTimeMark.lldpRemLocalPortNum.lldpRemIndex`.
+fn remote_key(arcs: &[u64], column: &[u64]) -> Option<RemoteKey> {
+    match arcs.strip_prefix(column)? {
+        &[time_mark, local_port, remote_index] => Some((
+            u32::try_from(local_port).ok()?,
+            u32::try_from(remote_index).ok()?,
+            u32::try_from(time_mark).ok()?,
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::mock::{cell, octets, MockAgent};
+
+    /// Time mark 0, local port 3, remote index 1.
+    const REMOTE: &[u64] = &[0, 3, 1];
+
+    #[test]
+    fn test_lldp_neighbors_one_neighbor() {
+        let agent = MockAgent::start(vec![
+            cell(LLDP_REM_CHASSIS_ID_SUBTYPE, REMOTE, OwnedValue::Integer(4)),
+            cell(
+                LLDP_REM_CHASSIS_ID,
+                REMOTE,
+                octets(&[0x00, 0x27, 0x22, 0xaa, 0xbb, 0xcc]),
+            ),
+            cell(LLDP_REM_PORT_ID_SUBTYPE, REMOTE, OwnedValue::Integer(5)),
+            cell(LLDP_REM_PORT_ID, REMOTE, octets(b"Gi1/0/24")),
+            cell(LLDP_REM_PORT_DESC, REMOTE, octets(b"uplink to core")),
+            cell(LLDP_REM_SYS_NAME, REMOTE, octets(b"core-sw1")),
+        ]);
+        let mut session = agent.session();
+        assert_eq!(
+            session.lldp_neighbors().unwrap(),
+            vec![LldpNeighbor {
+                local_port: 3,
+                remote_index: 1,
+                chassis_id: Some(LldpId::Mac("00:27:22:aa:bb:cc".to_string())),
+                port_id: Some(LldpId::InterfaceName("Gi1/0/24".to_string())),
+                port_desc: Some("uplink to core".to_string()),
+                sys_name: Some("core-sw1".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_lldp_id_subtypes() {
+        assert_eq!(
+            LldpId::chassis(5, &[1, 10, 0, 0, 1]),
+            LldpId::NetworkAddress(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)))
+        );
+        assert_eq!(
+            LldpId::port(3, &[0xf0, 0x9f, 0xc2, 0x10, 0x20, 0x30]),
+            LldpId::Mac("f0:9f:c2:10:20:30".to_string())
+        );
+        assert_eq!(LldpId::port(7, b"17"), LldpId::Local("17".to_string()));
+        // a MAC subtype with the wrong length stays raw
+        assert_eq!(
+            LldpId::chassis(4, &[0x00, 0x27]),
+            LldpId::Other {
+                subtype: 4,
+                bytes: vec![0x00, 0x27]
+            }
+        );
+        assert_eq!(
+            LldpId::chassis(9, b"x"),
+            LldpId::Other {
+                subtype: 9,
+                bytes: b"x".to_vec()
+            }
+        );
+    }
+}
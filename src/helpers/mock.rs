@@ -0,0 +1,360 @@
+//! In-process SNMP agent used by the helper tests.
+//!
+//! Serves GET, GETNEXT and GETBULK from a static table over loopback UDP so
+//! session-level helpers can be exercised without a live device. SETs are
+//! echoed back but never change the table.
+
+use std::{
+    net::{SocketAddr, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    pdu::{self, Pdu},
+    snmp, MessageType, Oid, SyncSession, Value, BUFFER_SIZE,
+};
+
+use super::oid::{oid_arcs, oid_from_arcs, parse_oid};
+use super::value::OwnedValue;
+
+pub(crate) const COMMUNITY: &[u8] = b"public";
+
+/// Table entry for the cell of `column` at `index`.
+pub(crate) fn cell(column: &[u64], index: &[u64], value: OwnedValue) -> (Oid<'static>, OwnedValue) {
+    (oid_from_arcs(&[column, index].concat()).unwrap(), value)
+}
+
+/// An OctetString value.
+pub(crate) fn octets(bytes: impl AsRef<[u8]>) -> OwnedValue {
+    OwnedValue::OctetString(bytes.as_ref().to_vec())
+}
+
+/// `ifInOctets.1` to `ifInOctets.count`, for tests that need many OIDs.
+pub(crate) fn if_in_octets(count: u64) -> Vec<Oid<'static>> {
+    (1..=count)
+        .map(|i| oid_from_arcs(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 10, i]).unwrap())
+        .collect()
+}
+
+/// Deviations from a well-behaved agent.
+#[derive(Default)]
+pub(crate) struct MockOptions {
+    /// Fixed answer to every GET, replacing the table lookup.
+    pub(crate) get_reply: Option<Vec<(Oid<'static>, OwnedValue)>>,
+    /// Extra varbinds appended to every GET response.
+    pub(crate) get_padding: Vec<(Oid<'static>, OwnedValue)>,
+    /// GETNEXT answers that bypass the table: (requested OID, answer).
+    pub(crate) getnext_overrides: Vec<(Oid<'static>, (Oid<'static>, OwnedValue))>,
+    /// Pause before answering each request, to simulate a slow device.
+    pub(crate) response_delay: Duration,
+    /// Fixed answer to every GETBULK, for agents that mishandle it.
+    pub(crate) getbulk_reply: Option<Vec<(Oid<'static>, OwnedValue)>>,
+    /// Answer requests with an unknown community with `authorizationError`
+    /// instead of dropping them.
+    pub(crate) reject_bad_community: bool,
+    /// Ignore this many well-formed requests before answering, to force
+    /// retransmits.
+    pub(crate) drop_first: usize,
+    /// Fail a GET for a missing object like an SNMPv1 agent: `noSuchName`
+    /// pointing at the first one, with the request echoed back as Nulls.
+    pub(crate) no_such_name: bool,
+    /// Reject every SET with this error-status (0: accept), pointing at the
+    /// first varbind.
+    pub(crate) set_error_status: u32,
+    /// Fail every GET with this error-status (0: answer normally) and
+    /// error-index 0, like `tooBig` or an unattributed `genErr`.
+    pub(crate) get_error_status: u32,
+}
+
+pub(crate) struct MockAgent {
+    addr: SocketAddr,
+    socket: UdpSocket,
+    requests: Arc<AtomicUsize>,
+    req_ids: Arc<Mutex<Vec<i32>>>,
+    varbinds_sent: Arc<AtomicUsize>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockAgent {
+    /// Start an agent answering for `COMMUNITY` from the given table.
+    pub(crate) fn start(table: Vec<(Oid<'static>, OwnedValue)>) -> Self {
+        Self::start_with(table, MockOptions::default())
+    }
+
+    /// Start an agent with non-default behaviour.
+    pub(crate) fn start_with(table: Vec<(Oid<'static>, OwnedValue)>, options: MockOptions) -> Self {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket
+            .set_read_timeout(Some(Duration::from_millis(20)))
+            .unwrap();
+        let addr = socket.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let req_ids = Arc::new(Mutex::new(Vec::new()));
+        let varbinds_sent = Arc::new(AtomicUsize::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut table = table;
+        table.sort_by_cached_key(|(oid, _)| oid_arcs(oid));
+        let handle = {
+            let counters = Counters {
+                requests: requests.clone(),
+                req_ids: req_ids.clone(),
+                varbinds_sent: varbinds_sent.clone(),
+            };
+            let stop = stop.clone();
+            let socket = socket.try_clone().unwrap();
+            thread::spawn(move || serve(&socket, &table, &options, &counters, &stop))
+        };
+        Self {
+            addr,
+            socket: socket.try_clone().unwrap(),
+            requests,
+            req_ids,
+            varbinds_sent,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Open a v2c session pointing at this agent.
+    pub(crate) fn session(&self) -> SyncSession {
+        SyncSession::new_v2c(self.addr, COMMUNITY, Some(Duration::from_secs(1)), 0).unwrap()
+    }
+
+    pub(crate) fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Send a raw datagram from the agent's address, e.g. a late reply.
+    pub(crate) fn inject(&self, peer: SocketAddr, bytes: &[u8]) {
+        self.socket.send_to(bytes, peer).unwrap();
+    }
+
+    /// Number of well-formed requests received so far.
+    pub(crate) fn requests(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+
+    /// Request IDs of all well-formed requests received so far, in order.
+    pub(crate) fn req_ids(&self) -> Vec<i32> {
+        self.req_ids.lock().unwrap().clone()
+    }
+
+    /// Total number of varbinds in all responses sent so far.
+    pub(crate) fn varbinds_sent(&self) -> usize {
+        self.varbinds_sent.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for MockAgent {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+struct Counters {
+    requests: Arc<AtomicUsize>,
+    req_ids: Arc<Mutex<Vec<i32>>>,
+    varbinds_sent: Arc<AtomicUsize>,
+}
+
+fn serve(
+    socket: &UdpSocket,
+    table: &[(Oid<'static>, OwnedValue)],
+    options: &MockOptions,
+    counters: &Counters,
+    stop: &AtomicBool,
+) {
+    let mut recv_buf = vec![0u8; BUFFER_SIZE];
+    let mut send_buf = pdu::Buf::default();
+    while !stop.load(Ordering::SeqCst) {
+        let Ok((len, peer)) = socket.recv_from(&mut recv_buf) else {
+            continue;
+        };
+        let Ok(req) = Pdu::from_bytes(&recv_buf[..len]) else {
+            continue;
+        };
+        let received = counters.requests.fetch_add(1, Ordering::SeqCst);
+        counters.req_ids.lock().unwrap().push(req.req_id);
+        if received < options.drop_first {
+            continue;
+        }
+        let Ok(version) = req.version() else {
+            continue;
+        };
+        // real agents silently drop requests with an unknown community
+        if req.community != COMMUNITY {
+            if options.reject_bad_community
+                && pdu::build(
+                    version,
+                    req.community,
+                    snmp::MSG_RESPONSE,
+                    req.req_id,
+                    &[],
+                    snmp::ERRSTATUS_AUTHORIZATIONERROR,
+                    0,
+                    &mut send_buf,
+                    #[cfg(feature = "v3")]
+                    None,
+                )
+                .is_ok()
+            {
+                let _ = socket.send_to(&send_buf, peer);
+            }
+            continue;
+        }
+        let oids: Vec<Oid<'static>> = req
+            .varbinds
+            .clone()
+            .map(|(oid, _)| oid.to_owned())
+            .collect();
+        let reply: Vec<(Oid<'static>, OwnedValue)> = match req.message_type {
+            MessageType::GetRequest => match &options.get_reply {
+                Some(reply) => reply.clone(),
+                None => oids
+                    .iter()
+                    .map(|oid| lookup(table, oid))
+                    .chain(options.get_padding.iter().cloned())
+                    .collect(),
+            },
+            MessageType::GetNextRequest => oids
+                .iter()
+                .map(|oid| {
+                    options
+                        .getnext_overrides
+                        .iter()
+                        .find(|(requested, _)| requested == oid)
+                        .map_or_else(|| next(table, oid), |(_, answer)| answer.clone())
+                })
+                .collect(),
+            MessageType::GetBulkRequest => match &options.getbulk_reply {
+                Some(reply) => reply.clone(),
+                None => bulk(table, &oids, req.error_status, req.error_index),
+            },
+            // like an agent that acknowledges writes and then ignores them
+            MessageType::SetRequest => req
+                .varbinds
+                .clone()
+                .map(|(oid, value)| (oid.to_owned(), OwnedValue::from_value(&value)))
+                .collect(),
+            _ => continue,
+        };
+        let (reply, error_status, error_index) = match reply
+            .iter()
+            .position(|(_, value)| *value == OwnedValue::NoSuchObject)
+        {
+            Some(missing)
+                if options.no_such_name && matches!(req.message_type, MessageType::GetRequest) =>
+            {
+                (
+                    oids.iter()
+                        .map(|oid| (oid.clone(), OwnedValue::Null))
+                        .collect(),
+                    snmp::ERRSTATUS_NOSUCHNAME,
+                    u32::try_from(missing + 1).unwrap(),
+                )
+            }
+            _ if options.set_error_status != 0
+                && matches!(req.message_type, MessageType::SetRequest) =>
+            {
+                (reply, options.set_error_status, 1)
+            }
+            _ if options.get_error_status != 0
+                && matches!(req.message_type, MessageType::GetRequest) =>
+            {
+                (reply, options.get_error_status, 0)
+            }
+            _ => (reply, 0, 0),
+        };
+        let values: Vec<(&Oid, Value)> = reply
+            .iter()
+            .map(|(oid, value)| (oid, to_value(value)))
+            .collect();
+        if pdu::build(
+            version,
+            COMMUNITY,
+            snmp::MSG_RESPONSE,
+            req.req_id,
+            &values,
+            error_status,
+            error_index,
+            &mut send_buf,
+            #[cfg(feature = "v3")]
+            None,
+        )
+        .is_ok()
+        {
+            thread::sleep(options.response_delay);
+            counters
+                .varbinds_sent
+                .fetch_add(values.len(), Ordering::SeqCst);
+            let _ = socket.send_to(&send_buf, peer);
+        }
+    }
+}
+
+fn lookup(table: &[(Oid<'static>, OwnedValue)], oid: &Oid) -> (Oid<'static>, OwnedValue) {
+    table
+        .iter()
+        .find(|(candidate, _)| candidate == oid)
+        .cloned()
+        .unwrap_or_else(|| (oid.to_owned(), OwnedValue::NoSuchObject))
+}
+
+fn next(table: &[(Oid<'static>, OwnedValue)], oid: &Oid) -> (Oid<'static>, OwnedValue) {
+    let arcs = oid_arcs(oid);
+    table
+        .iter()
+        .find(|(candidate, _)| oid_arcs(candidate) > arcs)
+        .cloned()
+        .unwrap_or_else(|| (oid.to_owned(), OwnedValue::EndOfMibView))
+}
+
+fn bulk(
+    table: &[(Oid<'static>, OwnedValue)],
+    oids: &[Oid<'static>],
+    non_repeaters: u32,
+    max_repetitions: u32,
+) -> Vec<(Oid<'static>, OwnedValue)> {
+    let split = (non_repeaters as usize).min(oids.len());
+    let (scalars, repeaters) = oids.split_at(split);
+    let mut reply: Vec<_> = scalars.iter().map(|oid| next(table, oid)).collect();
+    let mut cursors = repeaters.to_vec();
+    for _ in 0..max_repetitions {
+        for cursor in &mut cursors {
+            let (oid, value) = next(table, cursor);
+            *cursor = oid.clone();
+            reply.push((oid, value));
+        }
+    }
+    reply
+}
+
+fn to_value(value: &OwnedValue) -> Value<'_> {
+    match value {
+        OwnedValue::Boolean(b) => Value::Boolean(*b),
+        OwnedValue::Null => Value::Null,
+        OwnedValue::Integer(i) => Value::Integer(*i),
+        OwnedValue::OctetString(s) => Value::OctetString(s),
+        OwnedValue::ObjectIdentifier(s) => {
+            parse_oid(s).map_or(Value::Null, Value::ObjectIdentifier)
+        }
+        OwnedValue::IpAddress(ip) => Value::IpAddress(*ip),
+        OwnedValue::Counter32(c) => Value::Counter32(*c),
+        OwnedValue::Unsigned32(u) => Value::Unsigned32(*u),
+        OwnedValue::Timeticks(t) => Value::Timeticks(*t),
+        OwnedValue::Opaque(o) => Value::Opaque(o),
+        OwnedValue::Counter64(c) => Value::Counter64(*c),
+        OwnedValue::EndOfMibView => Value::EndOfMibView,
+        OwnedValue::NoSuchObject => Value::NoSuchObject,
+        OwnedValue::NoSuchInstance => Value::NoSuchInstance,
+    }
+}
@@ -1,21 +1,89 @@
 //! Convenience helpers and extensions for ergonomic SNMP operations.
 //!
 //! This module provides:
-//! - OID string parsing and the `oid!` macro (`parse_oid`)
-//! - Session extensions for walk operations with type preservation (`SessionExt`)
-//! - Owned value type for data that outlives the receive buffer (`OwnedValue`)
-//! - Value extraction helpers (`ValueExt`)
-//! - Version fallback client with retry logic (`SnmpClient`)
-//! - Network utilities: MAC formatting, distance conversion (`format_mac`, `meters_to_miles`)
+//! - OID string parsing, the `oid!` macro, string indexes and compact rendering
+//!   (`parse_oid`, `normalize_oid_str`, `append_string_index`, `relative_to`,
+//!   `enterprise_number`, `parse_walk_line`, `chunk_oids`, `next_oid`, `successor`,
+//!   `strip_prefix`, `oid_to_name`)
+//! - Drift detection between two walks of a subtree (`walk_diff`, `WalkDiff`)
+//! - Session extensions for walk operations with type preservation, pluggable walk bounds
+//!   (`SessionExt`, `SubtreeBound`, `DescendantBound`, `PrefixLenBound`)
+//! - Owned value and varbind types for data that outlives the receive buffer (`OwnedValue`, `Varbind`)
+//! - Value extraction and aggregation helpers, Hex-STRING and packed integer decoding,
+//!   row assembly from column data (`ValueExt`, `numeric_stats`, `type_histogram`,
+//!   `parse_hex_string_display`, `octet_string_to_uint`, `assemble_rows`, `Row`)
+//! - SET value constructors (`set_integer`, `set_string`, `set_oid`, `set_ip`, `set_opaque`,
+//!   `opaque`)
+//! - Walk results as a nested JSON tree (`walk_to_json_tree`, `json` feature)
+//! - Version fallback client with retry logic, proxy context communities, poller sizing,
+//!   target dedupe (`SnmpClient`, `community_for_context`, `recommended_concurrency`,
+//!   `same_target`)
+//! - Pluggable time source for deadlines, backoff and uptime tracking (`Clock`, `SystemClock`)
+//! - Best-effort broadcast discovery (`discover_broadcast`)
+//! - v1/v2c trap decoding and a blocking receiver with source filtering and
+//!   rate limiting (`Trap`, `TrapListener`)
+//! - System group and device snapshots, drift detection (`SystemInfo`, `DeviceSnapshot`,
+//!   `system_info_diff`, `decode_sys_services`)
+//! - Interface (IF-MIB) helpers, device throughput totals (`interface_label`, `InterfaceRow`,
+//!   `total_throughput`)
+//! - ARP / neighbor table entries (`ArpEntry`, `index_to_ip`)
+//! - Bridge forwarding database entries (`FdbEntry`, `FdbStatus`)
+//! - Physical inventory from the ENTITY-MIB (`PhysicalEntity`, `PhysicalClass`)
+//! - Host storage and processor load from the HOST-RESOURCES-MIB (`StorageEntry`)
+//! - LLDP neighbors for topology discovery (`LldpNeighbor`, `LldpId`)
+//! - Network utilities: MAC formatting and styles, distance conversion, rate/size formatting,
+//!   radio signal quality, InetAddress encoding, counter resets and saturated gauges
+//!   (`format_mac`, `MacFormat`, `meters_to_miles`, `format_bps`, `snr_to_quality`,
+//!   `encode_inet_address`, `counter_reset_detected`, `gauge_utilization`)
 
+mod arp;
+mod bridge;
 mod client;
+mod clock;
+mod discovery;
+mod dns;
+mod entity;
+mod host;
+mod interfaces;
+#[cfg(feature = "json")]
+mod json;
+mod lldp;
+#[cfg(test)]
+mod mock;
 mod net;
 mod oid;
 mod session;
+mod system;
+mod trap;
 mod value;
 
-pub use client::SnmpClient;
-pub use net::{bits_to_miles, format_mac, format_mac_dashed, meters_to_miles, parse_mac};
-pub use oid::parse_oid;
-pub use session::SessionExt;
-pub use value::{value_to_string, OwnedValue, ValueExt};
+pub use arp::ArpEntry;
+pub use bridge::{FdbEntry, FdbStatus};
+pub use client::{community_for_context, recommended_concurrency, same_target, SnmpClient};
+pub use clock::{Clock, SystemClock};
+pub use discovery::discover_broadcast;
+pub use entity::{PhysicalClass, PhysicalEntity};
+pub use host::StorageEntry;
+pub use interfaces::{interface_label, total_throughput, InterfaceRate, InterfaceRow};
+#[cfg(feature = "json")]
+pub use json::walk_to_json_tree;
+pub use lldp::{LldpId, LldpNeighbor};
+pub use net::{
+    bits_to_miles, counter_reset_detected, encode_inet_address, format_bps, format_bytes,
+    format_mac, format_mac_dashed, format_source, gauge_utilization, meters_to_miles, parse_mac,
+    snr_to_quality, MacFormat, QualityCurve, SaturatedGauge,
+};
+pub use oid::{
+    append_string_index, chunk_oids, enterprise_number, index_to_ip, next_oid, normalize_oid_str,
+    oid_diff, oid_from_arcs, oid_to_arcs, oid_to_name, parse_oid, parse_walk_line, relative_to,
+    strip_prefix, successor, walk_diff, WalkDiff,
+};
+pub use session::{BulkResponse, DescendantBound, PrefixLenBound, SessionExt, SubtreeBound};
+pub use system::{decode_sys_services, system_info_diff, DeviceSnapshot, SystemInfo};
+pub use trap::{Trap, TrapListener, TRAP_PORT};
+pub use value::{
+    assemble_rows, decode_opaque_sequence, numeric_stats, octet_string_to_uint, opaque,
+    parse_hex_string_display, set_integer, set_ip, set_oid, set_opaque, set_string, type_histogram,
+    value_to_string, value_to_string_with_opts, NumericStats, OwnedValue, Row, ValueExt,
+    ValueStringOpts, Varbind,
+};
@@ -1,3 +1,10 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
+
+use super::dns::reverse_lookup;
+
 /// Network-related SNMP utility functions.
 ///
 /// Provides common conversions needed when working with SNMP data from
@@ -53,6 +60,57 @@ pub fn format_mac_dashed(bytes: &[u8]) -> String {
         .join("-")
 }
 
+/// Letter case and separator for the MACs returned by the table helpers.
+///
+/// The default is lowercase with colons (`aa:bb:cc:dd:ee:ff`), the same as
+/// [`format_mac`]. Pass a different style to the `*_with_mac_format` methods
+/// of `SessionExt` to get a whole result set in the style a downstream system
+/// expects.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::MacFormat;
+///
+/// let bytes = [0x00, 0x27, 0x22, 0xaa, 0xbb, 0xcc];
+/// assert_eq!(MacFormat::default().format(&bytes), "00:27:22:aa:bb:cc");
+/// let upper_dash = MacFormat { uppercase: true, separator: '-' };
+/// assert_eq!(upper_dash.format(&bytes), "00-27-22-AA-BB-CC");
+/// assert_eq!(upper_dash.reformat("00:27:22:aa:bb:cc"), "00-27-22-AA-BB-CC");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MacFormat {
+    pub uppercase: bool,
+    pub separator: char,
+}
+
+impl Default for MacFormat {
+    fn default() -> Self {
+        Self {
+            uppercase: false,
+            separator: ':',
+        }
+    }
+}
+
+impl MacFormat {
+    /// Format raw MAC bytes in this style.
+    pub fn format(&self, bytes: &[u8]) -> String {
+        self.reformat(&format_mac(bytes))
+    }
+
+    /// Restyle a MAC in the default style, as returned by [`format_mac`] and
+    /// [`parse_mac`].
+    pub fn reformat(&self, mac: &str) -> String {
+        mac.chars()
+            .map(|c| match c {
+                ':' => self.separator,
+                c if self.uppercase => c.to_ascii_uppercase(),
+                c => c,
+            })
+            .collect()
+    }
+}
+
 /// Try to parse a MAC address from SNMP response bytes.
 ///
 /// SNMP agents return MACs in different formats:
@@ -152,6 +210,249 @@ pub fn bits_to_miles(bits: u64) -> f64 {
     meters_to_miles(meters)
 }
 
+/// How `snr_to_quality` maps SNR (dB) to a 0–100 quality score.
+///
+/// All curves are linear between a floor (score 0) and a ceiling (score 100)
+/// and clamp outside that range; the presets only differ in those two points.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QualityCurve {
+    /// 0 at `min` dB, 100 at `max` dB.
+    Linear { min: f64, max: f64 },
+    /// Generic 802.11 rule of thumb: 10 dB barely associates, 40 dB and
+    /// above is excellent.
+    Wifi,
+    /// Ubiquiti airMAX: 10 dB for the lowest MCS up to 35 dB for the highest.
+    AirMax,
+    /// Cambium PMP 450 / ePMP: 8 dB for QPSK up to 32 dB for 256-QAM.
+    Cambium,
+}
+
+impl QualityCurve {
+    fn range(self) -> (f64, f64) {
+        match self {
+            QualityCurve::Linear { min, max } => (min, max),
+            QualityCurve::Wifi => (10.0, 40.0),
+            QualityCurve::AirMax => (10.0, 35.0),
+            QualityCurve::Cambium => (8.0, 32.0),
+        }
+    }
+}
+
+/// Convert an SNR reading to a 0–100 signal quality score for dashboards.
+///
+/// The score is rounded and clamped; a NaN reading scores 0. A degenerate
+/// `Linear` curve (`min >= max`) acts as a threshold at `max`.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{snr_to_quality, QualityCurve};
+///
+/// assert_eq!(snr_to_quality(25.0, QualityCurve::Wifi), 50);
+/// assert_eq!(snr_to_quality(20.0, QualityCurve::Linear { min: 0.0, max: 40.0 }), 50);
+/// assert_eq!(snr_to_quality(45.0, QualityCurve::Cambium), 100);
+/// ```
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn snr_to_quality(snr_db: f64, curve: QualityCurve) -> u8 {
+    let (min, max) = curve.range();
+    if snr_db.is_nan() {
+        return 0;
+    }
+    if min >= max {
+        return if snr_db >= max { 100 } else { 0 };
+    }
+    let score = (snr_db - min) / (max - min) * 100.0;
+    score.round().clamp(0.0, 100.0) as u8
+}
+
+/// Format a trap source address for logs.
+///
+/// Without `resolve` this is plain `ip:port`. With `resolve`, a reverse (PTR)
+/// lookup is sent to the first `nameserver` in `/etc/resolv.conf` and the
+/// host name is used when one comes back, falling back to the IP otherwise.
+///
+/// **Note:** resolution blocks the calling thread for up to 500 ms per call.
+/// Keep it off on hot paths such as a flooded trap receiver.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::format_source;
+///
+/// let addr = "10.56.27.13:162".parse().unwrap();
+/// assert_eq!(format_source(addr, false), "10.56.27.13:162");
+/// ```
+pub fn format_source(addr: SocketAddr, resolve: bool) -> String {
+    if resolve {
+        if let Some(name) = reverse_lookup(addr.ip(), Duration::from_millis(500)) {
+            return format!("{}:{}", name, addr.port());
+        }
+    }
+    addr.to_string()
+}
+
+/// Encode an address as an INET-ADDRESS-MIB `(InetAddressType, InetAddress)`
+/// pair, the two varbinds an address-typed column is SET with.
+///
+/// IPv4 gives type `ipv4(1)` and 4 bytes, IPv6 type `ipv6(2)` and 16 bytes.
+/// IPv4-mapped IPv6 addresses are kept as IPv6; convert them first if the
+/// agent expects IPv4.
+///
+/// # Examples
+/// ```
+/// use std::net::Ipv4Addr;
+/// use snmp2::helpers::encode_inet_address;
+///
+/// let (addr_type, addr) = encode_inet_address(Ipv4Addr::new(10, 0, 0, 1).into());
+/// assert_eq!((addr_type, addr), (1, vec![10, 0, 0, 1]));
+/// ```
+pub fn encode_inet_address(ip: IpAddr) -> (i64, Vec<u8>) {
+    match ip {
+        IpAddr::V4(v4) => (1, v4.octets().to_vec()),
+        IpAddr::V6(v6) => (2, v6.octets().to_vec()),
+    }
+}
+
+/// Decide whether a counter was reset (device or interface restart) between
+/// two samples, as opposed to having wrapped or simply increased.
+///
+/// Rate calculators should skip a sample pair flagged as a reset instead of
+/// computing a delta, which would otherwise show up as a huge spike.
+///
+/// The heuristic:
+/// - sysUpTime going backwards means the agent restarted, so every counter
+///   was reset, whatever the values say.
+/// - A counter that did not decrease was not reset.
+/// - A 64-bit counter that decreased was reset: wrapping one takes decades
+///   even at 100 Gbit/s.
+/// - A 32-bit counter that decreased was reset if the wrap would imply an
+///   increase of more than half the counter range (2^31). Otherwise it is
+///   taken to have wrapped once, which is common on busy links.
+///   Values that do not fit 32 bits count as a reset.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::counter_reset_detected;
+///
+/// // wrapped a little past 2^32
+/// assert!(!counter_reset_detected(4_294_966_000, 2_000, false, false));
+/// // the device rebooted
+/// assert!(counter_reset_detected(4_294_966_000, 2_000, false, true));
+/// assert!(counter_reset_detected(5_000_000, 1_200, false, false));
+/// ```
+pub fn counter_reset_detected(
+    previous: u64,
+    current: u64,
+    is_64bit: bool,
+    uptime_went_backwards: bool,
+) -> bool {
+    if uptime_went_backwards {
+        return true;
+    }
+    if current >= previous {
+        return false;
+    }
+    if is_64bit {
+        return true;
+    }
+    let (Ok(previous), Ok(current)) = (u32::try_from(previous), u32::try_from(current)) else {
+        return true;
+    };
+    current.wrapping_sub(previous) > 1 << 31
+}
+
+/// What `gauge_utilization` reports for a Gauge32 latched at its maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaturatedGauge {
+    /// Report full utilization, `Some(100.0)`.
+    Full,
+    /// Report no reading, `None`: the true value is somewhere above the
+    /// maximum and cannot be known.
+    Unknown,
+}
+
+/// Percentage of `capacity` that a Gauge32 reading represents, e.g. a
+/// current-throughput gauge against the link speed in the same unit.
+///
+/// Unlike counters, gauges do not wrap: a value too large for 32 bits
+/// latches at 4294967295 until it drops again. A reading at that maximum is
+/// therefore "at or above the maximum", not a value to do arithmetic with,
+/// and `saturated` decides what is reported for it. Do not compute deltas
+/// against a latched reading either; the drop from it to the next sample is
+/// not a real decrease.
+///
+/// Other readings are clamped to 100%. Returns `None` for a zero capacity.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{gauge_utilization, SaturatedGauge};
+///
+/// assert_eq!(gauge_utilization(250, 1000, SaturatedGauge::Unknown), Some(25.0));
+/// assert_eq!(gauge_utilization(u32::MAX, 10_000_000_000, SaturatedGauge::Full), Some(100.0));
+/// assert_eq!(gauge_utilization(u32::MAX, 10_000_000_000, SaturatedGauge::Unknown), None);
+/// ```
+pub fn gauge_utilization(value: u32, capacity: u64, saturated: SaturatedGauge) -> Option<f64> {
+    if capacity == 0 {
+        return None;
+    }
+    if value == u32::MAX {
+        return match saturated {
+            SaturatedGauge::Full => Some(100.0),
+            SaturatedGauge::Unknown => None,
+        };
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let percent = f64::from(value) / capacity as f64 * 100.0;
+    Some(percent.min(100.0))
+}
+
+/// Format a data rate in bits per second with SI (1000-based) units.
+///
+/// Picks the largest of bps/Kbps/Mbps/Gbps/Tbps that keeps the value at or
+/// above 1, with two decimal places.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::format_bps;
+///
+/// assert_eq!(format_bps(999.0), "999.00 bps");
+/// assert_eq!(format_bps(1_234_567_890.0), "1.23 Gbps");
+/// ```
+pub fn format_bps(bps: f64) -> String {
+    const UNITS: [&str; 5] = ["bps", "Kbps", "Mbps", "Gbps", "Tbps"];
+    let mut value = bps;
+    let mut unit = 0;
+    while value.abs() >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+/// Format a byte count with IEC (1024-based) units.
+///
+/// Plain byte counts are printed without decimals, larger units with two.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::format_bytes;
+///
+/// assert_eq!(format_bytes(1023), "1023 B");
+/// assert_eq!(format_bytes(1536), "1.50 KiB");
+/// ```
+pub fn format_bytes(n: u64) -> String {
+    const UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if n < 1024 {
+        return format!("{} B", n);
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let mut value = n as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,6 +470,41 @@ mod tests {
         assert_eq!(format_mac(&[]), "");
     }
 
+    #[test]
+    fn test_snr_to_quality_linear() {
+        let curve = QualityCurve::Linear {
+            min: 5.0,
+            max: 35.0,
+        };
+        assert_eq!(snr_to_quality(5.0, curve), 0);
+        assert_eq!(snr_to_quality(20.0, curve), 50);
+        assert_eq!(snr_to_quality(35.0, curve), 100);
+        assert_eq!(snr_to_quality(-3.0, curve), 0);
+        assert_eq!(snr_to_quality(60.0, curve), 100);
+        assert_eq!(snr_to_quality(f64::NAN, curve), 0);
+    }
+
+    #[test]
+    fn test_snr_to_quality_presets() {
+        assert_eq!(snr_to_quality(10.0, QualityCurve::Wifi), 0);
+        assert_eq!(snr_to_quality(25.0, QualityCurve::Wifi), 50);
+        assert_eq!(snr_to_quality(40.0, QualityCurve::Wifi), 100);
+        assert_eq!(snr_to_quality(22.5, QualityCurve::AirMax), 50);
+        assert_eq!(snr_to_quality(8.0, QualityCurve::Cambium), 0);
+        assert_eq!(snr_to_quality(20.0, QualityCurve::Cambium), 50);
+        assert_eq!(snr_to_quality(32.0, QualityCurve::Cambium), 100);
+    }
+
+    #[test]
+    fn test_snr_to_quality_degenerate_curve() {
+        let curve = QualityCurve::Linear {
+            min: 20.0,
+            max: 20.0,
+        };
+        assert_eq!(snr_to_quality(19.9, curve), 0);
+        assert_eq!(snr_to_quality(20.0, curve), 100);
+    }
+
     #[test]
     fn test_format_mac_dashed() {
         assert_eq!(
@@ -177,6 +513,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mac_format() {
+        let bytes = [0xaa, 0xbb, 0xcc, 0x01, 0x02, 0x03];
+        assert_eq!(MacFormat::default().format(&bytes), format_mac(&bytes));
+        let upper = MacFormat {
+            uppercase: true,
+            separator: ':',
+        };
+        assert_eq!(upper.format(&bytes), "AA:BB:CC:01:02:03");
+        let lower_dash = MacFormat {
+            uppercase: false,
+            separator: '-',
+        };
+        assert_eq!(lower_dash.format(&bytes), format_mac_dashed(&bytes));
+        assert_eq!(upper.format(&[]), "");
+    }
+
     #[test]
     fn test_parse_mac_raw_bytes() {
         assert_eq!(
@@ -238,4 +591,107 @@ mod tests {
         assert!(miles > 0.0);
         assert_eq!(bits_to_miles(0), 0.0);
     }
+
+    #[test]
+    fn test_counter_reset_reboot_with_uptime_reset() {
+        // counters and sysUpTime both restarted from zero
+        assert!(counter_reset_detected(5_000_000, 1_200, false, true));
+        assert!(counter_reset_detected(5_000_000, 1_200, true, true));
+        // rebooted and already counted past the old value
+        assert!(counter_reset_detected(100, 5_000, false, true));
+    }
+
+    #[test]
+    fn test_counter_reset_vs_wrap() {
+        assert!(!counter_reset_detected(1_000, 1_000, false, false));
+        assert!(!counter_reset_detected(1_000, 2_000, false, false));
+        assert!(!counter_reset_detected(
+            u64::from(u32::MAX) - 10,
+            5,
+            false,
+            false
+        ));
+        // small previous value: wrapping would mean ~2^32 octets in one interval
+        assert!(counter_reset_detected(3_000, 10, false, false));
+        // 64-bit counters do not wrap in practice
+        assert!(counter_reset_detected(u64::MAX - 10, 5, true, false));
+        assert!(!counter_reset_detected(10, u64::MAX, true, false));
+        // not a 32-bit value
+        assert!(counter_reset_detected(1 << 40, 5, false, false));
+    }
+
+    #[test]
+    fn test_gauge_utilization_pinned_at_max() {
+        // 10G link: a latched gauge is below the true rate, not 43% of it
+        let capacity = 10_000_000_000;
+        assert_eq!(
+            gauge_utilization(u32::MAX, capacity, SaturatedGauge::Full),
+            Some(100.0)
+        );
+        assert_eq!(
+            gauge_utilization(u32::MAX, capacity, SaturatedGauge::Unknown),
+            None
+        );
+        let below = gauge_utilization(u32::MAX - 1, capacity, SaturatedGauge::Unknown).unwrap();
+        assert!((below - 42.95).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_gauge_utilization_clamps() {
+        assert_eq!(
+            gauge_utilization(500, 1000, SaturatedGauge::Full),
+            Some(50.0)
+        );
+        assert_eq!(
+            gauge_utilization(1500, 1000, SaturatedGauge::Full),
+            Some(100.0)
+        );
+        assert_eq!(gauge_utilization(0, 1000, SaturatedGauge::Full), Some(0.0));
+        assert_eq!(gauge_utilization(5, 0, SaturatedGauge::Full), None);
+    }
+
+    #[test]
+    fn test_format_bps_boundaries() {
+        assert_eq!(format_bps(0.0), "0.00 bps");
+        assert_eq!(format_bps(999.0), "999.00 bps");
+        assert_eq!(format_bps(1000.0), "1.00 Kbps");
+        assert_eq!(format_bps(1_500_000.0), "1.50 Mbps");
+        assert_eq!(format_bps(1_234_567_890.0), "1.23 Gbps");
+        assert_eq!(format_bps(2e12), "2.00 Tbps");
+        assert_eq!(format_bps(5e15), "5000.00 Tbps");
+    }
+
+    #[test]
+    fn test_format_bytes_boundaries() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.00 KiB");
+        assert_eq!(format_bytes(1024 * 1024), "1.00 MiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.00 GiB");
+        assert_eq!(format_bytes(u64::MAX), "16.00 EiB");
+    }
+
+    #[test]
+    fn test_encode_inet_address() {
+        let (addr_type, bytes) = encode_inet_address("192.168.1.20".parse().unwrap());
+        assert_eq!(addr_type, 1);
+        assert_eq!(bytes, [192, 168, 1, 20]);
+
+        let (addr_type, bytes) = encode_inet_address("2001:db8::1".parse().unwrap());
+        assert_eq!(addr_type, 2);
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(bytes[..4], [0x20, 0x01, 0x0d, 0xb8]);
+        assert_eq!(bytes[15], 1);
+
+        let (addr_type, bytes) = encode_inet_address("::ffff:10.0.0.1".parse().unwrap());
+        assert_eq!((addr_type, bytes.len()), (2, 16));
+    }
+
+    #[test]
+    fn test_format_source_without_resolving() {
+        let v4: SocketAddr = "10.56.27.13:162".parse().unwrap();
+        assert_eq!(format_source(v4, false), "10.56.27.13:162");
+        let v6: SocketAddr = "[fe80::1]:162".parse().unwrap();
+        assert_eq!(format_source(v6, false), "[fe80::1]:162");
+    }
 }
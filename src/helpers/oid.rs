@@ -1,9 +1,26 @@
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    num::{IntErrorKind, ParseIntError},
+};
+
 use crate::{Error, Oid, Result};
 
+use super::session::is_subtree;
+
 /// Parse an OID from dot-notation string (e.g., "1.3.6.1.2.1.1.1.0" or ".1.3.6.1.2.1.1.1.0")
 ///
 /// Leading dots are optional and will be stripped.
 ///
+/// `Oid` is re-exported from `asn1-rs`, which already implements `FromStr`
+/// for it, so `"1.3.6.1".parse::<Oid>()` works too (the orphan rule rules out
+/// a second impl here). That impl rejects a leading dot and reports errors
+/// as `asn1_rs::OidParseError`; use `parse_oid` for those cases.
+///
+/// Sub-identifiers are 64-bit: values above `u32::MAX` are legal in BER and
+/// accepted, values above `u64::MAX` are rejected with `Error::OidArcTooLarge`.
+/// `oid_from_arcs` and `oid_to_arcs` follow the same policy.
+///
 /// # Examples
 /// ```
 /// use snmp2::helpers::parse_oid;
@@ -15,39 +32,559 @@ use crate::{Error, Oid, Result};
 /// ```
 ///
 /// # Errors
-/// Returns `Error::AsnParse` if the string contains non-numeric components
+/// Returns `Error::OidComponent` if the string contains non-numeric components;
+/// its `source()` is the underlying `ParseIntError`. Returns
+/// `Error::OidArcTooLarge` for a component above `u64::MAX`, and
+/// `Error::ValueOutOfRange` for invalid leading arcs (see `oid_from_arcs`).
 pub fn parse_oid(s: &str) -> Result<Oid<'static>> {
-    let parts: Vec<u32> = s
+    let parts: Vec<u64> = s
         .trim_start_matches('.')
         .split('.')
-        .map(|p| p.parse().map_err(|_| Error::AsnParse))
+        .map(|p| {
+            p.parse().map_err(|e: ParseIntError| match e.kind() {
+                IntErrorKind::PosOverflow => Error::OidArcTooLarge,
+                _ => Error::OidComponent(e),
+            })
+        })
         .collect::<Result<Vec<_>>>()?;
 
-    Oid::from(&parts[..])
+    oid_from_arcs(&parts)
+}
+
+/// Re-emit an OID string in canonical dotted numeric form.
+///
+/// Accepts the spellings tools mix in configs: `"1.3.6.1"`, `".1.3.6.1"` and
+/// net-snmp's `"iso.3.6.1"` (the `iso` keyword stands for arc 1). Use it to
+/// compare or de-duplicate OIDs stored as strings.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::normalize_oid_str;
+///
+/// assert_eq!(normalize_oid_str("iso.3.6.1.2.1.1.5.0")?, "1.3.6.1.2.1.1.5.0");
+/// assert_eq!(normalize_oid_str(".1.3.6.1.2.1.1.5.0")?, "1.3.6.1.2.1.1.5.0");
+/// # Ok::<(), snmp2::Error>(())
+/// ```
+///
+/// # Errors
+/// Same as [`parse_oid`].
+pub fn normalize_oid_str(s: &str) -> Result<String> {
+    Ok(parse_oid_any(s)?.to_string())
+}
+
+/// `parse_oid`, also accepting a leading `iso` keyword for arc 1.
+fn parse_oid_any(s: &str) -> Result<Oid<'static>> {
+    let trimmed = s.trim_start_matches('.');
+    match trimmed.strip_prefix("iso") {
+        Some(rest) if rest.is_empty() || rest.starts_with('.') => parse_oid(&format!("1{}", rest)),
+        _ => parse_oid(trimmed),
+    }
+}
+
+/// Parse one line of saved `snmpwalk` output into `(oid, type, value)`.
+///
+/// Expects the numeric form printed with `-On`, `.1.3.6.1.2.1.1.5.0 =
+/// STRING: "ap-01"`; `iso.3.6...` prefixes are accepted as well. The line is
+/// split only at the first ` = ` and the first `: ` after it, so values that
+/// contain either are kept whole, quotes included. Lines without a type tag
+/// (e.g. `= ""` or `= No Such Object available ...`) give an empty type and
+/// the whole right-hand side as the value.
+///
+/// Returns `None` for lines that are not `OID = ...` with a numeric OID,
+/// such as the continuation lines of a multi-line Hex-STRING.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::parse_walk_line};
+///
+/// let line = r#".1.3.6.1.2.1.1.1.0 = STRING: "Linux 5.4: ap""#;
+/// let (oid, tag, value) = parse_walk_line(line).unwrap();
+/// assert_eq!(oid, oid!("1.3.6.1.2.1.1.1.0"));
+/// assert_eq!(tag, "STRING");
+/// assert_eq!(value, r#""Linux 5.4: ap""#);
+/// ```
+pub fn parse_walk_line(line: &str) -> Option<(Oid<'static>, String, String)> {
+    let (oid, rest) = line.trim_end().split_once(" = ")?;
+    let oid = parse_oid_any(oid.trim()).ok()?;
+    let (tag, value) = match rest.split_once(": ") {
+        Some((tag, value))
+            if !tag.is_empty() && !tag.contains(|c: char| c.is_whitespace() || c == '"') =>
+        {
+            (tag, value)
+        }
+        _ => ("", rest),
+    };
+    Some((oid, tag.to_string(), value.to_string()))
+}
+
+/// Build an OID from numeric arcs.
+///
+/// # Errors
+/// Returns `Error::ValueOutOfRange` if there are fewer than two arcs, or the
+/// first two arcs cannot be packed into one sub-identifier (first arc 7 or
+/// more, second 40 or more).
+pub fn oid_from_arcs(arcs: &[u64]) -> Result<Oid<'static>> {
+    Oid::from(arcs).map_err(|_| Error::ValueOutOfRange)
+}
+
+/// Split an OID into its numeric arcs.
+///
+/// # Errors
+/// Returns `Error::OidArcTooLarge` if a sub-identifier received from an agent
+/// does not fit in a `u64`.
+pub fn oid_to_arcs(oid: &Oid) -> Result<Vec<u64>> {
+    oid.iter()
+        .map(Iterator::collect)
+        .ok_or(Error::OidArcTooLarge)
+}
+
+/// The smallest OID strictly greater than `oid`: `oid` with `.0` appended.
+///
+/// Every descendant of `oid` sorts after it, and `.0` is the first of them,
+/// so this is where a manual GETNEXT emulation or an inclusive walk resumes.
+/// Compare [`successor`], which skips the whole subtree.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::next_oid};
+///
+/// assert_eq!(next_oid(&oid!("1.3.6.1.2.1.1.1")), oid!("1.3.6.1.2.1.1.1.0"));
+/// ```
+pub fn next_oid(oid: &Oid) -> Oid<'static> {
+    let mut arcs = oid_arcs(oid);
+    arcs.push(0);
+    oid_from_arcs(&arcs).unwrap_or_else(|_| oid.to_owned())
+}
+
+/// The next sibling of `oid`: its last arc incremented.
+///
+/// This is the smallest OID after everything in the `oid` subtree, so a
+/// GETNEXT from it skips the subtree entirely; use it to step past a table
+/// column, while [`next_oid`] descends into it. A last arc that cannot be
+/// incremented (`u64::MAX`, or a second arc of 39, see [`oid_from_arcs`]) is
+/// dropped and its parent incremented instead. An OID with no such ancestor
+/// is returned unchanged.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::successor};
+///
+/// // ifDescr column -> ifType column
+/// assert_eq!(successor(&oid!("1.3.6.1.2.1.2.2.1.2")), oid!("1.3.6.1.2.1.2.2.1.3"));
+/// ```
+pub fn successor(oid: &Oid) -> Oid<'static> {
+    let mut arcs = oid_arcs(oid);
+    while let Some(last) = arcs.pop() {
+        if let Some(next) = last.checked_add(1) {
+            arcs.push(next);
+            if let Ok(successor) = oid_from_arcs(&arcs) {
+                return successor;
+            }
+            arcs.pop();
+        }
+    }
+    oid.to_owned()
+}
+
+/// `enterprises` (1.3.6.1.4.1), the root of vendor MIBs.
+const ENTERPRISES: &[u64] = &[1, 3, 6, 1, 4, 1];
+
+/// Return the IANA private enterprise number of an OID under
+/// `enterprises` (1.3.6.1.4.1), such as a `sysObjectID` value or the
+/// enterprise field of a v1 trap.
+///
+/// Returns `None` if `oid` is not below `enterprises` or the number does not
+/// fit in a `u32`.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::enterprise_number};
+///
+/// // Cambium Networks cnPilot
+/// assert_eq!(enterprise_number(&oid!("1.3.6.1.4.1.17713.22")), Some(17713));
+/// assert_eq!(enterprise_number(&oid!("1.3.6.1.2.1.1.2.0")), None);
+/// ```
+pub fn enterprise_number(oid: &Oid) -> Option<u32> {
+    let arcs = oid_arcs(oid);
+    if !arcs.starts_with(ENTERPRISES) {
+        return None;
+    }
+    arcs.get(ENTERPRISES.len())
+        .and_then(|&number| u32::try_from(number).ok())
+}
+
+/// Decode an IP address from the arcs of a table index.
+///
+/// Four arcs give an IPv4 address (as in `IpAddress` indexes such as
+/// `ipNetToMediaTable`), sixteen an IPv6 address. For length-prefixed
+/// `InetAddress` indexes, strip the length arc first. Returns `None` for any
+/// other length or an arc above 255.
+///
+/// # Examples
+/// ```
+/// use std::net::Ipv4Addr;
+/// use snmp2::helpers::index_to_ip;
+///
+/// assert_eq!(index_to_ip(&[10, 0, 0, 1]), Some(Ipv4Addr::new(10, 0, 0, 1).into()));
+/// assert_eq!(index_to_ip(&[10, 0, 0]), None);
+/// ```
+pub fn index_to_ip(arcs: &[u64]) -> Option<IpAddr> {
+    let bytes = arcs
+        .iter()
+        .map(|&arc| u8::try_from(arc).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    match bytes.len() {
+        4 => Some(IpAddr::V4(Ipv4Addr::new(
+            bytes[0], bytes[1], bytes[2], bytes[3],
+        ))),
+        16 => {
+            let mut octets = [0u8; 16];
+            octets.copy_from_slice(&bytes);
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        }
+        _ => None,
+    }
+}
+
+/// Append a string table index to `base`, giving the instance OID of a row.
+///
+/// Follows the SMIv2 rule for variable-length OCTET STRING indexes: the
+/// length followed by one arc per byte. Columns declared with an `IMPLIED`
+/// index omit the length and are not covered by this helper.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::append_string_index};
+///
+/// let column = oid!("1.3.6.1.4.1.9999.1.2");
+/// let instance = append_string_index(&column, "eth0")?;
+/// assert_eq!(instance, oid!("1.3.6.1.4.1.9999.1.2.4.101.116.104.48"));
+/// # Ok::<(), snmp2::Error>(())
+/// ```
+///
+/// # Errors
+/// Returns `Error::ValueOutOfRange` if `base` is not a valid OID prefix.
+pub fn append_string_index(base: &Oid, s: &str) -> Result<Oid<'static>> {
+    let mut arcs = oid_arcs(base);
+    arcs.push(s.len() as u64);
+    arcs.extend(s.bytes().map(u64::from));
+    oid_from_arcs(&arcs)
+}
+
+/// Compare two OID sets, e.g. the indexes of two polls of the same table.
+///
+/// Returns `(added, removed)`: OIDs only in `new`, and OIDs only in `old`,
+/// each in the order they appear in their input. Order between the inputs does
+/// not matter, only membership.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::oid_diff};
+///
+/// let old = [oid!("1.3.6.1.2.1.4.22.1.2.1.10.0.0.1"), oid!("1.3.6.1.2.1.4.22.1.2.1.10.0.0.2")];
+/// let new = [oid!("1.3.6.1.2.1.4.22.1.2.1.10.0.0.3"), oid!("1.3.6.1.2.1.4.22.1.2.1.10.0.0.1")];
+///
+/// let (added, removed) = oid_diff(&old, &new);
+/// assert_eq!(added, vec![oid!("1.3.6.1.2.1.4.22.1.2.1.10.0.0.3")]);
+/// assert_eq!(removed, vec![oid!("1.3.6.1.2.1.4.22.1.2.1.10.0.0.2")]);
+/// ```
+pub fn oid_diff(old: &[Oid], new: &[Oid]) -> (Vec<Oid<'static>>, Vec<Oid<'static>>) {
+    let old_arcs: HashSet<Vec<u64>> = old.iter().map(oid_arcs).collect();
+    let new_arcs: HashSet<Vec<u64>> = new.iter().map(oid_arcs).collect();
+    let added = new
+        .iter()
+        .filter(|oid| !old_arcs.contains(&oid_arcs(oid)))
+        .map(Oid::to_owned)
+        .collect();
+    let removed = old
+        .iter()
+        .filter(|oid| !new_arcs.contains(&oid_arcs(oid)))
+        .map(Oid::to_owned)
+        .collect();
+    (added, removed)
+}
+
+/// Differences between two walks of the same subtree, from `walk_diff`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WalkDiff {
+    /// Rows only in the new walk, in its order.
+    pub added: Vec<(Oid<'static>, String)>,
+    /// Rows only in the old walk, in its order.
+    pub removed: Vec<(Oid<'static>, String)>,
+    /// Rows in both walks with different values: (OID, old value, new
+    /// value), in the order of the new walk.
+    pub changed: Vec<(Oid<'static>, String, String)>,
+}
+
+impl WalkDiff {
+    /// True if the walks had the same rows with the same values.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare two walks of the same subtree, e.g. snapshots of a device's
+/// configuration taken a day apart, matching rows by OID.
+///
+/// Row order does not matter. If an OID appears more than once in a walk,
+/// its last value counts.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::walk_diff};
+///
+/// let old = vec![
+///     (oid!("1.3.6.1.2.1.1.5.0"), "ap-01".to_string()),
+///     (oid!("1.3.6.1.2.1.1.6.0"), "Tower 3".to_string()),
+/// ];
+/// let new = vec![
+///     (oid!("1.3.6.1.2.1.1.5.0"), "ap-01".to_string()),
+///     (oid!("1.3.6.1.2.1.1.6.0"), "Tower 4".to_string()),
+/// ];
+/// let diff = walk_diff(&old, &new);
+/// assert!(diff.added.is_empty() && diff.removed.is_empty());
+/// assert_eq!(
+///     diff.changed,
+///     vec![(oid!("1.3.6.1.2.1.1.6.0"), "Tower 3".to_string(), "Tower 4".to_string())]
+/// );
+/// ```
+pub fn walk_diff(old: &[(Oid<'static>, String)], new: &[(Oid<'static>, String)]) -> WalkDiff {
+    let old_values: HashMap<Vec<u64>, &String> = old
+        .iter()
+        .map(|(oid, value)| (oid_arcs(oid), value))
+        .collect();
+    let new_arcs: HashSet<Vec<u64>> = new.iter().map(|(oid, _)| oid_arcs(oid)).collect();
+    let mut diff = WalkDiff::default();
+    for (oid, value) in new {
+        match old_values.get(&oid_arcs(oid)) {
+            None => diff.added.push((oid.clone(), value.clone())),
+            Some(&old_value) if old_value != value => {
+                diff.changed
+                    .push((oid.clone(), old_value.clone(), value.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|(oid, _)| !new_arcs.contains(&oid_arcs(oid)))
+        .cloned()
+        .collect();
+    diff
+}
+
+/// Split `oids` into consecutive chunks of at most `chunk_size` OIDs.
+///
+/// Unlike the size-based packing in `SnmpClient::get_many`, the number of
+/// chunks depends only on the count: `oids.len() / chunk_size`, rounded up.
+/// A `chunk_size` of 0 is treated as 1.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{chunk_oids, oid_from_arcs};
+///
+/// let oids = (1..=5)
+///     .map(|i| oid_from_arcs(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 10, i]))
+///     .collect::<Result<Vec<_>, _>>()?;
+/// let refs: Vec<_> = oids.iter().collect();
+/// let chunks = chunk_oids(&refs, 2);
+/// assert_eq!(chunks.iter().map(|c| c.len()).collect::<Vec<_>>(), [2, 2, 1]);
+/// # Ok::<(), snmp2::Error>(())
+/// ```
+pub fn chunk_oids<'a, 'b>(oids: &'a [&'b Oid<'b>], chunk_size: usize) -> Vec<&'a [&'b Oid<'b>]> {
+    oids.chunks(chunk_size.max(1)).collect()
+}
+
+/// Split an OID into its numeric arcs for ordering and comparison.
+///
+/// Comparing the resulting vectors orders OIDs the way agents do (arc by
+/// arc), unlike comparing their dotted strings. An OID with an arc above
+/// `u64::MAX` yields an empty vector and so sorts first; use `oid_to_arcs`
+/// where that case must be reported.
+pub(super) fn oid_arcs(oid: &Oid) -> Vec<u64> {
+    oid_to_arcs(oid).unwrap_or_default()
 }
 
 /// Convenience macro for compile-time-checked OID creation.
 ///
-/// Panics at runtime if the OID string is malformed (intended for use with
-/// static OID strings that are known at compile time).
+/// Accepts either a dotted string or a list of numeric arcs.
+///
+/// With a string, panics at runtime if the OID is malformed (intended for use
+/// with static OID strings that are known at compile time).
+///
+/// With arcs, every arc must be a constant expression and the first two arcs
+/// are checked at compile time (at least two arcs, first below 7, second below
+/// 40), so the expansion cannot panic.
 ///
 /// # Examples
 /// ```
-/// use snmp2::oid;
+/// use snmp2::{oid, parse_oid};
 ///
 /// let sys_descr = oid!("1.3.6.1.2.1.1.1.0");
 /// let ubnt_freq = oid!(".1.3.6.1.4.1.41112.1.4.1.1.4");
+///
+/// let from_arcs = oid!(1, 3, 6, 1, 2, 1, 1, 1, 0);
+/// assert_eq!(from_arcs, sys_descr);
+/// assert_eq!(from_arcs, parse_oid("1.3.6.1.2.1.1.1.0")?);
+/// # Ok::<(), snmp2::Error>(())
+/// ```
+///
+/// Out-of-range leading arcs are rejected by the compiler:
+/// ```compile_fail
+/// let bad = snmp2::oid!(1, 40, 1);
 /// ```
 #[macro_export]
 macro_rules! oid {
     ($s:expr) => {
         $crate::helpers::parse_oid($s).expect(concat!("invalid OID: ", $s))
     };
+    ($first:expr, $($arc:expr),+ $(,)?) => {{
+        const ARCS: &[u64] = &[$first, $($arc),+];
+        const _: () = assert!(
+            ARCS[0] < 7 && ARCS[1] < 40,
+            "invalid OID: first arc must be below 7 and second below 40"
+        );
+        $crate::Oid::from(ARCS).expect("OID arcs validated at compile time")
+    }};
+}
+
+/// Render `full` relative to `base` for compact logging.
+///
+/// Returns the suffix after `base` prefixed with `+` (e.g. `"+.10.3"`) when
+/// `full` is a descendant of `base`, `"+"` when both are equal, or `None`
+/// when `full` lies outside the `base` subtree.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::relative_to};
+///
+/// let if_table = oid!("1.3.6.1.2.1.2.2");
+/// let cell = oid!("1.3.6.1.2.1.2.2.1.10.3");
+/// assert_eq!(relative_to(&if_table, &cell), Some("+.1.10.3".to_string()));
+/// assert_eq!(relative_to(&cell, &if_table), None);
+/// ```
+pub fn relative_to(base: &Oid, full: &Oid) -> Option<String> {
+    let base_str = base.to_string();
+    let full_str = full.to_string();
+    if !is_subtree(&base_str, &full_str) {
+        return None;
+    }
+    Some(format!("+{}", &full_str[base_str.len()..]))
+}
+
+/// Replace the OID of each walk row with its arcs below `base`.
+///
+/// Rows outside the `base` subtree, and rows with an arc beyond `u32`, are
+/// skipped. A row at `base` itself gets an empty suffix.
+///
+/// # Examples
+/// ```no_run
+/// use snmp2::{oid, SyncSession, helpers::{strip_prefix, SessionExt}};
+///
+/// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+/// let if_descr = oid!("1.3.6.1.2.1.2.2.1.2");
+/// let rows = session.walk_values(&if_descr)?;
+/// let rows = rows.into_iter().map(|(oid, value)| (oid, value.to_string_lossy()));
+/// for (index, descr) in strip_prefix(&if_descr, rows) {
+///     println!("ifIndex {:?}: {}", index, descr);
+/// }
+/// # Ok::<(), snmp2::Error>(())
+/// ```
+pub fn strip_prefix<'a>(
+    base: &'a Oid,
+    rows: impl Iterator<Item = (Oid<'static>, String)> + 'a,
+) -> impl Iterator<Item = (Vec<u32>, String)> + 'a {
+    let base_arcs = oid_arcs(base);
+    rows.filter_map(move |(oid, value)| {
+        let arcs = oid_arcs(&oid);
+        let suffix = arcs.strip_prefix(base_arcs.as_slice())?;
+        let suffix = suffix
+            .iter()
+            .map(|&arc| u32::try_from(arc).ok())
+            .collect::<Option<Vec<u32>>>()?;
+        Some((suffix, value))
+    })
+}
+
+/// Symbolic names `oid_to_name` knows without loading any MIB.
+const WELL_KNOWN: &[(&str, &[u64])] = &[
+    ("mgmt", &[1, 3, 6, 1, 2]),
+    ("mib-2", &[1, 3, 6, 1, 2, 1]),
+    ("system", &[1, 3, 6, 1, 2, 1, 1]),
+    ("sysDescr", &[1, 3, 6, 1, 2, 1, 1, 1]),
+    ("sysObjectID", &[1, 3, 6, 1, 2, 1, 1, 2]),
+    ("sysUpTime", &[1, 3, 6, 1, 2, 1, 1, 3]),
+    ("sysContact", &[1, 3, 6, 1, 2, 1, 1, 4]),
+    ("sysName", &[1, 3, 6, 1, 2, 1, 1, 5]),
+    ("sysLocation", &[1, 3, 6, 1, 2, 1, 1, 6]),
+    ("sysServices", &[1, 3, 6, 1, 2, 1, 1, 7]),
+    ("interfaces", &[1, 3, 6, 1, 2, 1, 2]),
+    ("ifNumber", &[1, 3, 6, 1, 2, 1, 2, 1]),
+    ("ifTable", &[1, 3, 6, 1, 2, 1, 2, 2]),
+    ("ifEntry", &[1, 3, 6, 1, 2, 1, 2, 2, 1]),
+    ("ifIndex", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 1]),
+    ("ifDescr", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 2]),
+    ("ifType", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 3]),
+    ("ifMtu", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 4]),
+    ("ifSpeed", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 5]),
+    ("ifPhysAddress", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 6]),
+    ("ifAdminStatus", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 7]),
+    ("ifOperStatus", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 8]),
+    ("ifInOctets", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 10]),
+    ("ifInErrors", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 14]),
+    ("ifOutOctets", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 16]),
+    ("ifOutErrors", &[1, 3, 6, 1, 2, 1, 2, 2, 1, 20]),
+    ("ip", &[1, 3, 6, 1, 2, 1, 4]),
+    ("ipNetToMediaTable", &[1, 3, 6, 1, 2, 1, 4, 22]),
+    ("dot1dBridge", &[1, 3, 6, 1, 2, 1, 17]),
+    ("dot1dTpFdbTable", &[1, 3, 6, 1, 2, 1, 17, 4, 3]),
+    ("ifXTable", &[1, 3, 6, 1, 2, 1, 31, 1, 1]),
+    ("ifName", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 1]),
+    ("ifHCInOctets", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6]),
+    ("ifHCOutOctets", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 10]),
+    ("ifHighSpeed", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 15]),
+    ("ifAlias", &[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 18]),
+    ("entPhysicalTable", &[1, 3, 6, 1, 2, 1, 47, 1, 1, 1]),
+    ("enterprises", ENTERPRISES),
+    ("snmpTrapOID", &[1, 3, 6, 1, 6, 3, 1, 1, 4, 1]),
+];
+
+/// Render `oid` with the longest matching prefix from a small built-in table
+/// of well-known objects, e.g. `sysDescr.0` or `ifInOctets.3`.
+///
+/// The table covers the system and interfaces groups, `ifXTable` and a few
+/// roots such as `enterprises`; OIDs outside it are returned in numeric form.
+/// For full MIB resolution use `MibConversion` (`mibs` feature).
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::oid_to_name};
+///
+/// assert_eq!(oid_to_name(&oid!("1.3.6.1.2.1.1.1.0")), "sysDescr.0");
+/// assert_eq!(oid_to_name(&oid!("1.3.6.1.4.1.41112.1.4")), "enterprises.41112.1.4");
+/// assert_eq!(oid_to_name(&oid!("2.5.4.3")), "2.5.4.3");
+/// ```
+pub fn oid_to_name(oid: &Oid) -> String {
+    let arcs = oid_arcs(oid);
+    let best = WELL_KNOWN
+        .iter()
+        .filter(|(_, prefix)| arcs.starts_with(prefix))
+        .max_by_key(|(_, prefix)| prefix.len());
+    let Some((name, prefix)) = best else {
+        return oid.to_string();
+    };
+    let mut rendered = name.to_string();
+    for arc in &arcs[prefix.len()..] {
+        rendered.push('.');
+        rendered.push_str(&arc.to_string());
+    }
+    rendered
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::mock::if_in_octets;
 
     #[test]
     fn test_parse_oid() {
@@ -67,6 +604,232 @@ mod tests {
         assert!(parse_oid("").is_err());
     }
 
+    #[test]
+    fn test_parse_oid_error_source() {
+        use std::error::Error as _;
+
+        let err = parse_oid("1.3.x").unwrap_err();
+        assert!(matches!(err, Error::OidComponent(_)));
+        let source = err.source().expect("source should be present");
+        assert!(source.is::<std::num::ParseIntError>());
+        assert_eq!(source.to_string(), "invalid digit found in string");
+    }
+
+    #[test]
+    fn test_str_parse_matches_parse_oid() {
+        let parsed: Oid = "1.3.6.1.2.1.1.1.0".parse().unwrap();
+        assert_eq!(parsed, parse_oid("1.3.6.1.2.1.1.1.0").unwrap());
+        assert_eq!(parsed, parse_oid(".1.3.6.1.2.1.1.1.0").unwrap());
+        // only parse_oid strips a leading dot
+        assert!(".1.3.6.1.2.1.1.1.0".parse::<Oid>().is_err());
+        assert!("1.3.x".parse::<Oid>().is_err());
+    }
+
+    #[test]
+    fn test_parse_oid_arc_above_u32() {
+        let oid = parse_oid("1.3.6.1.4.1.4294967296.1").unwrap();
+        assert_eq!(oid.to_string(), "1.3.6.1.4.1.4294967296.1");
+        assert_eq!(
+            oid_to_arcs(&oid).unwrap(),
+            vec![1, 3, 6, 1, 4, 1, 4_294_967_296, 1]
+        );
+        assert_eq!(
+            oid_from_arcs(&[1, 3, 6, 1, 4, 1, 4_294_967_296, 1]).unwrap(),
+            oid
+        );
+    }
+
+    #[test]
+    fn test_parse_oid_arc_above_u64() {
+        assert_eq!(
+            parse_oid("1.3.6.1.18446744073709551616").unwrap_err(),
+            Error::OidArcTooLarge
+        );
+        assert!(parse_oid("1.3.6.1.18446744073709551615").is_ok());
+    }
+
+    #[test]
+    fn test_oid_to_arcs_arc_above_u64() {
+        // 1.3.(2^70), as an agent could legally encode it
+        let bytes = [
+            0x2b, 0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00,
+        ];
+        let oid = Oid::new(std::borrow::Cow::Borrowed(&bytes[..]));
+        assert_eq!(oid_to_arcs(&oid).unwrap_err(), Error::OidArcTooLarge);
+        assert!(oid_arcs(&oid).is_empty());
+    }
+
+    #[test]
+    fn test_normalize_oid_str_iso_prefix() {
+        assert_eq!(
+            normalize_oid_str("iso.3.6.1.2.1.1.5.0").unwrap(),
+            "1.3.6.1.2.1.1.5.0"
+        );
+        assert_eq!(normalize_oid_str(".iso.3.6.1").unwrap(), "1.3.6.1");
+        assert!(normalize_oid_str("isotope.3.6.1").is_err());
+    }
+
+    #[test]
+    fn test_normalize_oid_str_leading_dot() {
+        assert_eq!(
+            normalize_oid_str(".1.3.6.1.2.1.1.5.0").unwrap(),
+            "1.3.6.1.2.1.1.5.0"
+        );
+    }
+
+    #[test]
+    fn test_normalize_oid_str_canonical() {
+        assert_eq!(
+            normalize_oid_str("1.3.6.1.4.1.41112.1.4").unwrap(),
+            "1.3.6.1.4.1.41112.1.4"
+        );
+        assert!(normalize_oid_str("1.3.x").is_err());
+    }
+
+    #[test]
+    fn test_parse_walk_line_string_with_colons() {
+        let (oid, tag, value) =
+            parse_walk_line(".1.3.6.1.2.1.1.6.0 = STRING: \"Tower 3: sector = B\"\n").unwrap();
+        assert_eq!(oid, oid!("1.3.6.1.2.1.1.6.0"));
+        assert_eq!(tag, "STRING");
+        assert_eq!(value, "\"Tower 3: sector = B\"");
+    }
+
+    #[test]
+    fn test_parse_walk_line_hex_string() {
+        let (oid, tag, value) =
+            parse_walk_line(".1.3.6.1.2.1.2.2.1.6.2 = Hex-STRING: 00 27 22 AA BB CC ").unwrap();
+        assert_eq!(oid, oid!("1.3.6.1.2.1.2.2.1.6.2"));
+        assert_eq!(tag, "Hex-STRING");
+        assert_eq!(value, "00 27 22 AA BB CC");
+    }
+
+    #[test]
+    fn test_parse_walk_line_other_forms() {
+        let (oid, tag, value) =
+            parse_walk_line("iso.3.6.1.2.1.1.3.0 = Timeticks: (4200) 0:00:42.00").unwrap();
+        assert_eq!(oid, oid!("1.3.6.1.2.1.1.3.0"));
+        assert_eq!(tag, "Timeticks");
+        assert_eq!(value, "(4200) 0:00:42.00");
+
+        let (_, tag, value) = parse_walk_line(".1.3.6.1.2.1.1.4.0 = \"\"").unwrap();
+        assert_eq!(tag, "");
+        assert_eq!(value, "\"\"");
+
+        assert!(parse_walk_line("DD EE FF 00").is_none());
+        assert!(parse_walk_line("SNMPv2-MIB::sysName.0 = STRING: ap-01").is_none());
+    }
+
+    #[test]
+    fn test_oid_from_arcs_invalid_prefix() {
+        assert_eq!(
+            oid_from_arcs(&[1, 40, 1]).unwrap_err(),
+            Error::ValueOutOfRange
+        );
+        assert_eq!(oid_from_arcs(&[7, 1]).unwrap_err(), Error::ValueOutOfRange);
+    }
+
+    #[test]
+    fn test_enterprise_number() {
+        // Cambium ePMP sysObjectID
+        assert_eq!(
+            enterprise_number(&oid!("1.3.6.1.4.1.17713.21.9.1")),
+            Some(17713)
+        );
+        assert_eq!(enterprise_number(&oid!("1.3.6.1.4.1.41112")), Some(41112));
+    }
+
+    #[test]
+    fn test_enterprise_number_outside_enterprises() {
+        assert_eq!(enterprise_number(&oid!("1.3.6.1.2.1.2.2.1.2")), None);
+        assert_eq!(enterprise_number(&oid!("1.3.6.1.4.1")), None);
+        assert_eq!(enterprise_number(&oid!("1.3.6.1.4.2.9")), None);
+        assert_eq!(enterprise_number(&oid!("1.3.6.1.4.1.4294967296")), None);
+    }
+
+    #[test]
+    fn test_index_to_ip() {
+        assert_eq!(
+            index_to_ip(&[192, 168, 1, 20]),
+            Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)))
+        );
+        let mut v6 = vec![0xfe, 0x80];
+        v6.extend([0; 13]);
+        v6.push(1);
+        assert_eq!(index_to_ip(&v6), Some("fe80::1".parse().unwrap()));
+        assert_eq!(index_to_ip(&[10, 0, 0, 256]), None);
+        assert_eq!(index_to_ip(&[4, 10, 0, 0, 1]), None);
+    }
+
+    #[test]
+    fn test_append_string_index() {
+        let if_name = oid!("1.3.6.1.2.1.31.1.1.1.1");
+        assert_eq!(
+            append_string_index(&if_name, "eth0").unwrap().to_string(),
+            "1.3.6.1.2.1.31.1.1.1.1.4.101.116.104.48"
+        );
+        assert_eq!(
+            append_string_index(&if_name, "").unwrap().to_string(),
+            "1.3.6.1.2.1.31.1.1.1.1.0"
+        );
+    }
+
+    #[test]
+    fn test_oid_diff_added_and_removed() {
+        let old = [
+            oid!("1.3.6.1.2.1.17.4.3.1.1.1"),
+            oid!("1.3.6.1.2.1.17.4.3.1.1.2"),
+            oid!("1.3.6.1.2.1.17.4.3.1.1.3"),
+        ];
+        let new = [
+            oid!("1.3.6.1.2.1.17.4.3.1.1.3"),
+            oid!("1.3.6.1.2.1.17.4.3.1.1.4"),
+            oid!("1.3.6.1.2.1.17.4.3.1.1.1"),
+        ];
+        let (added, removed) = oid_diff(&old, &new);
+        assert_eq!(added, vec![oid!("1.3.6.1.2.1.17.4.3.1.1.4")]);
+        assert_eq!(removed, vec![oid!("1.3.6.1.2.1.17.4.3.1.1.2")]);
+    }
+
+    #[test]
+    fn test_oid_diff_reordered_is_empty() {
+        let old = [oid!("1.3.6.1.2.1.1.1.0"), oid!("1.3.6.1.2.1.1.5.0")];
+        let new = [oid!("1.3.6.1.2.1.1.5.0"), oid!("1.3.6.1.2.1.1.1.0")];
+        let (added, removed) = oid_diff(&old, &new);
+        assert!(added.is_empty());
+        assert!(removed.is_empty());
+    }
+
+    #[test]
+    fn test_walk_diff_each_category() {
+        let row = |oid: &str, value: &str| (parse_oid(oid).unwrap(), value.to_string());
+        let old = vec![
+            row("1.3.6.1.2.1.1.4.0", "noc@example.net"),
+            row("1.3.6.1.2.1.1.5.0", "ap-01"),
+            row("1.3.6.1.2.1.1.6.0", "Tower 3"),
+        ];
+        let new = vec![
+            row("1.3.6.1.2.1.1.6.0", "Tower 3, sector B"),
+            row("1.3.6.1.2.1.1.5.0", "ap-01"),
+            row("1.3.6.1.2.1.1.7.0", "72"),
+        ];
+        let diff = walk_diff(&old, &new);
+        assert_eq!(
+            diff,
+            WalkDiff {
+                added: vec![row("1.3.6.1.2.1.1.7.0", "72")],
+                removed: vec![row("1.3.6.1.2.1.1.4.0", "noc@example.net")],
+                changed: vec![(
+                    parse_oid("1.3.6.1.2.1.1.6.0").unwrap(),
+                    "Tower 3".to_string(),
+                    "Tower 3, sector B".to_string()
+                )],
+            }
+        );
+        assert!(!diff.is_empty());
+        assert!(walk_diff(&new, &new).is_empty());
+    }
+
     #[test]
     fn test_parse_oid_equivalence() {
         let oid1 = parse_oid("1.3.6.1.2.1.1.1.0").unwrap();
@@ -86,9 +849,130 @@ mod tests {
         assert_eq!(oid.to_string(), "1.3.6.1.4.1.41112.1.4.1.1.4");
     }
 
+    #[test]
+    fn test_oid_macro_arcs() {
+        let oid = oid!(1, 3, 6, 1, 4, 1, 41112, 1, 4, 1, 1, 4);
+        assert_eq!(oid, parse_oid("1.3.6.1.4.1.41112.1.4.1.1.4").unwrap());
+        let trailing_comma = oid!(1, 3, 6, 1,);
+        assert_eq!(trailing_comma.to_string(), "1.3.6.1");
+    }
+
     #[test]
     #[should_panic(expected = "invalid OID")]
     fn test_oid_macro_invalid() {
         let _oid = oid!("not.a.valid.oid.abc");
     }
+
+    #[test]
+    fn test_relative_to_descendant() {
+        let base = parse_oid("1.3.6.1.2.1.2.2.1").unwrap();
+        let full = parse_oid("1.3.6.1.2.1.2.2.1.10.3").unwrap();
+        assert_eq!(relative_to(&base, &full), Some("+.10.3".to_string()));
+    }
+
+    #[test]
+    fn test_relative_to_equal() {
+        let base = parse_oid("1.3.6.1.2.1.2.2.1").unwrap();
+        assert_eq!(relative_to(&base, &base), Some("+".to_string()));
+    }
+
+    #[test]
+    fn test_relative_to_not_descendant() {
+        let base = parse_oid("1.3.6.1.4.1.41112").unwrap();
+        let sibling = parse_oid("1.3.6.1.4.1.411").unwrap();
+        let parent = parse_oid("1.3.6.1.4.1").unwrap();
+        assert_eq!(relative_to(&base, &sibling), None);
+        assert_eq!(relative_to(&base, &parent), None);
+    }
+
+    #[test]
+    fn test_strip_prefix_mixed_rows() {
+        let base = parse_oid("1.3.6.1.2.1.2.2.1.2").unwrap();
+        let row = |oid: &str, value: &str| (parse_oid(oid).unwrap(), value.to_string());
+        let rows = vec![
+            row("1.3.6.1.2.1.2.2.1.2.1", "eth0"),
+            row("1.3.6.1.2.1.2.2.1.20.1", "sibling column"),
+            row("1.3.6.1.2.1.2.2.1.2.10.5", "eth1.5"),
+            row("1.3.6.1.2.1.2.2.1.2", "base"),
+            row("1.3.6.1.2.1.2.2.1.3.1", "next column"),
+            row("1.3.6.1.2.1.2.2.1.2.4294967296", "too large"),
+        ];
+        let stripped: Vec<_> = strip_prefix(&base, rows.into_iter()).collect();
+        assert_eq!(
+            stripped,
+            vec![
+                (vec![1], "eth0".to_string()),
+                (vec![10, 5], "eth1.5".to_string()),
+                (vec![], "base".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_oid_to_name_longest_prefix() {
+        let name = |s: &str| oid_to_name(&parse_oid(s).unwrap());
+        assert_eq!(name("1.3.6.1.2.1.1.1.0"), "sysDescr.0");
+        assert_eq!(name("1.3.6.1.2.1.1.5"), "sysName");
+        assert_eq!(name("1.3.6.1.2.1.2.2.1.10.3"), "ifInOctets.3");
+        assert_eq!(name("1.3.6.1.2.1.31.1.1.1.1.12"), "ifName.12");
+        assert_eq!(name("1.3.6.1.2.1.2.2.1.9.1"), "ifEntry.9.1");
+        assert_eq!(name("1.3.6.1.2.1.99.1"), "mib-2.99.1");
+    }
+
+    #[test]
+    fn test_oid_to_name_unmatched() {
+        assert_eq!(oid_to_name(&parse_oid("2.5.4.3").unwrap()), "2.5.4.3");
+        assert_eq!(
+            oid_to_name(&parse_oid("1.3.6.1.3.1").unwrap()),
+            "1.3.6.1.3.1"
+        );
+    }
+
+    #[test]
+    fn test_next_oid() {
+        let sys_descr = parse_oid("1.3.6.1.2.1.1.1").unwrap();
+        let next = next_oid(&sys_descr);
+        assert_eq!(next.to_string(), "1.3.6.1.2.1.1.1.0");
+        assert!(oid_arcs(&next) > oid_arcs(&sys_descr));
+        assert_eq!(next_oid(&next).to_string(), "1.3.6.1.2.1.1.1.0.0");
+    }
+
+    #[test]
+    fn test_successor() {
+        let if_descr = parse_oid("1.3.6.1.2.1.2.2.1.2").unwrap();
+        let next = successor(&if_descr);
+        assert_eq!(next.to_string(), "1.3.6.1.2.1.2.2.1.3");
+        // past every row of the column
+        let row = parse_oid("1.3.6.1.2.1.2.2.1.2.4294967295").unwrap();
+        assert!(oid_arcs(&next) > oid_arcs(&row));
+
+        let max = oid_from_arcs(&[1, 3, 6, u64::MAX]).unwrap();
+        assert_eq!(successor(&max).to_string(), "1.3.7");
+        let top = parse_oid("1.39").unwrap();
+        assert_eq!(successor(&top), top);
+    }
+
+    #[test]
+    fn test_chunk_oids_exact_multiple() {
+        let oids = if_in_octets(6);
+        let refs: Vec<&Oid> = oids.iter().collect();
+        let chunks = chunk_oids(&refs, 3);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0], &refs[..3]);
+        assert_eq!(chunks[1], &refs[3..]);
+    }
+
+    #[test]
+    fn test_chunk_oids_remainder() {
+        let oids = if_in_octets(7);
+        let refs: Vec<&Oid> = oids.iter().collect();
+        let chunks = chunk_oids(&refs, 3);
+        assert_eq!(
+            chunks.iter().map(|c| c.len()).collect::<Vec<_>>(),
+            [3, 3, 1]
+        );
+        assert_eq!(chunks[2][0], &oids[6]);
+        assert_eq!(chunk_oids(&refs, 0).len(), 7);
+        assert!(chunk_oids(&[], 3).is_empty());
+    }
 }
@@ -1,11 +1,38 @@
-use crate::{Oid, Result, SyncSession};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    sync::mpsc::{Sender, SyncSender},
+    time::{Duration, Instant},
+};
 
-use super::value::{value_to_string, OwnedValue};
+use crate::{snmp, Error, Oid, Result, SyncSession, Value};
+
+use super::arp::{self, ArpEntry};
+use super::bridge::{self, FdbEntry};
+use super::clock::Clock;
+use super::entity::{self, PhysicalEntity};
+use super::host::{self, StorageEntry};
+use super::interfaces::{self, InterfaceRow};
+use super::lldp::{self, LldpNeighbor};
+use super::net::MacFormat;
+use super::oid::{chunk_oids, oid_arcs, oid_from_arcs};
+use super::system::{self, DeviceSnapshot, SystemInfo};
+use super::value::{hex_bytes, value_to_string, value_type_name, OwnedValue};
 
 /// Extension trait for SyncSession providing convenience methods
 ///
 /// This trait adds higher-level operations on top of the basic SNMP operations,
 /// including walk variants that preserve type information.
+///
+/// # Empty responses
+///
+/// A response without any varbinds is handled the same way everywhere:
+/// - GET-based methods (`get_string`, `get_value`, and `SnmpClient::get_many`)
+///   fail with `Error::EmptyResponse`, since an agent must answer every
+///   requested OID. A missing object comes back as a `NoSuchObject` varbind,
+///   and an empty OctetString as `""`; neither is an error.
+/// - Walks (GETNEXT and GETBULK) treat it as the end of the subtree and return
+///   what they collected so far.
 pub trait SessionExt {
     /// Walk an SNMP tree, returning owned (OID, Value) pairs.
     ///
@@ -65,155 +92,1464 @@ pub trait SessionExt {
     /// need raw binary data (e.g., MAC addresses), use `walk_values()` or `walk_bytes()`.
     fn walk(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, String)>>;
 
-    /// Walk an SNMP tree and return only the values as strings.
+    /// Walk an SNMP tree, tolerating agents that briefly stray out of it.
     ///
-    /// **Note:** Lossy conversion. Use `walk_bytes()` for binary data.
-    fn walk_strings(&mut self, oid: &Oid) -> Result<Vec<String>>;
+    /// Like `walk()`, but when a returned OID leaves the subtree it keeps
+    /// issuing GETNEXT from the stray OID (up to 2 times in a row) in case the
+    /// agent re-enters the subtree. Re-entered OIDs must still be greater than
+    /// the last accepted one, so a misbehaving agent cannot make the walk loop.
+    ///
+    /// This trades up to 2 extra requests at the end of every walk for
+    /// robustness against buggy agents; prefer `walk()` for well-behaved ones.
+    fn walk_tolerant(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, String)>>;
 
-    /// Get a single value and convert to string.
-    fn get_string(&mut self, oid: &Oid) -> Result<String>;
+    /// GET sysUpTime.0, then walk `oid` like `walk()`, returning the uptime
+    /// (in hundredths of a second) with the rows.
+    ///
+    /// Storing the uptime with each sample shows when the device restarted
+    /// between two samples; pass both to `counter_reset_detected`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let (uptime, rows) = session.walk_with_uptime(&oid!("1.3.6.1.2.1.2.2.1.10"))?;
+    /// println!("{} rows at uptime {}", rows.len(), uptime);
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::UnexpectedType` if sysUpTime.0 is not Timeticks, and
+    /// `Error::EmptyResponse` if the agent returns no value for it.
+    fn walk_with_uptime(&mut self, oid: &Oid) -> Result<(u32, Vec<(Oid<'static>, String)>)>;
 
-    /// Get a single value as an OwnedValue, preserving type information.
+    /// Walk `oid` like `walk()`, also returning how many GETNEXT requests
+    /// the walk issued.
+    ///
+    /// A walk of `n` rows normally costs `n + 1` round trips, the last one
+    /// answering from outside the subtree. Compare against the rows of
+    /// `bulk_walk()` to see what GETBULK saves on a given agent.
     ///
     /// # Examples
     /// ```no_run
-    /// use snmp2::{SyncSession, helpers::{SessionExt, parse_oid}};
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
     ///
     /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
-    /// let oid = parse_oid("1.3.6.1.4.1.161.19.3.1.1.2.0")?; // frequency
+    /// let (requests, rows) = session.walk_counted(&oid!("1.3.6.1.2.1.2.2.1.2"))?;
+    /// println!("{} rows in {} requests", rows.len(), requests);
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn walk_counted(&mut self, oid: &Oid) -> Result<(usize, Vec<(Oid<'static>, String)>)>;
+
+    /// Walk from `base` with GETNEXT for as long as `bound` accepts the
+    /// returned OIDs, returning typed values.
     ///
-    /// let value = session.get_value(&oid)?;
-    /// if let Some(freq) = value.as_i64() {
-    ///     println!("Frequency: {} MHz", freq);
-    /// }
+    /// With `DescendantBound` this is `walk_values()`. `PrefixLenBound`
+    /// keeps only part of `base` fixed, e.g. to resume a column walk from
+    /// the last row seen. Any `Fn(&Oid, &Oid) -> bool` closure is a bound
+    /// too. The walk also ends at the first exception value.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::{PrefixLenBound, SessionExt}};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// // rest of the ifDescr column (10 arcs) after ifIndex 24
+    /// let rows = session.walk_bounded(&oid!("1.3.6.1.2.1.2.2.1.2.24"), &PrefixLenBound(10))?;
     /// # Ok::<(), snmp2::Error>(())
     /// ```
-    fn get_value(&mut self, oid: &Oid) -> Result<OwnedValue>;
-}
+    fn walk_bounded(
+        &mut self,
+        base: &Oid,
+        bound: &dyn SubtreeBound,
+    ) -> Result<Vec<(Oid<'static>, OwnedValue)>>;
 
-/// Check if `candidate` is a child OID of `base`.
-///
-/// Subtree check that avoids the string prefix bug where
-/// "1.3.6.1.4.1.411" would incorrectly match as a prefix of "1.3.6.1.4.1.41112".
-fn is_subtree(base: &str, candidate: &str) -> bool {
-    if candidate == base {
-        return true;
-    }
-    if candidate.len() > base.len() {
-        // Must start with base AND the next character must be '.'
-        candidate.starts_with(base) && candidate.as_bytes()[base.len()] == b'.'
-    } else {
-        false
-    }
-}
+    /// Walk an SNMP tree, sending each (OID, String) pair to `tx` as it arrives.
+    ///
+    /// Same rows as `walk()`, but nothing is buffered, so a consumer on another
+    /// thread can process rows while the walk is still running. Returns when
+    /// the walk ends; dropping the receiver stops the walk early and is not an
+    /// error.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::{sync::mpsc, thread};
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let (tx, rx) = mpsc::channel();
+    /// let walker = thread::spawn(move || session.walk_to_channel(&oid!("1.3.6.1.2.1.2.2.1.2"), tx));
+    /// for (oid, descr) in rx {
+    ///     println!("{} = {}", oid, descr);
+    /// }
+    /// walker.join().unwrap()?;
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn walk_to_channel(&mut self, oid: &Oid, tx: Sender<(Oid<'static>, String)>) -> Result<()>;
 
-impl SessionExt for SyncSession {
-    fn walk_values(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, OwnedValue)>> {
-        let mut results = Vec::new();
-        let mut current_oid = oid.clone();
-        let base_str = oid.to_string();
+    /// Like `walk_to_channel()`, but over a bounded channel: when it is full,
+    /// the walk blocks in `send` and issues no further GETNEXT until the
+    /// consumer catches up, so at most the channel's capacity plus one row is
+    /// held in memory.
+    ///
+    /// Dropping the receiver aborts the walk after the row in flight; this is
+    /// not an error.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::{sync::mpsc, thread};
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let (tx, rx) = mpsc::sync_channel(16);
+    /// let if_table = oid!("1.3.6.1.2.1.2.2");
+    /// let walker = thread::spawn(move || session.walk_to_sync_sender(&if_table, tx));
+    /// for (oid, value) in rx {
+    ///     println!("{} = {}", oid, value);
+    /// }
+    /// walker.join().unwrap()?;
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn walk_to_sync_sender(
+        &mut self,
+        oid: &Oid,
+        tx: SyncSender<(Oid<'static>, String)>,
+    ) -> Result<()>;
 
-        loop {
-            let response = self.getnext(&current_oid)?;
+    /// Walk an SNMP tree, writing an `oid = value` line per row as it arrives.
+    ///
+    /// Meant for dumps too large to hold in memory; values are rendered like
+    /// `walk()`. Returns the number of rows written. The writer is not
+    /// flushed, and a write failure aborts the walk with `Error::Write`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::io::{self, BufWriter};
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let mut out = BufWriter::new(io::stdout().lock());
+    /// let rows = session.walk_to_writer(&oid!("1.3.6.1.2.1"), &mut out)?;
+    /// eprintln!("{} rows", rows);
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn walk_to_writer(&mut self, oid: &Oid, w: &mut dyn Write) -> Result<usize>;
 
-            if let Some((next_oid, value)) = response.varbinds.next() {
-                let next_oid_str = next_oid.to_string();
+    /// Walk an SNMP tree with GETBULK (v2c), returning owned (OID, Value) pairs.
+    ///
+    /// Fetches up to 25 rows per request. `estimated_rows` is purely a
+    /// performance hint for small tables: the first request then asks for one
+    /// more varbind than the estimate, so a correct estimate finishes the walk
+    /// in a single round trip without padding the response with varbinds from
+    /// past the end of the subtree. A wrong estimate only costs extra requests;
+    /// the result is the same either way.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// // a chassis with two power supplies
+    /// let psu_status = session.bulk_walk(&oid!("1.3.6.1.4.1.9.9.13.1.5.1.3"), Some(2))?;
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn bulk_walk(
+        &mut self,
+        oid: &Oid,
+        estimated_rows: Option<u32>,
+    ) -> Result<Vec<(Oid<'static>, OwnedValue)>>;
 
-                if !is_subtree(&base_str, &next_oid_str) {
-                    break;
-                }
+    /// Check whether the agent answers GETBULK correctly.
+    ///
+    /// Sends a GETBULK for one repetition after `sysDescr` and expects
+    /// `sysDescr.0` back as a string. Some v2c agents time out, return an
+    /// error status or echo garbage instead; all of that, and v1 sessions,
+    /// yield `Ok(false)`, so callers can fall back to GETNEXT. Only a failure
+    /// to send the request is returned as an error.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let table = oid!("1.3.6.1.2.1.2.2.1.2");
+    /// let rows = if session.supports_getbulk()? {
+    ///     session.bulk_walk(&table, None)?
+    /// } else {
+    ///     session.walk_values(&table)?
+    /// };
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn supports_getbulk(&mut self) -> Result<bool>;
 
-                let owned = OwnedValue::from_value(&value);
+    /// Check whether the agent accepts this session's community.
+    ///
+    /// GETs `sysUpTime.0`: any valid response, even `noSuchObject`, means the
+    /// community works. An explicit rejection (a bad-community Report or an
+    /// `authorizationError` response) returns `Ok(false)`.
+    ///
+    /// # Errors
+    /// Most agents drop requests with a wrong community without answering,
+    /// so a timeout (`Error::Receive`) cannot tell a wrong community from an
+    /// unreachable agent and is returned as an error, as are send failures.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"private", None, 0)?;
+    /// if !session.check_community()? {
+    ///     eprintln!("community rejected");
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn check_community(&mut self) -> Result<bool>;
 
-                // EndOfMibView means we've exhausted the subtree
-                if owned.is_error() {
-                    break;
-                }
+    /// Walk several base OIDs and merge the results into one snapshot.
+    ///
+    /// Results are sorted in arc order and de-duplicated by OID, keeping the
+    /// first occurrence, so overlapping bases are safe to pass.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let system = oid!("1.3.6.1.2.1.1");
+    /// let if_number = oid!("1.3.6.1.2.1.2.1");
+    ///
+    /// for (oid, value) in session.walk_multi(&[&system, &if_number])? {
+    ///     println!("{} = {}", oid, value);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn walk_multi(&mut self, bases: &[&Oid]) -> Result<Vec<(Oid<'static>, String)>>;
 
-                let owned_oid = next_oid.to_owned();
-                results.push((owned_oid, owned));
-                current_oid = Oid::from(
-                    &next_oid_str
-                        .split('.')
-                        .map(|p| p.parse::<u32>().unwrap_or(0))
-                        .collect::<Vec<_>>()[..],
-                )
-                .unwrap_or_else(|_| oid.clone());
-            } else {
-                break;
-            }
-        }
+    /// Walk an SNMP tree and return only the values as strings.
+    ///
+    /// **Note:** Lossy conversion. Use `walk_bytes()` for binary data.
+    fn walk_strings(&mut self, oid: &Oid) -> Result<Vec<String>>;
 
-        Ok(results)
-    }
+    /// Reassemble a value too large for one datagram from its chunks.
+    ///
+    /// Vendors expose such values (config blobs, certificate chains) as a
+    /// column of OctetString chunks under `chunk_base`, one row per chunk.
+    /// The chunks are concatenated in ascending index order (arc by arc, so
+    /// chunk 10 follows chunk 9); gaps in the numbering are not detected.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let config = session.get_blob(&oid!("1.3.6.1.4.1.41112.1.6.1.1.2"))?;
+    /// std::fs::write("config.bin", config).unwrap();
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::AsnWrongType` if a chunk is not an OctetString.
+    fn get_blob(&mut self, chunk_base: &Oid) -> Result<Vec<u8>>;
 
-    fn walk_bytes(&mut self, oid: &Oid) -> Result<Vec<Vec<u8>>> {
-        Ok(self
-            .walk_values(oid)?
-            .into_iter()
-            .map(|(_, v)| match v {
-                OwnedValue::OctetString(bytes) => bytes,
-                OwnedValue::Opaque(bytes) => bytes,
-                OwnedValue::IpAddress(ip) => {
-                    format!("{}.{}.{}.{}", ip[0], ip[1], ip[2], ip[3]).into_bytes()
-                }
-                other => other.to_string_lossy().into_bytes(),
-            })
-            .collect())
-    }
+    /// Walk a table column and report values that appear at more than one index.
+    ///
+    /// Returns each duplicated value with the indices (the arcs after
+    /// `column_oid`) it was found at, in walk order. Values are rendered like
+    /// `to_string_lossy`, except that OctetStrings which are not UTF-8 (MACs,
+    /// for instance) are rendered as hex so distinct binary values never
+    /// collide. An empty result means every value in the column is unique.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let if_phys_address = oid!("1.3.6.1.2.1.2.2.1.6");
+    /// for (mac, indices) in session.walk_check_unique(&if_phys_address)? {
+    ///     println!("{} is on ports {:?}", mac, indices);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn walk_check_unique(&mut self, column_oid: &Oid) -> Result<Vec<(String, Vec<Vec<u32>>)>>;
 
-    fn walk(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, String)>> {
-        Ok(self
-            .walk_values(oid)?
-            .into_iter()
-            .map(|(oid, v)| (oid, v.to_string_lossy()))
-            .collect())
-    }
+    /// Walk table columns and render them as CSV, one line per row.
+    ///
+    /// Each `(name, arc)` in `columns` is walked at `table_oid.1.arc` (the
+    /// conventional `...Entry` node). The header line is `index` followed by
+    /// the names; rows are in index order, with the index in dotted form as
+    /// the first field and an empty field where a row lacks a column. Values
+    /// are rendered like `to_string_lossy`, binary OctetStrings as hex, and
+    /// quoted per RFC 4180 where needed. Lines end in `\n`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let csv = session.table_to_csv(
+    ///     &oid!("1.3.6.1.2.1.2.2"), // ifTable
+    ///     &[("ifDescr", 2), ("ifOperStatus", 8), ("ifInOctets", 10)],
+    /// )?;
+    /// std::fs::write("interfaces.csv", csv).unwrap();
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn table_to_csv(&mut self, table_oid: &Oid, columns: &[(&str, u32)]) -> Result<String>;
 
-    fn walk_strings(&mut self, oid: &Oid) -> Result<Vec<String>> {
-        Ok(self
-            .walk_values(oid)?
-            .into_iter()
-            .map(|(_, v)| v.to_string_lossy())
-            .collect())
-    }
+    /// Get a single value and convert to string.
+    ///
+    /// # Errors
+    /// Returns `Error::EmptyResponse` if the response has no varbinds; a
+    /// zero-length OctetString gives `Ok("")`.
+    fn get_string(&mut self, oid: &Oid) -> Result<String>;
 
-    fn get_string(&mut self, oid: &Oid) -> Result<String> {
-        let response = self.get(oid)?;
+    /// Get an OID and return every varbind in the response, not just the first.
+    ///
+    /// Some agents pad GET responses with additional varbinds; `get_string()`
+    /// and `get_value()` only look at the first one.
+    fn get_all(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, String)>>;
 
-        if let Some((_, value)) = response.varbinds.next() {
-            Ok(value_to_string(&value))
-        } else {
-            Ok(String::new())
-        }
-    }
+    /// Fetch scalars and table columns in a single GETBULK request.
+    ///
+    /// The `scalars` are sent as non-repeaters and fetched once (GETNEXT
+    /// semantics, so pass e.g. `sysName` to get `sysName.0`); the `columns` are
+    /// repeated up to `max_repetitions` times. The response is split back into
+    /// the two parts. Agents may return fewer repetitions than asked, in which
+    /// case the last row can be shorter than `columns`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let sys_name = oid!("1.3.6.1.2.1.1.5");
+    /// let if_descr = oid!("1.3.6.1.2.1.2.2.1.2");
+    ///
+    /// let bulk = session.get_bulk(&[&sys_name], &[&if_descr], 10)?;
+    /// println!("{:?}", bulk.scalars);
+    /// for row in &bulk.rows {
+    ///     println!("{:?}", row);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn get_bulk(
+        &mut self,
+        scalars: &[&Oid],
+        columns: &[&Oid],
+        max_repetitions: u32,
+    ) -> Result<BulkResponse>;
 
-    fn get_value(&mut self, oid: &Oid) -> Result<OwnedValue> {
-        let response = self.get(oid)?;
+    /// Look up an interface by name and fetch its IF-MIB row.
+    ///
+    /// Matches `ifName` first, then `ifDescr`, comparing trimmed and ASCII
+    /// case-insensitive, and returns `None` when no interface matches. Costs
+    /// one walk per name column searched plus one GET per row column.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// if let Some(row) = session.interface_by_name("GigabitEthernet0/1")? {
+    ///     println!("{} is ifIndex {}, {:?} bytes in", row.label(), row.if_index, row.in_octets);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn interface_by_name(&mut self, name: &str) -> Result<Option<InterfaceRow>>;
 
-        if let Some((_, value)) = response.varbinds.next() {
-            Ok(OwnedValue::from_value(&value))
-        } else {
-            Ok(OwnedValue::Null)
-        }
-    }
-}
+    /// Name of the interface with the given ifIndex, for readable trap and
+    /// log output.
+    ///
+    /// A targeted GET of `ifName.<if_index>`, falling back to
+    /// `ifDescr.<if_index>` when the agent has no (or a blank) `ifName`.
+    /// Returns `None` if neither exists, e.g. the interface is gone.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let if_index = 3; // from a linkDown trap
+    /// let name = session.if_name_for_index(if_index)?;
+    /// println!("link down on {}", name.as_deref().unwrap_or("unknown interface"));
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn if_name_for_index(&mut self, if_index: i64) -> Result<Option<String>>;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Map every ifIndex to a name that survives reboots, for keying time
+    /// series.
+    ///
+    /// Some devices, stacked switches in particular, renumber ifIndex when
+    /// they restart, so series keyed by ifIndex get mixed up. Re-keying by
+    /// the returned names after a reboot keeps them apart. Walks `ifName`
+    /// and falls back to `ifDescr` for interfaces without a (non-blank)
+    /// name.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let keys = session.interface_stable_keys()?;
+    /// if let Some(name) = keys.get(&10101) {
+    ///     println!("ifIndex 10101 is now {}", name);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn interface_stable_keys(&mut self) -> Result<HashMap<i64, String>>;
 
-    #[test]
-    fn test_is_subtree_exact_match() {
-        assert!(is_subtree("1.3.6.1.4.1.41112", "1.3.6.1.4.1.41112"));
-    }
+    /// Read the agent's ARP (IPv4) and neighbor (IPv6) cache.
+    ///
+    /// Walks `ipNetToPhysicalTable`, falling back to the deprecated
+    /// `ipNetToMediaTable` (IPv4 only) when the agent lacks the newer table.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// for entry in session.arp_table()? {
+    ///     println!("{} -> {:?} (ifIndex {})", entry.ip, entry.mac, entry.if_index);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn arp_table(&mut self) -> Result<Vec<ArpEntry>>;
 
-    #[test]
-    fn test_is_subtree_child() {
-        assert!(is_subtree(
-            "1.3.6.1.4.1.41112",
-            "1.3.6.1.4.1.41112.1.4.7"
-        ));
+    /// Same as `arp_table()`, with every MAC in `mac_format`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::{MacFormat, SessionExt}};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let upper_dash = MacFormat { uppercase: true, separator: '-' };
+    /// for entry in session.arp_table_with_mac_format(upper_dash)? {
+    ///     println!("{} -> {:?}", entry.ip, entry.mac);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn arp_table_with_mac_format(&mut self, mac_format: MacFormat) -> Result<Vec<ArpEntry>>;
+
+    /// Read the bridge forwarding database (`dot1dTpFdbTable`).
+    ///
+    /// Maps each MAC address the bridge knows to the bridge port it was seen
+    /// on. Ports are `dot1dBasePort` numbers, which are not necessarily
+    /// ifIndex values.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// for entry in session.bridge_fdb()? {
+    ///     println!("{} on port {} ({:?})", entry.mac, entry.port, entry.status);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn bridge_fdb(&mut self) -> Result<Vec<FdbEntry>>;
+
+    /// Same as `bridge_fdb()`, with every MAC in `mac_format`.
+    fn bridge_fdb_with_mac_format(&mut self, mac_format: MacFormat) -> Result<Vec<FdbEntry>>;
+
+    /// Read the physical inventory (`entPhysicalTable`): chassis, modules,
+    /// power supplies, fans, sensors, ...
+    ///
+    /// Walks the description, name, serial number, model name and class
+    /// columns and returns one entry per `entPhysicalIndex`, in index order.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::{PhysicalClass, SessionExt}};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// for entity in session.entity_inventory()? {
+    ///     if entity.class == Some(PhysicalClass::Chassis) {
+    ///         println!("{:?} serial {:?}", entity.model, entity.serial);
+    ///     }
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn entity_inventory(&mut self) -> Result<Vec<PhysicalEntity>>;
+
+    /// Read the storage areas of a host (`hrStorageTable`): filesystems,
+    /// physical and virtual memory, ...
+    ///
+    /// Returns one entry per `hrStorageIndex`, in index order. Multiply
+    /// `size` and `used` by `units` for bytes.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// for storage in session.host_storage()? {
+    ///     if let (Some(units), Some(used)) = (storage.units, storage.used) {
+    ///         println!("{:?}: {} bytes used", storage.descr, units * used);
+    ///     }
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn host_storage(&mut self) -> Result<Vec<StorageEntry>>;
+
+    /// Read the load of each processor (`hrProcessorLoad`): the percentage
+    /// of time it was busy over the last minute, in `hrDeviceIndex` order.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let loads = session.cpu_load()?;
+    /// println!("{} cores, busiest at {:?}%", loads.len(), loads.iter().max());
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn cpu_load(&mut self) -> Result<Vec<u8>>;
+
+    /// Read the LLDP neighbors (`lldpRemTable`): the devices directly
+    /// connected to each local port, for building topology maps.
+    ///
+    /// Chassis and port IDs are decoded according to their subtypes (MAC,
+    /// interface name, network address, ...). Rows come in local port order.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// for neighbor in session.lldp_neighbors()? {
+    ///     println!(
+    ///         "port {}: {:?} {:?} ({:?})",
+    ///         neighbor.local_port, neighbor.sys_name, neighbor.port_id, neighbor.chassis_id
+    ///     );
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn lldp_neighbors(&mut self) -> Result<Vec<LldpNeighbor>>;
+
+    /// Fetch the `system` group scalars (sysDescr, sysName, ...) in one GET.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let info = session.system_info()?;
+    /// println!("{:?} at {:?}", info.name, info.location);
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn system_info(&mut self) -> Result<SystemInfo>;
+
+    /// GET the scalars of a MIB group in one request, keyed by name.
+    ///
+    /// Each `(name, arc)` in `names` is read from `group_oid.arc.0`. Objects
+    /// the agent does not have are left out of the map; an SNMPv1 agent that
+    /// fails the request with `noSuchName` is asked again for the rest.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let counters = session.get_scalar_group(
+    ///     &oid!("1.3.6.1.2.1.11"), // snmp group
+    ///     &[("snmpInPkts", 1), ("snmpInBadCommunityNames", 4)],
+    /// )?;
+    /// println!("{:?}", counters.get("snmpInPkts"));
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::ErrorStatus` when the agent fails the request with
+    /// any other error-status, e.g. `tooBig` or `genErr`, rather than an
+    /// empty map.
+    fn get_scalar_group(
+        &mut self,
+        group_oid: &Oid,
+        names: &[(&str, u32)],
+    ) -> Result<HashMap<String, OwnedValue>>;
+
+    /// GET one row of a table whose index is already known, in one request.
+    ///
+    /// Each column in `columns` is read from `table_oid.column.index`, where
+    /// `table_oid` is the table's entry OID (e.g. `ifEntry`). Returns one
+    /// `(column, value)` per requested column, in order, with `None` for
+    /// cells the agent does not have. An SNMPv1 agent that fails the request
+    /// with `noSuchName` is asked again for the other columns.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// // ifDescr, ifOperStatus and ifInOctets of ifIndex 5
+    /// let row = session.get_row(&oid!("1.3.6.1.2.1.2.2.1"), &[2, 8, 10], &[5])?;
+    /// for (column, value) in row {
+    ///     println!("{}: {:?}", column, value);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::ErrorStatus` when the agent fails the request with
+    /// any other error-status, e.g. `tooBig` or `genErr`.
+    fn get_row(
+        &mut self,
+        table_oid: &Oid,
+        columns: &[u32],
+        index: &[u32],
+    ) -> Result<Vec<(u32, Option<String>)>>;
+
+    /// GET many OIDs, `chunk_size` per request, and concatenate the results.
+    ///
+    /// Sends exactly `oids.len() / chunk_size` requests (rounded up), which
+    /// makes the load predictable for capacity planning; see
+    /// [`chunk_oids`](super::chunk_oids). Varbinds come back in request order.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::{SessionExt, oid_from_arcs}};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let oids = (1..=48)
+    ///     .map(|port| oid_from_arcs(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 10, port]))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let refs: Vec<_> = oids.iter().collect();
+    /// let in_octets = session.get_chunked(&refs, 16)?; // 3 requests
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Fails on the first request that fails; returns `Error::EmptyResponse`
+    /// if a response has no varbinds.
+    fn get_chunked(
+        &mut self,
+        oids: &[&Oid],
+        chunk_size: usize,
+    ) -> Result<Vec<(Oid<'static>, OwnedValue)>>;
+
+    /// Fetch the `system` group and every interface for a device overview.
+    ///
+    /// The system scalars come from one GET; `ifTable` and `ifXTable` are
+    /// bulk-walked (v2c) and joined on `ifIndex`, preferring the 64-bit
+    /// counters and `ifHighSpeed` like [`interface_by_name`](Self::interface_by_name).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let snapshot = session.device_snapshot()?;
+    /// println!("{:?}", snapshot.system.name);
+    /// for interface in &snapshot.interfaces {
+    ///     println!("  {} oper={:?}", interface.label(), interface.oper_status);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn device_snapshot(&mut self) -> Result<DeviceSnapshot>;
+
+    /// Fetch sysUpTime as a `Duration` that keeps counting past the 32-bit
+    /// timeticks wrap (every ~497 days).
+    ///
+    /// `previous` is an earlier raw sysUpTime sample and the `Instant` it was
+    /// taken at, as read from `clock`. The time `clock` reports as elapsed
+    /// since then decides how many wraps happened in between. Passing the
+    /// first sample of a device as a fixed baseline yields a cumulative
+    /// uptime across any number of wraps; passing the last sample only
+    /// accounts for wraps since that sample. Without `previous` the raw value
+    /// is returned.
+    ///
+    /// This is a heuristic. When the current value does not fit any number of
+    /// wraps (within 60 seconds or 1% of the elapsed time), the agent is
+    /// assumed to have restarted and the raw value is returned; take a new
+    /// baseline then. A restart that happens to land close to where a wrap
+    /// would put the counter is counted as a wrap, and agents whose clock
+    /// drifts more than the tolerance look like they restarted.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::time::Instant;
+    /// use snmp2::{SyncSession, helpers::{SessionExt, SystemClock}};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let baseline = session.system_info()?.uptime.map(|ticks| (ticks, Instant::now()));
+    /// // ... much later
+    /// let uptime = session.uptime_with_wrap(baseline, &SystemClock)?;
+    /// println!("up {} days", uptime.as_secs() / 86_400);
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    fn uptime_with_wrap(
+        &mut self,
+        previous: Option<(u32, Instant)>,
+        clock: &dyn Clock,
+    ) -> Result<Duration>;
+
+    /// Get a single value as an OwnedValue, preserving type information.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{SyncSession, helpers::{SessionExt, parse_oid}};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let oid = parse_oid("1.3.6.1.4.1.161.19.3.1.1.2.0")?; // frequency
+    ///
+    /// let value = session.get_value(&oid)?;
+    /// if let Some(freq) = value.as_i64() {
+    ///     println!("Frequency: {} MHz", freq);
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::EmptyResponse` if the response has no varbinds.
+    fn get_value(&mut self, oid: &Oid) -> Result<OwnedValue>;
+
+    /// GET a single value and convert it with `extract`, failing loudly when
+    /// the agent returns a different type than the MIB promises.
+    ///
+    /// A type mismatch (say an OctetString where a Gauge32 was expected)
+    /// usually means the wrong MIB or a misbehaving agent; `extract`
+    /// returning `None` turns it into an error instead of a silent default.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, Value, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let if_speed = session.get_expecting(&oid!("1.3.6.1.2.1.2.2.1.5.1"), |value| match value {
+    ///     Value::Unsigned32(speed) => Some(*speed),
+    ///     _ => None,
+    /// })?;
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::UnexpectedType` with the actual type name (e.g.
+    /// `"OctetString"`) when `extract` returns `None`, and
+    /// `Error::EmptyResponse` if the response has no varbinds.
+    fn get_expecting<T>(&mut self, oid: &Oid, extract: impl Fn(&Value) -> Option<T>) -> Result<T>
+    where
+        Self: Sized;
+
+    /// SET `oid` to `value`, then GET it back and report whether the agent
+    /// now holds what was written.
+    ///
+    /// The SET response echoes the request even on agents that quietly
+    /// discard the write, so only the read-back shows whether it stuck.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::{set_string, SessionExt}};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"private", None, 0)?;
+    /// let sys_contact = oid!("1.3.6.1.2.1.1.4.0");
+    /// if !session.set_and_verify(&sys_contact, set_string("noc@example.net"))? {
+    ///     eprintln!("sysContact write did not persist");
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::ErrorStatus` when the agent rejects the SET (e.g.
+    /// `notWritable`), without the GET, and the error of the SET or the GET
+    /// when either request fails, including `Error::EmptyResponse` for a
+    /// read-back without varbinds.
+    fn set_and_verify(&mut self, oid: &Oid, value: Value) -> Result<bool>;
+}
+
+/// A GETBULK response split into its non-repeater and repeater parts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkResponse {
+    /// One varbind per non-repeater OID, in request order.
+    pub scalars: Vec<(Oid<'static>, OwnedValue)>,
+    /// One entry per repetition, each with one varbind per repeated column.
+    pub rows: Vec<Vec<(Oid<'static>, OwnedValue)>>,
+}
+
+/// Inherent shortcuts for the most common [`SessionExt`] methods, usable
+/// without importing the trait.
+///
+/// # Examples
+/// ```no_run
+/// use snmp2::{oid, SyncSession};
+///
+/// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+/// let sys_name = session.get_str(&oid!("1.3.6.1.2.1.1.5.0"))?;
+/// for (oid, descr) in session.walk_str(&oid!("1.3.6.1.2.1.2.2.1.2"))? {
+///     println!("{}: {} = {}", sys_name, oid, descr);
+/// }
+/// # Ok::<(), snmp2::Error>(())
+/// ```
+impl SyncSession {
+    /// Get a single value and convert to string. Same as [`SessionExt::get_string`].
+    pub fn get_str(&mut self, oid: &Oid) -> Result<String> {
+        SessionExt::get_string(self, oid)
+    }
+
+    /// Walk an SNMP tree returning (OID, String) pairs. Same as [`SessionExt::walk`].
+    pub fn walk_str(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, String)>> {
+        SessionExt::walk(self, oid)
+    }
+}
+
+/// `sysDescr`, probed by `supports_getbulk`.
+const SYS_DESCR: &[u64] = &[1, 3, 6, 1, 2, 1, 1, 1];
+
+/// `sysUpTime.0`, probed by `check_community` and read by `walk_with_uptime`.
+const SYS_UP_TIME_0: &[u64] = &[1, 3, 6, 1, 2, 1, 1, 3, 0];
+
+/// Rows `bulk_walk` asks for per GETBULK request.
+const BULK_MAX_REPETITIONS: u32 = 25;
+
+/// Consecutive out-of-subtree answers `walk_tolerant` will follow.
+const MAX_STRAY_PROBES: usize = 2;
+
+/// Walk `oid` with GETNEXT, handing each row to `f` as soon as it is decoded.
+///
+/// Stops at the end of the subtree or when `f` returns `Ok(false)`.
+fn walk_each<F>(session: &mut SyncSession, oid: &Oid, f: F) -> Result<()>
+where
+    F: FnMut(Oid<'static>, OwnedValue) -> Result<bool>,
+{
+    walk_each_bounded(session, oid, &DescendantBound, f)?;
+    Ok(())
+}
+
+/// `walk_each` with the end of the walk decided by `bound`, returning the
+/// number of GETNEXT requests issued.
+fn walk_each_bounded<F>(
+    session: &mut SyncSession,
+    oid: &Oid,
+    bound: &dyn SubtreeBound,
+    mut f: F,
+) -> Result<usize>
+where
+    F: FnMut(Oid<'static>, OwnedValue) -> Result<bool>,
+{
+    let mut current_oid = oid.to_owned();
+    let mut requests = 0;
+
+    loop {
+        requests += 1;
+        let mut response = session.getnext(&current_oid)?;
+
+        let Some((next_oid, value)) = response.varbinds.next() else {
+            break;
+        };
+        if !bound.in_bound(oid, &next_oid) {
+            break;
+        }
+        let owned = OwnedValue::from_value(&value);
+        if owned.is_error() {
+            break;
+        }
+        let next_oid = next_oid.to_owned();
+        if !f(next_oid.clone(), owned)? {
+            break;
+        }
+        current_oid = next_oid;
+    }
+
+    Ok(requests)
+}
+
+/// GET `oids` in one request and match the answers back to them by OID, with
+/// `None` for objects the agent does not have.
+///
+/// An SNMPv1 agent fails the whole request with `noSuchName` (echoing the
+/// request with Nulls); it is asked again without the object `error_index`
+/// points at. Any other error-status, or `noSuchName` without a usable
+/// index, fails with `Error::ErrorStatus`.
+fn get_matched(
+    session: &mut SyncSession,
+    oids: &[Oid<'static>],
+) -> Result<Vec<Option<OwnedValue>>> {
+    let mut values = vec![None; oids.len()];
+    let mut pending: Vec<usize> = (0..oids.len()).collect();
+    while !pending.is_empty() {
+        let refs: Vec<&Oid> = pending.iter().map(|&position| &oids[position]).collect();
+        let response = session.get_multi(&refs)?;
+        let failed = (response.error_index as usize).checked_sub(1);
+        match (response.error_status, failed) {
+            (snmp::ERRSTATUS_NOERROR, _) => {}
+            (snmp::ERRSTATUS_NOSUCHNAME, Some(failed)) if failed < pending.len() => {
+                pending.remove(failed);
+                continue;
+            }
+            (status, _) => return Err(Error::ErrorStatus(status)),
+        }
+        for (oid, value) in response.varbinds {
+            let value = OwnedValue::from_value(&value);
+            if value.is_error() {
+                continue;
+            }
+            if let Some(&position) = pending.iter().find(|&&position| oids[position] == oid) {
+                values[position] = Some(value);
+            }
+        }
+        break;
+    }
+    Ok(values)
+}
+
+/// Decides where `SessionExt::walk_bounded` stops: the walk continues while
+/// `in_bound(base, candidate)` holds for each OID the agent returns.
+///
+/// Closures `Fn(&Oid, &Oid) -> bool` implement it directly.
+pub trait SubtreeBound {
+    fn in_bound(&self, base: &Oid, candidate: &Oid) -> bool;
+}
+
+impl<F: Fn(&Oid, &Oid) -> bool> SubtreeBound for F {
+    fn in_bound(&self, base: &Oid, candidate: &Oid) -> bool {
+        self(base, candidate)
+    }
+}
+
+/// `base` itself and everything below it: the bound of `walk()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DescendantBound;
+
+impl SubtreeBound for DescendantBound {
+    fn in_bound(&self, base: &Oid, candidate: &Oid) -> bool {
+        oid_arcs(candidate).starts_with(&oid_arcs(base))
+    }
+}
+
+/// OIDs that share the first `n` arcs of `base` (all of them if `base` is
+/// shorter), so a walk started at a row of a table column runs to the end of
+/// that column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrefixLenBound(pub usize);
+
+impl SubtreeBound for PrefixLenBound {
+    fn in_bound(&self, base: &Oid, candidate: &Oid) -> bool {
+        let base = oid_arcs(base);
+        let prefix = &base[..self.0.min(base.len())];
+        oid_arcs(candidate).starts_with(prefix)
+    }
+}
+
+/// Check if `candidate` is a child OID of `base`.
+///
+/// Subtree check that avoids the string prefix bug where
+/// "1.3.6.1.4.1.411" would incorrectly match as a prefix of "1.3.6.1.4.1.41112".
+pub(super) fn is_subtree(base: &str, candidate: &str) -> bool {
+    if candidate == base {
+        return true;
+    }
+    if candidate.len() > base.len() {
+        // Must start with base AND the next character must be '.'
+        candidate.starts_with(base) && candidate.as_bytes()[base.len()] == b'.'
+    } else {
+        false
+    }
+}
+
+/// Text form of a value for reports: like `to_string_lossy`, but binary
+/// OctetStrings as hex so distinct values never render the same.
+fn render_value(value: &OwnedValue) -> String {
+    match value {
+        OwnedValue::OctetString(bytes) if std::str::from_utf8(bytes).is_err() => hex_bytes(bytes),
+        other => other.to_string_lossy(),
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break (RFC 4180).
+fn csv_field(field: &str) -> String {
+    if field.contains(|c: char| matches!(c, ',' | '"' | '\n' | '\r')) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl SessionExt for SyncSession {
+    fn walk_values(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, OwnedValue)>> {
+        let mut results = Vec::new();
+        walk_each_bounded(self, oid, &DescendantBound, |oid, value| {
+            results.push((oid, value));
+            Ok(true)
+        })?;
+        Ok(results)
+    }
+
+    fn walk_bytes(&mut self, oid: &Oid) -> Result<Vec<Vec<u8>>> {
+        Ok(self
+            .walk_values(oid)?
+            .into_iter()
+            .map(|(_, v)| match v {
+                OwnedValue::OctetString(bytes) => bytes,
+                OwnedValue::Opaque(bytes) => bytes,
+                OwnedValue::IpAddress(ip) => {
+                    format!("{}.{}.{}.{}", ip[0], ip[1], ip[2], ip[3]).into_bytes()
+                }
+                other => other.to_string_lossy().into_bytes(),
+            })
+            .collect())
+    }
+
+    fn walk(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, String)>> {
+        Ok(self
+            .walk_values(oid)?
+            .into_iter()
+            .map(|(oid, v)| (oid, v.to_string_lossy()))
+            .collect())
+    }
+
+    fn walk_with_uptime(&mut self, oid: &Oid) -> Result<(u32, Vec<(Oid<'static>, String)>)> {
+        let sys_up_time = oid_from_arcs(SYS_UP_TIME_0)?;
+        let uptime = self.get_expecting(&sys_up_time, |value| match value {
+            Value::Timeticks(ticks) => Some(*ticks),
+            _ => None,
+        })?;
+        Ok((uptime, self.walk(oid)?))
+    }
+
+    fn walk_counted(&mut self, oid: &Oid) -> Result<(usize, Vec<(Oid<'static>, String)>)> {
+        let mut results = Vec::new();
+        let requests = walk_each_bounded(self, oid, &DescendantBound, |oid, value| {
+            results.push((oid, value.to_string_lossy()));
+            Ok(true)
+        })?;
+        Ok((requests, results))
+    }
+
+    fn walk_bounded(
+        &mut self,
+        base: &Oid,
+        bound: &dyn SubtreeBound,
+    ) -> Result<Vec<(Oid<'static>, OwnedValue)>> {
+        let mut results = Vec::new();
+        walk_each_bounded(self, base, bound, |oid, value| {
+            results.push((oid, value));
+            Ok(true)
+        })?;
+        Ok(results)
+    }
+
+    fn walk_tolerant(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, String)>> {
+        let mut results = Vec::new();
+        let mut current_oid = oid.to_owned();
+        let mut last_accepted = oid_arcs(oid);
+        let mut strays = 0;
+
+        loop {
+            let mut response = self.getnext(&current_oid)?;
+
+            let Some((next_oid, value)) = response.varbinds.next() else {
+                break;
+            };
+            let owned = OwnedValue::from_value(&value);
+            if owned.is_error() {
+                break;
+            }
+            let next_oid = next_oid.to_owned();
+
+            if DescendantBound.in_bound(oid, &next_oid) {
+                let arcs = oid_arcs(&next_oid);
+                // never accept an OID at or before the last one, or we may loop
+                if arcs <= last_accepted {
+                    break;
+                }
+                last_accepted = arcs;
+                strays = 0;
+                results.push((next_oid.clone(), owned.to_string_lossy()));
+            } else {
+                strays += 1;
+                if strays > MAX_STRAY_PROBES {
+                    break;
+                }
+            }
+            current_oid = next_oid;
+        }
+
+        Ok(results)
+    }
+
+    fn walk_to_channel(&mut self, oid: &Oid, tx: Sender<(Oid<'static>, String)>) -> Result<()> {
+        // stop once the receiver hangs up, nobody wants the rest of the walk
+        walk_each(self, oid, |oid, value| {
+            Ok(tx.send((oid, value.to_string_lossy())).is_ok())
+        })
+    }
+
+    fn walk_to_sync_sender(
+        &mut self,
+        oid: &Oid,
+        tx: SyncSender<(Oid<'static>, String)>,
+    ) -> Result<()> {
+        // a full channel blocks here, holding back the next GETNEXT
+        walk_each(self, oid, |oid, value| {
+            Ok(tx.send((oid, value.to_string_lossy())).is_ok())
+        })
+    }
+
+    fn walk_to_writer(&mut self, oid: &Oid, w: &mut dyn Write) -> Result<usize> {
+        let mut rows = 0;
+        walk_each(self, oid, |oid, value| {
+            writeln!(w, "{} = {}", oid, value.to_string_lossy())
+                .map_err(|e| Error::Write(e.to_string()))?;
+            rows += 1;
+            Ok(true)
+        })?;
+        Ok(rows)
+    }
+
+    fn bulk_walk(
+        &mut self,
+        oid: &Oid,
+        estimated_rows: Option<u32>,
+    ) -> Result<Vec<(Oid<'static>, OwnedValue)>> {
+        let mut results = Vec::new();
+        let mut current_oid = oid.to_owned();
+        // one extra varbind shows where the subtree ends
+        let mut max_repetitions = estimated_rows.map_or(BULK_MAX_REPETITIONS, |rows| {
+            rows.saturating_add(1).min(BULK_MAX_REPETITIONS)
+        });
+
+        loop {
+            let response = self.getbulk(&[&current_oid], 0, max_repetitions)?;
+            let mut last = None;
+            for (next_oid, value) in response.varbinds {
+                if !DescendantBound.in_bound(oid, &next_oid) {
+                    return Ok(results);
+                }
+                let owned = OwnedValue::from_value(&value);
+                if owned.is_error() {
+                    return Ok(results);
+                }
+                let next_oid = next_oid.to_owned();
+                last = Some(next_oid.clone());
+                results.push((next_oid, owned));
+            }
+            let Some(next_oid) = last else {
+                break;
+            };
+            current_oid = next_oid;
+            max_repetitions = BULK_MAX_REPETITIONS;
+        }
+
+        Ok(results)
+    }
+
+    fn supports_getbulk(&mut self) -> Result<bool> {
+        let sys_descr = oid_from_arcs(SYS_DESCR)?;
+        let mut response = match self.getbulk(&[&sys_descr], 0, 1) {
+            Ok(response) => response,
+            Err(Error::Send) => return Err(Error::Send),
+            Err(_) => return Ok(false),
+        };
+        if response.error_status != 0 {
+            return Ok(false);
+        }
+        Ok(match response.varbinds.next() {
+            Some((oid, Value::OctetString(_))) => oid_arcs(&oid) == [SYS_DESCR, &[0][..]].concat(),
+            _ => false,
+        })
+    }
+
+    fn check_community(&mut self) -> Result<bool> {
+        let sys_up_time = oid_from_arcs(SYS_UP_TIME_0)?;
+        match self.get(&sys_up_time) {
+            Ok(_) => Ok(true),
+            Err(Error::AuthFailure(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn walk_multi(&mut self, bases: &[&Oid]) -> Result<Vec<(Oid<'static>, String)>> {
+        let mut results = Vec::new();
+        for base in bases {
+            results.extend(self.walk(base)?);
+        }
+        // stable sort keeps the first occurrence ahead of its duplicates
+        results.sort_by_cached_key(|(oid, _)| oid_arcs(oid));
+        results.dedup_by(|later, earlier| later.0 == earlier.0);
+        Ok(results)
+    }
+
+    fn walk_check_unique(&mut self, column_oid: &Oid) -> Result<Vec<(String, Vec<Vec<u32>>)>> {
+        let column_len = oid_arcs(column_oid).len();
+        let mut groups: Vec<(String, Vec<Vec<u32>>)> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for (oid, value) in self.walk_values(column_oid)? {
+            let Ok(index) = oid_arcs(&oid)[column_len..]
+                .iter()
+                .map(|&arc| u32::try_from(arc))
+                .collect::<std::result::Result<Vec<u32>, _>>()
+            else {
+                continue;
+            };
+            let key = render_value(&value);
+            let position = *positions.entry(key.clone()).or_insert_with(|| {
+                groups.push((key, Vec::new()));
+                groups.len() - 1
+            });
+            groups[position].1.push(index);
+        }
+        groups.retain(|(_, indices)| indices.len() > 1);
+        Ok(groups)
+    }
+
+    fn get_blob(&mut self, chunk_base: &Oid) -> Result<Vec<u8>> {
+        let mut chunks = self.walk_values(chunk_base)?;
+        chunks.sort_by_cached_key(|(oid, _)| oid_arcs(oid));
+        let mut blob = Vec::new();
+        for (_, value) in chunks {
+            match value {
+                OwnedValue::OctetString(bytes) => blob.extend_from_slice(&bytes),
+                _ => return Err(Error::AsnWrongType),
+            }
+        }
+        Ok(blob)
+    }
+
+    fn table_to_csv(&mut self, table_oid: &Oid, columns: &[(&str, u32)]) -> Result<String> {
+        let table = oid_arcs(table_oid);
+        let mut rows: BTreeMap<Vec<u64>, Vec<Option<String>>> = BTreeMap::new();
+        for (position, &(_, arc)) in columns.iter().enumerate() {
+            let column = [&table[..], &[1, u64::from(arc)]].concat();
+            for (oid, value) in self.walk_values(&oid_from_arcs(&column)?)? {
+                let index = oid_arcs(&oid)[column.len()..].to_vec();
+                rows.entry(index)
+                    .or_insert_with(|| vec![None; columns.len()])[position] =
+                    Some(render_value(&value));
+            }
+        }
+
+        let mut csv = String::from("index");
+        for (name, _) in columns {
+            csv.push(',');
+            csv.push_str(&csv_field(name));
+        }
+        csv.push('\n');
+        for (index, cells) in rows {
+            let index: Vec<String> = index.iter().map(u64::to_string).collect();
+            csv.push_str(&index.join("."));
+            for cell in cells {
+                csv.push(',');
+                csv.push_str(&csv_field(cell.as_deref().unwrap_or_default()));
+            }
+            csv.push('\n');
+        }
+        Ok(csv)
+    }
+
+    fn walk_strings(&mut self, oid: &Oid) -> Result<Vec<String>> {
+        Ok(self
+            .walk_values(oid)?
+            .into_iter()
+            .map(|(_, v)| v.to_string_lossy())
+            .collect())
+    }
+
+    fn get_string(&mut self, oid: &Oid) -> Result<String> {
+        let mut response = self.get(oid)?;
+
+        match response.varbinds.next() {
+            Some((_, value)) => Ok(value_to_string(&value)),
+            None => Err(Error::EmptyResponse),
+        }
+    }
+
+    fn get_all(&mut self, oid: &Oid) -> Result<Vec<(Oid<'static>, String)>> {
+        let response = self.get(oid)?;
+
+        Ok(response
+            .varbinds
+            .map(|(oid, value)| (oid.to_owned(), value_to_string(&value)))
+            .collect())
+    }
+
+    fn get_bulk(
+        &mut self,
+        scalars: &[&Oid],
+        columns: &[&Oid],
+        max_repetitions: u32,
+    ) -> Result<BulkResponse> {
+        let oids: Vec<&Oid> = scalars.iter().chain(columns).copied().collect();
+        let non_repeaters = u32::try_from(scalars.len())?;
+        let response = self.getbulk(&oids, non_repeaters, max_repetitions)?;
+
+        let mut varbinds: Vec<(Oid<'static>, OwnedValue)> = response
+            .varbinds
+            .map(|(oid, value)| (oid.to_owned(), OwnedValue::from_value(&value)))
+            .collect();
+        let rest = varbinds.split_off(scalars.len().min(varbinds.len()));
+        let rows = if columns.is_empty() {
+            Vec::new()
+        } else {
+            rest.chunks(columns.len()).map(<[_]>::to_vec).collect()
+        };
+        Ok(BulkResponse {
+            scalars: varbinds,
+            rows,
+        })
+    }
+
+    fn interface_by_name(&mut self, name: &str) -> Result<Option<InterfaceRow>> {
+        interfaces::interface_by_name(self, name)
+    }
+
+    fn if_name_for_index(&mut self, if_index: i64) -> Result<Option<String>> {
+        interfaces::if_name_for_index(self, if_index)
+    }
+
+    fn interface_stable_keys(&mut self) -> Result<HashMap<i64, String>> {
+        interfaces::interface_stable_keys(self)
+    }
+
+    fn arp_table(&mut self) -> Result<Vec<ArpEntry>> {
+        arp::arp_table(self, MacFormat::default())
+    }
+
+    fn arp_table_with_mac_format(&mut self, mac_format: MacFormat) -> Result<Vec<ArpEntry>> {
+        arp::arp_table(self, mac_format)
+    }
+
+    fn bridge_fdb(&mut self) -> Result<Vec<FdbEntry>> {
+        bridge::bridge_fdb(self, MacFormat::default())
+    }
+
+    fn bridge_fdb_with_mac_format(&mut self, mac_format: MacFormat) -> Result<Vec<FdbEntry>> {
+        bridge::bridge_fdb(self, mac_format)
+    }
+
+    fn entity_inventory(&mut self) -> Result<Vec<PhysicalEntity>> {
+        entity::entity_inventory(self)
+    }
+
+    fn host_storage(&mut self) -> Result<Vec<StorageEntry>> {
+        host::host_storage(self)
+    }
+
+    fn cpu_load(&mut self) -> Result<Vec<u8>> {
+        host::cpu_load(self)
+    }
+
+    fn lldp_neighbors(&mut self) -> Result<Vec<LldpNeighbor>> {
+        lldp::lldp_neighbors(self)
+    }
+
+    fn system_info(&mut self) -> Result<SystemInfo> {
+        system::system_info(self)
+    }
+
+    fn get_scalar_group(
+        &mut self,
+        group_oid: &Oid,
+        names: &[(&str, u32)],
+    ) -> Result<HashMap<String, OwnedValue>> {
+        let mut scalars = HashMap::new();
+        if names.is_empty() {
+            return Ok(scalars);
+        }
+        let group = oid_arcs(group_oid);
+        let oids = names
+            .iter()
+            .map(|&(_, arc)| oid_from_arcs(&[&group[..], &[u64::from(arc), 0]].concat()))
+            .collect::<Result<Vec<_>>>()?;
+        for (&(name, _), value) in names.iter().zip(get_matched(self, &oids)?) {
+            if let Some(value) = value {
+                scalars.insert(name.to_string(), value);
+            }
+        }
+        Ok(scalars)
+    }
+
+    fn get_row(
+        &mut self,
+        table_oid: &Oid,
+        columns: &[u32],
+        index: &[u32],
+    ) -> Result<Vec<(u32, Option<String>)>> {
+        if columns.is_empty() {
+            return Ok(Vec::new());
+        }
+        let table = oid_arcs(table_oid);
+        let index: Vec<u64> = index.iter().map(|&arc| u64::from(arc)).collect();
+        let oids = columns
+            .iter()
+            .map(|&column| oid_from_arcs(&[&table[..], &[u64::from(column)], &index[..]].concat()))
+            .collect::<Result<Vec<_>>>()?;
+
+        let values = get_matched(self, &oids)?;
+        Ok(columns
+            .iter()
+            .zip(values)
+            .map(|(&column, value)| (column, value.map(|value| value.to_string_lossy())))
+            .collect())
+    }
+
+    fn get_chunked(
+        &mut self,
+        oids: &[&Oid],
+        chunk_size: usize,
+    ) -> Result<Vec<(Oid<'static>, OwnedValue)>> {
+        let mut results = Vec::with_capacity(oids.len());
+        for chunk in chunk_oids(oids, chunk_size) {
+            let varbinds = self.get_multi(chunk)?.into_owned_varbinds();
+            if varbinds.is_empty() {
+                return Err(Error::EmptyResponse);
+            }
+            results.extend(varbinds.into_iter().map(|vb| (vb.oid, vb.value)));
+        }
+        Ok(results)
+    }
+
+    fn device_snapshot(&mut self) -> Result<DeviceSnapshot> {
+        system::device_snapshot(self)
+    }
+
+    fn uptime_with_wrap(
+        &mut self,
+        previous: Option<(u32, Instant)>,
+        clock: &dyn Clock,
+    ) -> Result<Duration> {
+        system::uptime_with_wrap(self, previous, clock)
+    }
+
+    fn get_value(&mut self, oid: &Oid) -> Result<OwnedValue> {
+        let mut response = self.get(oid)?;
+
+        match response.varbinds.next() {
+            Some((_, value)) => Ok(OwnedValue::from_value(&value)),
+            None => Err(Error::EmptyResponse),
+        }
+    }
+
+    fn get_expecting<T>(&mut self, oid: &Oid, extract: impl Fn(&Value) -> Option<T>) -> Result<T> {
+        let mut response = self.get(oid)?;
+        let (_, value) = response.varbinds.next().ok_or(Error::EmptyResponse)?;
+        extract(&value).ok_or_else(|| Error::UnexpectedType(value_type_name(&value).to_string()))
+    }
+
+    fn set_and_verify(&mut self, oid: &Oid, value: Value) -> Result<bool> {
+        let written = OwnedValue::from_value(&value);
+        let error_status = self.set(&[(oid, value)])?.error_status;
+        if error_status != 0 {
+            return Err(Error::ErrorStatus(error_status));
+        }
+        Ok(self.get_value(oid)? == written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread};
+
+    use super::*;
+    use crate::helpers::mock::{octets, MockAgent, MockOptions};
+    use crate::oid;
+
+    #[test]
+    fn test_is_subtree_exact_match() {
+        assert!(is_subtree("1.3.6.1.4.1.41112", "1.3.6.1.4.1.41112"));
+    }
+
+    #[test]
+    fn test_is_subtree_child() {
+        assert!(is_subtree("1.3.6.1.4.1.41112", "1.3.6.1.4.1.41112.1.4.7"));
     }
 
     #[test]
@@ -221,18 +1557,12 @@ mod tests {
         // This is the bug the old string prefix check had
         // "1.3.6.1.4.1.411" is a string prefix of "1.3.6.1.4.1.41112"
         // but not OID subtree parent
-        assert!(!is_subtree(
-            "1.3.6.1.4.1.41112",
-            "1.3.6.1.4.1.411"
-        ));
+        assert!(!is_subtree("1.3.6.1.4.1.41112", "1.3.6.1.4.1.411"));
     }
 
     #[test]
     fn test_is_subtree_different_tree() {
-        assert!(!is_subtree(
-            "1.3.6.1.4.1.41112",
-            "1.3.6.1.4.1.17713"
-        ));
+        assert!(!is_subtree("1.3.6.1.4.1.41112", "1.3.6.1.4.1.17713"));
     }
 
     #[test]
@@ -243,6 +1573,1010 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_descendant_bound() {
+        let base = oid!("1.3.6.1.4.1.411");
+        assert!(DescendantBound.in_bound(&base, &base));
+        assert!(DescendantBound.in_bound(&base, &oid!("1.3.6.1.4.1.411.1.2")));
+        assert!(!DescendantBound.in_bound(&base, &oid!("1.3.6.1.4.1.41112")));
+        assert!(!DescendantBound.in_bound(&base, &oid!("1.3.6.1.4.1")));
+    }
+
+    #[test]
+    fn test_walk_multi_overlapping() {
+        let agent = MockAgent::start(vec![
+            (
+                oid!("1.3.6.1.2.1.1.1.0"),
+                OwnedValue::OctetString(b"descr".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.1.5.0"),
+                OwnedValue::OctetString(b"name".to_vec()),
+            ),
+            (oid!("1.3.6.1.2.1.1.10.0"), OwnedValue::Integer(10)),
+            (oid!("1.3.6.1.2.1.2.1.0"), OwnedValue::Integer(2)),
+            (oid!("1.3.6.1.2.1.3.1.0"), OwnedValue::Integer(3)),
+        ]);
+        let mut session = agent.session();
+        let system = oid!("1.3.6.1.2.1.1");
+        let sys_name = oid!("1.3.6.1.2.1.1.5");
+        let interfaces = oid!("1.3.6.1.2.1.2");
+
+        let results = session
+            .walk_multi(&[&interfaces, &sys_name, &system])
+            .unwrap();
+        let rendered: Vec<(String, String)> = results
+            .into_iter()
+            .map(|(oid, value)| (oid.to_string(), value))
+            .collect();
+        assert_eq!(
+            rendered,
+            vec![
+                ("1.3.6.1.2.1.1.1.0".to_string(), "descr".to_string()),
+                ("1.3.6.1.2.1.1.5.0".to_string(), "name".to_string()),
+                ("1.3.6.1.2.1.1.10.0".to_string(), "10".to_string()),
+                ("1.3.6.1.2.1.2.1.0".to_string(), "2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_to_channel_incremental() {
+        let agent = MockAgent::start_with(
+            (1..=5)
+                .map(|i| {
+                    (
+                        crate::helpers::parse_oid(&format!("1.3.6.1.2.1.2.2.1.2.{}", i)).unwrap(),
+                        OwnedValue::OctetString(format!("eth{}", i).into_bytes()),
+                    )
+                })
+                .collect(),
+            MockOptions {
+                response_delay: Duration::from_millis(50),
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+        let (tx, rx) = mpsc::channel();
+        let walker =
+            thread::spawn(move || session.walk_to_channel(&oid!("1.3.6.1.2.1.2.2.1.2"), tx));
+
+        let (first_oid, first_value) = rx.recv().unwrap();
+        assert_eq!(first_oid.to_string(), "1.3.6.1.2.1.2.2.1.2.1");
+        assert_eq!(first_value, "eth1");
+        // the first row is out while the walk is still fetching the rest
+        assert!(!walker.is_finished());
+
+        let rest: Vec<String> = rx.iter().map(|(_, value)| value).collect();
+        assert_eq!(rest, vec!["eth2", "eth3", "eth4", "eth5"]);
+        walker.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_walk_to_channel_receiver_dropped() {
+        let agent = MockAgent::start_with(
+            (1..=5)
+                .map(|i| {
+                    (
+                        crate::helpers::parse_oid(&format!("1.3.6.1.2.1.2.2.1.2.{}", i)).unwrap(),
+                        OwnedValue::Integer(i),
+                    )
+                })
+                .collect(),
+            MockOptions {
+                response_delay: Duration::from_millis(100),
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+        let (tx, rx) = mpsc::channel();
+        let walker =
+            thread::spawn(move || session.walk_to_channel(&oid!("1.3.6.1.2.1.2.2.1.2"), tx));
+
+        rx.recv().unwrap();
+        drop(rx);
+        walker.join().unwrap().unwrap();
+        // the row fetched while the receiver was dropped is the last request
+        assert_eq!(agent.requests(), 2);
+    }
+
+    #[test]
+    fn test_walk_to_sync_sender_backpressure() {
+        let agent = MockAgent::start(
+            (1..=5)
+                .map(|i| {
+                    (
+                        crate::helpers::parse_oid(&format!("1.3.6.1.2.1.2.2.1.2.{}", i)).unwrap(),
+                        OwnedValue::Integer(i),
+                    )
+                })
+                .collect(),
+        );
+        let mut session = agent.session();
+        let (tx, rx) = mpsc::sync_channel(1);
+        let walker =
+            thread::spawn(move || session.walk_to_sync_sender(&oid!("1.3.6.1.2.1.2.2.1.2"), tx));
+
+        // one row buffered, one blocked in send: the walk waits for us
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(agent.requests(), 2);
+        assert!(!walker.is_finished());
+
+        let values: Vec<String> = rx.iter().map(|(_, value)| value).collect();
+        assert_eq!(values, vec!["1", "2", "3", "4", "5"]);
+        walker.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_walk_with_uptime() {
+        let agent = MockAgent::start(vec![
+            (oid!("1.3.6.1.2.1.1.3.0"), OwnedValue::Timeticks(8_640_000)),
+            (oid!("1.3.6.1.2.1.2.2.1.10.1"), OwnedValue::Counter32(1200)),
+            (oid!("1.3.6.1.2.1.2.2.1.10.2"), OwnedValue::Counter32(3400)),
+            (oid!("1.3.6.1.2.1.2.2.1.16.1"), OwnedValue::Counter32(5600)),
+        ]);
+        let mut session = agent.session();
+
+        let (uptime, rows) = session
+            .walk_with_uptime(&oid!("1.3.6.1.2.1.2.2.1.10"))
+            .unwrap();
+        assert_eq!(uptime, 8_640_000);
+        assert_eq!(
+            rows,
+            vec![
+                (oid!("1.3.6.1.2.1.2.2.1.10.1"), "1200".to_string()),
+                (oid!("1.3.6.1.2.1.2.2.1.10.2"), "3400".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_with_uptime_wrong_type() {
+        let agent = MockAgent::start(vec![(
+            oid!("1.3.6.1.2.1.1.3.0"),
+            OwnedValue::Integer(8_640_000),
+        )]);
+        let mut session = agent.session();
+        assert!(matches!(
+            session.walk_with_uptime(&oid!("1.3.6.1.2.1.2.2.1.10")),
+            Err(Error::UnexpectedType(name)) if name == "Integer"
+        ));
+    }
+
+    #[test]
+    fn test_walk_counted() {
+        let agent = MockAgent::start(if_descr_table());
+        let mut session = agent.session();
+
+        let (requests, rows) = session.walk_counted(&oid!("1.3.6.1.2.1.2.2.1.2")).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[3], (oid!("1.3.6.1.2.1.2.2.1.2.4"), "4".to_string()));
+        // four rows plus the GETNEXT that left the column
+        assert_eq!(requests, 5);
+        assert_eq!(requests, agent.requests());
+    }
+
+    fn if_descr_table() -> Vec<(Oid<'static>, OwnedValue)> {
+        (1..=4)
+            .map(|i| {
+                (
+                    crate::helpers::parse_oid(&format!("1.3.6.1.2.1.2.2.1.2.{}", i)).unwrap(),
+                    OwnedValue::Integer(i),
+                )
+            })
+            .chain([(oid!("1.3.6.1.2.1.2.2.1.3.1"), OwnedValue::Integer(6))])
+            .collect()
+    }
+
+    #[test]
+    fn test_walk_bounded_prefix_len() {
+        let agent = MockAgent::start(if_descr_table());
+        let mut session = agent.session();
+        let base = oid!("1.3.6.1.2.1.2.2.1.2.2");
+
+        // the descendant bound stops at once: .2.3 is not below .2.2
+        let rows = session.walk_bounded(&base, &DescendantBound).unwrap();
+        assert!(rows.is_empty());
+
+        // resumes the column after ifIndex 2 and stops at ifType
+        let rows = session.walk_bounded(&base, &PrefixLenBound(10)).unwrap();
+        let oids: Vec<String> = rows.iter().map(|(oid, _)| oid.to_string()).collect();
+        assert_eq!(oids, ["1.3.6.1.2.1.2.2.1.2.3", "1.3.6.1.2.1.2.2.1.2.4"]);
+    }
+
+    #[test]
+    fn test_walk_bounded_custom_predicate() {
+        let agent = MockAgent::start(if_descr_table());
+        let mut session = agent.session();
+        let base = oid!("1.3.6.1.2.1.2.2.1.2");
+        let below_three = |base: &Oid, candidate: &Oid| {
+            DescendantBound.in_bound(base, candidate) && oid_arcs(candidate)[10] < 3
+        };
+
+        let rows = session.walk_bounded(&base, &below_three).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(
+            rows[1],
+            (oid!("1.3.6.1.2.1.2.2.1.2.2"), OwnedValue::Integer(2))
+        );
+        assert_eq!(
+            session.walk_bounded(&base, &DescendantBound).unwrap(),
+            session.walk_values(&base).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_walk_to_writer() {
+        let agent = MockAgent::start(vec![
+            (
+                oid!("1.3.6.1.2.1.1.1.0"),
+                OwnedValue::OctetString(b"descr".to_vec()),
+            ),
+            (oid!("1.3.6.1.2.1.1.3.0"), OwnedValue::Timeticks(12345)),
+            (oid!("1.3.6.1.2.1.2.1.0"), OwnedValue::Integer(2)),
+        ]);
+        let mut session = agent.session();
+        let mut out = Vec::new();
+
+        let rows = session
+            .walk_to_writer(&oid!("1.3.6.1.2.1.1"), &mut out)
+            .unwrap();
+        assert_eq!(rows, 2);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "1.3.6.1.2.1.1.1.0 = descr\n1.3.6.1.2.1.1.3.0 = 12345\n"
+        );
+    }
+
+    /// A Response to request `req_id` carrying `sysDescr.0 = descr`.
+    fn sys_descr_response(req_id: i32, descr: &[u8]) -> Vec<u8> {
+        let mut buf = crate::pdu::Buf::default();
+        crate::pdu::build(
+            crate::Version::V2C,
+            b"public",
+            crate::snmp::MSG_RESPONSE,
+            req_id,
+            &[(&oid!("1.3.6.1.2.1.1.1.0"), crate::Value::OctetString(descr))],
+            0,
+            0,
+            &mut buf,
+            #[cfg(feature = "v3")]
+            None,
+        )
+        .unwrap();
+        buf.to_vec()
+    }
+
+    #[test]
+    fn test_late_reply_is_drained() {
+        let agent = MockAgent::start(vec![(
+            oid!("1.3.6.1.2.1.1.1.0"),
+            OwnedValue::OctetString(b"fresh".to_vec()),
+        )]);
+        let mut session = agent.session();
+        // reply to a request that already timed out, queued before the next one
+        agent.inject(
+            session.socket().local_addr().unwrap(),
+            &sys_descr_response(999, b"stale"),
+        );
+        thread::sleep(Duration::from_millis(50));
+
+        let value = session.get_value(&oid!("1.3.6.1.2.1.1.1.0")).unwrap();
+        assert_eq!(value, OwnedValue::OctetString(b"fresh".to_vec()));
+    }
+
+    #[test]
+    fn test_drain_stale_discards_queued() {
+        let agent = MockAgent::start(Vec::new());
+        let mut session = agent.session();
+        let local = session.socket().local_addr().unwrap();
+        agent.inject(local, &sys_descr_response(1, b"stale"));
+        agent.inject(local, &sys_descr_response(2, b"stale"));
+        thread::sleep(Duration::from_millis(50));
+
+        session.drain_stale();
+        session.socket().set_nonblocking(true).unwrap();
+        let mut buf = [0u8; 64];
+        assert!(session.socket().recv(&mut buf).is_err());
+    }
+
+    fn large_strings(count: u32, len: usize) -> Vec<(Oid<'static>, OwnedValue)> {
+        (1..=count)
+            .map(|i| {
+                let oid = oid_from_arcs(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 2, u64::from(i)]).unwrap();
+                (oid, OwnedValue::OctetString(vec![b'x'; len]))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_max_response_bytes_trips_walk() {
+        let agent = MockAgent::start(large_strings(3, 1000));
+        let column = oid!("1.3.6.1.2.1.2.2.1.2");
+
+        let mut session = agent.session().with_max_response_bytes(2500);
+        assert_eq!(
+            session.walk_values(&column).unwrap_err(),
+            Error::ResponseTooLarge
+        );
+
+        let mut session = agent.session().with_max_response_bytes(10_000);
+        assert_eq!(session.walk_values(&column).unwrap().len(), 3);
+        assert!(
+            session.received_bytes() > 3000,
+            "{}",
+            session.received_bytes()
+        );
+        // the count keeps running until reset
+        assert_eq!(session.walk_values(&column).unwrap().len(), 3);
+        assert!(session.received_bytes() > 6000);
+        session.reset_received_bytes();
+        assert_eq!(session.received_bytes(), 0);
+    }
+
+    #[test]
+    fn test_max_varbinds_per_response() {
+        let agent = MockAgent::start(large_strings(50, 1));
+        let column = oid!("1.3.6.1.2.1.2.2.1.2");
+
+        let mut session = agent.session().with_max_varbinds_per_response(20);
+        assert!(matches!(
+            session.getbulk(&[&column], 0, 21),
+            Err(Error::TooManyVarbinds)
+        ));
+        let response = session.getbulk(&[&column], 0, 20).unwrap();
+        assert_eq!(response.varbinds.count(), 20);
+
+        // the default leaves ordinary bulk walks alone
+        let mut session = agent.session();
+        assert_eq!(session.bulk_walk(&column, None).unwrap().len(), 50);
+    }
+
+    #[test]
+    fn test_request_id_mismatch() {
+        let agent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut session = SyncSession::new_v2c(
+            agent.local_addr().unwrap(),
+            b"public",
+            Some(Duration::from_secs(1)),
+            0,
+        )
+        .unwrap();
+        let responder = thread::spawn(move || {
+            let mut buf = [0u8; 512];
+            let (len, peer) = agent.recv_from(&mut buf).unwrap();
+            let req_id = crate::Pdu::from_bytes(&buf[..len]).unwrap().req_id;
+            agent
+                .send_to(&sys_descr_response(req_id + 1, b"wrong"), peer)
+                .unwrap();
+        });
+
+        let err = session.get(&oid!("1.3.6.1.2.1.1.1.0")).unwrap_err();
+        assert_eq!(err, crate::Error::RequestIdMismatch);
+        responder.join().unwrap();
+    }
+
+    #[test]
+    fn test_walk_tolerant_stray_then_reenter() {
+        let stray = oid!("1.3.6.1.2.1.4.1.0");
+        let agent = MockAgent::start_with(
+            vec![
+                (
+                    oid!("1.3.6.1.2.1.2.2.1.2.1"),
+                    OwnedValue::OctetString(b"eth0".to_vec()),
+                ),
+                (
+                    oid!("1.3.6.1.2.1.2.2.1.2.2"),
+                    OwnedValue::OctetString(b"eth1".to_vec()),
+                ),
+                (
+                    oid!("1.3.6.1.2.1.2.2.1.2.3"),
+                    OwnedValue::OctetString(b"wlan0".to_vec()),
+                ),
+                (oid!("1.3.6.1.2.1.2.2.1.3.1"), OwnedValue::Integer(6)),
+            ],
+            MockOptions {
+                getnext_overrides: vec![
+                    (
+                        oid!("1.3.6.1.2.1.2.2.1.2.1"),
+                        (stray.clone(), OwnedValue::Integer(2)),
+                    ),
+                    (
+                        stray.clone(),
+                        (
+                            oid!("1.3.6.1.2.1.2.2.1.2.2"),
+                            OwnedValue::OctetString(b"eth1".to_vec()),
+                        ),
+                    ),
+                ],
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+        let if_descr = oid!("1.3.6.1.2.1.2.2.1.2");
+
+        let strict = session.walk_strings(&if_descr).unwrap();
+        assert_eq!(strict, vec!["eth0"]);
+
+        let tolerant: Vec<String> = session
+            .walk_tolerant(&if_descr)
+            .unwrap()
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(tolerant, vec!["eth0", "eth1", "wlan0"]);
+    }
+
+    fn psu_agent() -> MockAgent {
+        let mut table = Vec::new();
+        for column in 2..=3 {
+            for psu in 1..=3 {
+                let oid = format!("1.3.6.1.4.1.9.9.13.1.5.1.{}.{}", column, psu);
+                table.push((
+                    crate::helpers::parse_oid(&oid).unwrap(),
+                    OwnedValue::Integer(column * 10 + psu),
+                ));
+            }
+        }
+        MockAgent::start(table)
+    }
+
+    #[test]
+    fn test_bulk_walk_estimate_avoids_overshoot() {
+        let column = oid!("1.3.6.1.4.1.9.9.13.1.5.1.2");
+
+        let agent = psu_agent();
+        let rows = agent.session().bulk_walk(&column, None).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(agent.requests(), 1);
+        assert_eq!(agent.varbinds_sent(), BULK_MAX_REPETITIONS as usize);
+
+        let agent = psu_agent();
+        let hinted = agent.session().bulk_walk(&column, Some(3)).unwrap();
+        assert_eq!(hinted, rows);
+        assert_eq!(agent.requests(), 1);
+        assert_eq!(agent.varbinds_sent(), 4);
+    }
+
+    #[test]
+    fn test_bulk_walk_underestimate() {
+        let agent = psu_agent();
+        let rows = agent
+            .session()
+            .bulk_walk(&oid!("1.3.6.1.4.1.9.9.13.1.5.1.2"), Some(1))
+            .unwrap();
+        let values: Vec<i64> = rows.iter().filter_map(|(_, v)| v.as_i64()).collect();
+        assert_eq!(values, vec![21, 22, 23]);
+        assert_eq!(agent.requests(), 2);
+    }
+
+    #[test]
+    fn test_get_scalar_group() {
+        let agent = MockAgent::start(vec![
+            (oid!("1.3.6.1.2.1.11.1.0"), OwnedValue::Counter32(1200)),
+            (oid!("1.3.6.1.2.1.11.4.0"), OwnedValue::Counter32(7)),
+        ]);
+        let mut session = agent.session();
+        let scalars = session
+            .get_scalar_group(
+                &oid!("1.3.6.1.2.1.11"),
+                &[
+                    ("snmpInPkts", 1),
+                    ("snmpInBadCommunityNames", 4),
+                    ("snmpEnableAuthenTraps", 30),
+                ],
+            )
+            .unwrap();
+        assert_eq!(scalars.len(), 2);
+        assert_eq!(scalars["snmpInPkts"], OwnedValue::Counter32(1200));
+        assert_eq!(scalars["snmpInBadCommunityNames"], OwnedValue::Counter32(7));
+        assert!(!scalars.contains_key("snmpEnableAuthenTraps"));
+        assert_eq!(agent.requests(), 1);
+    }
+
+    #[test]
+    fn test_get_scalar_group_error_status() {
+        let agent = MockAgent::start_with(
+            vec![(oid!("1.3.6.1.2.1.11.1.0"), OwnedValue::Counter32(1200))],
+            MockOptions {
+                get_error_status: snmp::ERRSTATUS_GENERR,
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+        assert_eq!(
+            session.get_scalar_group(&oid!("1.3.6.1.2.1.11"), &[("snmpInPkts", 1)]),
+            Err(Error::ErrorStatus(snmp::ERRSTATUS_GENERR))
+        );
+    }
+
+    #[test]
+    fn test_get_row_missing_column() {
+        let agent = MockAgent::start(vec![
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.5"),
+                OwnedValue::OctetString(b"eth4".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.6"),
+                OwnedValue::OctetString(b"eth5".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.10.5"),
+                OwnedValue::Counter32(98_765),
+            ),
+        ]);
+        let mut session = agent.session();
+        // no ifInErrors for ifIndex 5
+        let row = session
+            .get_row(&oid!("1.3.6.1.2.1.2.2.1"), &[2, 10, 14], &[5])
+            .unwrap();
+        assert_eq!(
+            row,
+            vec![
+                (2, Some("eth4".to_string())),
+                (10, Some("98765".to_string())),
+                (14, None),
+            ]
+        );
+        assert_eq!(agent.requests(), 1);
+    }
+
+    #[test]
+    fn test_get_row_v1_no_such_name() {
+        let agent = MockAgent::start_with(
+            vec![
+                (
+                    oid!("1.3.6.1.2.1.2.2.1.2.5"),
+                    OwnedValue::OctetString(b"eth4".to_vec()),
+                ),
+                (
+                    oid!("1.3.6.1.2.1.2.2.1.10.5"),
+                    OwnedValue::Counter32(98_765),
+                ),
+            ],
+            MockOptions {
+                no_such_name: true,
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+        let row = session
+            .get_row(&oid!("1.3.6.1.2.1.2.2.1"), &[2, 14, 10, 20], &[5])
+            .unwrap();
+        assert_eq!(
+            row,
+            vec![
+                (2, Some("eth4".to_string())),
+                (14, None),
+                (10, Some("98765".to_string())),
+                (20, None),
+            ]
+        );
+        // asked again without ifInErrors, then without ifOutQLen
+        assert_eq!(agent.requests(), 3);
+    }
+
+    #[test]
+    fn test_get_row_error_status() {
+        for status in [snmp::ERRSTATUS_TOOBIG, snmp::ERRSTATUS_GENERR] {
+            let agent = MockAgent::start_with(
+                vec![(oid!("1.3.6.1.2.1.2.2.1.2.5"), octets("eth4"))],
+                MockOptions {
+                    get_error_status: status,
+                    ..MockOptions::default()
+                },
+            );
+            let mut session = agent.session();
+            assert_eq!(
+                session.get_row(&oid!("1.3.6.1.2.1.2.2.1"), &[2, 10], &[5]),
+                Err(Error::ErrorStatus(status))
+            );
+            assert_eq!(agent.requests(), 1);
+        }
+    }
+
+    #[test]
+    fn test_get_scalar_group_v1_no_such_name() {
+        let agent = MockAgent::start_with(
+            vec![(oid!("1.3.6.1.2.1.11.1.0"), OwnedValue::Counter32(1200))],
+            MockOptions {
+                no_such_name: true,
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+        let scalars = session
+            .get_scalar_group(
+                &oid!("1.3.6.1.2.1.11"),
+                &[("snmpEnableAuthenTraps", 30), ("snmpInPkts", 1)],
+            )
+            .unwrap();
+        assert_eq!(scalars.len(), 1);
+        assert_eq!(scalars["snmpInPkts"], OwnedValue::Counter32(1200));
+    }
+
+    #[test]
+    fn test_walk_check_unique_duplicate_mac() {
+        let mac = |last: u8| OwnedValue::OctetString(vec![0x00, 0x27, 0x22, 0xaa, 0xbb, last]);
+        let agent = MockAgent::start(vec![
+            (oid!("1.3.6.1.2.1.2.2.1.6.1"), mac(0x01)),
+            (oid!("1.3.6.1.2.1.2.2.1.6.2"), mac(0xcc)),
+            (oid!("1.3.6.1.2.1.2.2.1.6.3"), mac(0x03)),
+            (oid!("1.3.6.1.2.1.2.2.1.6.4"), mac(0xcc)),
+            (oid!("1.3.6.1.2.1.2.2.1.7.1"), OwnedValue::Integer(1)),
+        ]);
+        let mut session = agent.session();
+        assert_eq!(
+            session
+                .walk_check_unique(&oid!("1.3.6.1.2.1.2.2.1.6"))
+                .unwrap(),
+            vec![("00 27 22 aa bb cc".to_string(), vec![vec![2], vec![4]])]
+        );
+        assert!(session
+            .walk_check_unique(&oid!("1.3.6.1.2.1.2.2.1.7"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_get_blob_reassembles_chunks() {
+        let chunk = |index: u64, bytes: &[u8]| {
+            (
+                oid_from_arcs(&[1, 3, 6, 1, 4, 1, 41112, 1, 6, 1, 1, 2, index]).unwrap(),
+                OwnedValue::OctetString(bytes.to_vec()),
+            )
+        };
+        let agent = MockAgent::start(vec![
+            chunk(10, b"-----END CERTIFICATE-----\n"),
+            chunk(1, b"-----BEGIN CERTIFICATE-----\n"),
+            chunk(2, b"MIIB"),
+            chunk(9, b"IDAQAB\n"),
+            (
+                oid!("1.3.6.1.4.1.41112.1.6.1.1.3.1"),
+                OwnedValue::Integer(4),
+            ),
+        ]);
+        let mut session = agent.session();
+        let blob = session
+            .get_blob(&oid!("1.3.6.1.4.1.41112.1.6.1.1.2"))
+            .unwrap();
+        assert_eq!(
+            blob,
+            b"-----BEGIN CERTIFICATE-----\nMIIBIDAQAB\n-----END CERTIFICATE-----\n".to_vec()
+        );
+        assert_eq!(
+            session.get_blob(&oid!("1.3.6.1.4.1.41112.1.6.1.1.3")),
+            Err(Error::AsnWrongType)
+        );
+    }
+
+    #[test]
+    fn test_get_expecting() {
+        let agent = MockAgent::start(vec![
+            (
+                oid!("1.3.6.1.2.1.2.2.1.5.1"),
+                OwnedValue::Unsigned32(100_000_000),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.5.2"),
+                OwnedValue::OctetString(b"100M".to_vec()),
+            ),
+        ]);
+        let mut session = agent.session();
+        let gauge = |value: &Value| match value {
+            Value::Unsigned32(speed) => Some(*speed),
+            _ => None,
+        };
+        assert_eq!(
+            session
+                .get_expecting(&oid!("1.3.6.1.2.1.2.2.1.5.1"), gauge)
+                .unwrap(),
+            100_000_000
+        );
+        assert_eq!(
+            session.get_expecting(&oid!("1.3.6.1.2.1.2.2.1.5.2"), gauge),
+            Err(Error::UnexpectedType("OctetString".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_table_to_csv() {
+        let agent = MockAgent::start(vec![
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.1"),
+                OwnedValue::OctetString(b"eth0".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.2"),
+                OwnedValue::OctetString(b"uplink, \"north\"".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.10"),
+                OwnedValue::OctetString(b"ath0".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.6.1"),
+                OwnedValue::OctetString(vec![0x00, 0x27, 0x22, 0xaa, 0xbb, 0xcc]),
+            ),
+            (oid!("1.3.6.1.2.1.2.2.1.8.1"), OwnedValue::Integer(1)),
+            (oid!("1.3.6.1.2.1.2.2.1.8.10"), OwnedValue::Integer(2)),
+        ]);
+        let mut session = agent.session();
+        let csv = session
+            .table_to_csv(
+                &oid!("1.3.6.1.2.1.2.2"),
+                &[("ifDescr", 2), ("ifPhysAddress", 6), ("ifOperStatus", 8)],
+            )
+            .unwrap();
+        assert_eq!(
+            csv,
+            "index,ifDescr,ifPhysAddress,ifOperStatus\n\
+             1,eth0,00 27 22 aa bb cc,1\n\
+             2,\"uplink, \"\"north\"\"\",,\n\
+             10,ath0,,2\n"
+        );
+    }
+
+    #[test]
+    fn test_get_chunked() {
+        let oids: Vec<Oid<'static>> = (1..=5)
+            .map(|i| oid_from_arcs(&[1, 3, 6, 1, 2, 1, 2, 2, 1, 10, i]).unwrap())
+            .collect();
+        let agent = MockAgent::start(
+            oids.iter()
+                .zip(100..)
+                .map(|(oid, octets)| (oid.clone(), OwnedValue::Counter32(octets)))
+                .collect(),
+        );
+        let mut session = agent.session();
+        let refs: Vec<&Oid> = oids.iter().collect();
+        let results = session.get_chunked(&refs, 2).unwrap();
+        assert_eq!(agent.requests(), 3);
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0], (oids[0].clone(), OwnedValue::Counter32(100)));
+        assert_eq!(results[4], (oids[4].clone(), OwnedValue::Counter32(104)));
+    }
+
+    #[test]
+    fn test_get_string_empty_octet_string() {
+        let agent = MockAgent::start(vec![(
+            oid!("1.3.6.1.2.1.1.6.0"),
+            OwnedValue::OctetString(Vec::new()),
+        )]);
+        let mut session = agent.session();
+        assert_eq!(session.get_string(&oid!("1.3.6.1.2.1.1.6.0")).unwrap(), "");
+        assert_eq!(
+            session.get_value(&oid!("1.3.6.1.2.1.1.6.0")).unwrap(),
+            OwnedValue::OctetString(Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_get_string_no_varbinds() {
+        let agent = MockAgent::start_with(
+            Vec::new(),
+            MockOptions {
+                get_reply: Some(Vec::new()),
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+        assert_eq!(
+            session.get_string(&oid!("1.3.6.1.2.1.1.6.0")).unwrap_err(),
+            Error::EmptyResponse
+        );
+        assert_eq!(
+            session.get_value(&oid!("1.3.6.1.2.1.1.6.0")).unwrap_err(),
+            Error::EmptyResponse
+        );
+    }
+
+    fn sys_descr_agent(options: MockOptions) -> MockAgent {
+        MockAgent::start_with(
+            vec![
+                (
+                    oid!("1.3.6.1.2.1.1.1.0"),
+                    OwnedValue::OctetString(b"Linux ap-01".to_vec()),
+                ),
+                (oid!("1.3.6.1.2.1.1.3.0"), OwnedValue::Timeticks(1000)),
+            ],
+            options,
+        )
+    }
+
+    #[test]
+    fn test_supports_getbulk() {
+        let agent = sys_descr_agent(MockOptions::default());
+        assert!(agent.session().supports_getbulk().unwrap());
+    }
+
+    #[test]
+    fn test_supports_getbulk_broken_agent() {
+        // answers with the requested OID instead of its successor
+        let agent = sys_descr_agent(MockOptions {
+            getbulk_reply: Some(vec![(oid!("1.3.6.1.2.1.1.1"), OwnedValue::Null)]),
+            ..MockOptions::default()
+        });
+        assert!(!agent.session().supports_getbulk().unwrap());
+
+        let agent = sys_descr_agent(MockOptions {
+            getbulk_reply: Some(Vec::new()),
+            ..MockOptions::default()
+        });
+        assert!(!agent.session().supports_getbulk().unwrap());
+    }
+
+    #[test]
+    fn test_check_community_valid() {
+        let agent = sys_descr_agent(MockOptions::default());
+        assert!(agent.session().check_community().unwrap());
+    }
+
+    #[test]
+    fn test_check_community_rejected() {
+        let agent = sys_descr_agent(MockOptions {
+            reject_bad_community: true,
+            ..MockOptions::default()
+        });
+        let mut session =
+            SyncSession::new_v2c(agent.addr(), b"private", Some(Duration::from_secs(1)), 0)
+                .unwrap();
+        assert!(!session.check_community().unwrap());
+    }
+
+    #[test]
+    fn test_check_community_timeout() {
+        let agent = sys_descr_agent(MockOptions::default());
+        let mut session = SyncSession::new_v2c(
+            agent.addr(),
+            b"private",
+            Some(Duration::from_millis(100)),
+            0,
+        )
+        .unwrap();
+        assert_eq!(session.check_community().unwrap_err(), Error::Receive);
+        assert_eq!(agent.requests(), 1);
+    }
+
+    #[test]
+    fn test_get_bulk_non_repeaters() {
+        let agent = MockAgent::start(vec![
+            (
+                oid!("1.3.6.1.2.1.1.1.0"),
+                OwnedValue::OctetString(b"descr".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.1.5.0"),
+                OwnedValue::OctetString(b"name".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.1"),
+                OwnedValue::OctetString(b"eth0".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.2"),
+                OwnedValue::OctetString(b"eth1".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.3"),
+                OwnedValue::OctetString(b"wlan0".to_vec()),
+            ),
+        ]);
+        let mut session = agent.session();
+        let sys_descr = oid!("1.3.6.1.2.1.1.1");
+        let sys_name = oid!("1.3.6.1.2.1.1.5");
+        let if_descr = oid!("1.3.6.1.2.1.2.2.1.2");
+
+        let bulk = session
+            .get_bulk(&[&sys_descr, &sys_name], &[&if_descr], 3)
+            .unwrap();
+        assert_eq!(
+            bulk.scalars,
+            vec![
+                (
+                    oid!("1.3.6.1.2.1.1.1.0"),
+                    OwnedValue::OctetString(b"descr".to_vec())
+                ),
+                (
+                    oid!("1.3.6.1.2.1.1.5.0"),
+                    OwnedValue::OctetString(b"name".to_vec())
+                ),
+            ]
+        );
+        assert_eq!(
+            bulk.rows,
+            vec![
+                vec![(
+                    oid!("1.3.6.1.2.1.2.2.1.2.1"),
+                    OwnedValue::OctetString(b"eth0".to_vec())
+                )],
+                vec![(
+                    oid!("1.3.6.1.2.1.2.2.1.2.2"),
+                    OwnedValue::OctetString(b"eth1".to_vec())
+                )],
+                vec![(
+                    oid!("1.3.6.1.2.1.2.2.1.2.3"),
+                    OwnedValue::OctetString(b"wlan0".to_vec())
+                )],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_get_all_padded_response() {
+        let agent = MockAgent::start_with(
+            vec![(
+                oid!("1.3.6.1.2.1.1.5.0"),
+                OwnedValue::OctetString(b"ap-1".to_vec()),
+            )],
+            MockOptions {
+                get_padding: vec![
+                    (oid!("1.3.6.1.2.1.1.3.0"), OwnedValue::Timeticks(4200)),
+                    (
+                        oid!("1.3.6.1.2.1.1.6.0"),
+                        OwnedValue::OctetString(b"roof".to_vec()),
+                    ),
+                ],
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+
+        let all = session.get_all(&oid!("1.3.6.1.2.1.1.5.0")).unwrap();
+        assert_eq!(
+            all,
+            vec![
+                (oid!("1.3.6.1.2.1.1.5.0"), "ap-1".to_string()),
+                (oid!("1.3.6.1.2.1.1.3.0"), "4200".to_string()),
+                (oid!("1.3.6.1.2.1.1.6.0"), "roof".to_string()),
+            ]
+        );
+        assert_eq!(
+            session.get_string(&oid!("1.3.6.1.2.1.1.5.0")).unwrap(),
+            "ap-1"
+        );
+    }
+
+    #[test]
+    fn test_set_and_verify() {
+        let sys_contact = oid!("1.3.6.1.2.1.1.4.0");
+        let agent = MockAgent::start(vec![(
+            sys_contact.clone(),
+            OwnedValue::OctetString(b"noc@example.net".to_vec()),
+        )]);
+        let mut session = agent.session();
+
+        let same = Value::OctetString(b"noc@example.net");
+        assert!(session.set_and_verify(&sys_contact, same).unwrap());
+        assert_eq!(agent.requests(), 2);
+
+        // the mock acknowledges the SET but keeps its old value
+        let other = Value::OctetString(b"ops@example.net");
+        assert!(!session.set_and_verify(&sys_contact, other).unwrap());
+        assert_eq!(agent.requests(), 4);
+    }
+
+    #[test]
+    fn test_set_and_verify_rejected() {
+        let sys_descr = oid!("1.3.6.1.2.1.1.1.0");
+        let agent = MockAgent::start_with(
+            vec![(sys_descr.clone(), OwnedValue::OctetString(b"AP".to_vec()))],
+            MockOptions {
+                set_error_status: snmp::ERRSTATUS_NOTWRITABLE,
+                ..MockOptions::default()
+            },
+        );
+        let mut session = agent.session();
+        let err = session
+            .set_and_verify(&sys_descr, Value::OctetString(b"switch"))
+            .unwrap_err();
+        assert_eq!(err, Error::ErrorStatus(snmp::ERRSTATUS_NOTWRITABLE));
+        assert_eq!(err.to_string(), "Agent returned notWritable");
+        // no read-back
+        assert_eq!(agent.requests(), 1);
+    }
+
     #[test]
     #[ignore]
     fn test_walk_values_integration() {
@@ -0,0 +1,434 @@
+//! SNMPv2-MIB system group helpers.
+
+use std::time::{Duration, Instant};
+
+use crate::{Error, Result, SyncSession, Value};
+
+use super::clock::Clock;
+use super::interfaces::{self, InterfaceRow};
+use super::oid::{oid_arcs, oid_from_arcs};
+use super::value::value_to_string;
+
+const SYSTEM: &[u64] = &[1, 3, 6, 1, 2, 1, 1];
+const SYS_DESCR: u64 = 1;
+const SYS_OBJECT_ID: u64 = 2;
+const SYS_UP_TIME: u64 = 3;
+const SYS_CONTACT: u64 = 4;
+const SYS_NAME: u64 = 5;
+const SYS_LOCATION: u64 = 6;
+const SYS_SERVICES: u64 = 7;
+
+/// The scalars of the `system` group (1.3.6.1.2.1.1).
+///
+/// Fields are `None` when the agent did not return the object.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemInfo {
+    pub descr: Option<String>,
+    /// `sysObjectID` in dotted notation, identifies the vendor and model.
+    pub object_id: Option<String>,
+    /// `sysUpTime` in hundredths of a second.
+    pub uptime: Option<u32>,
+    pub contact: Option<String>,
+    pub name: Option<String>,
+    pub location: Option<String>,
+    /// OSI layers from `sysServices`, see [`decode_sys_services`]; empty when
+    /// the agent did not return the object.
+    pub services: Vec<u8>,
+}
+
+/// GET all `system` scalars in one request.
+pub(super) fn system_info(session: &mut SyncSession) -> Result<SystemInfo> {
+    let oids = [
+        SYS_DESCR,
+        SYS_OBJECT_ID,
+        SYS_UP_TIME,
+        SYS_CONTACT,
+        SYS_NAME,
+        SYS_LOCATION,
+        SYS_SERVICES,
+    ]
+    .iter()
+    .map(|&arc| oid_from_arcs(&[SYSTEM, &[arc, 0][..]].concat()))
+    .collect::<Result<Vec<_>>>()?;
+    let refs: Vec<_> = oids.iter().collect();
+
+    let response = session.get_multi(&refs)?;
+    let mut info = SystemInfo::default();
+    for (oid, value) in response.varbinds {
+        let arcs = oid_arcs(&oid);
+        if arcs.len() != SYSTEM.len() + 2
+            || !arcs.starts_with(SYSTEM)
+            || arcs[SYSTEM.len() + 1] != 0
+        {
+            continue;
+        }
+        let text = match value {
+            Value::OctetString(_) | Value::ObjectIdentifier(_) => Some(value_to_string(&value)),
+            _ => None,
+        };
+        match arcs[SYSTEM.len()] {
+            SYS_DESCR => info.descr = text,
+            SYS_OBJECT_ID => info.object_id = text,
+            SYS_UP_TIME => {
+                if let Value::Timeticks(ticks) = value {
+                    info.uptime = Some(ticks);
+                }
+            }
+            SYS_CONTACT => info.contact = text,
+            SYS_NAME => info.name = text,
+            SYS_LOCATION => info.location = text,
+            SYS_SERVICES => {
+                if let Value::Integer(services) = value {
+                    info.services = decode_sys_services(services);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(info)
+}
+
+/// Decode the `sysServices` bitmask into the OSI layers (1-7) it lists.
+///
+/// A device offering layer L adds 2^(L-1) to the value (RFC 3418), so 72 is
+/// layers 4 and 7 (a host), 2 a bridge and 6 a router that also bridges.
+/// Values outside 0..=127 are invalid and decode to no layers.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::decode_sys_services;
+///
+/// assert_eq!(decode_sys_services(72), vec![4, 7]);
+/// assert_eq!(decode_sys_services(78), vec![2, 3, 4, 7]);
+/// ```
+pub fn decode_sys_services(value: i64) -> Vec<u8> {
+    if !(0..=127).contains(&value) {
+        return Vec::new();
+    }
+    (1..=7u8)
+        .filter(|layer| value & (1 << (layer - 1)) != 0)
+        .collect()
+}
+
+/// Everything a device overview page shows: the `system` group and all
+/// interfaces.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeviceSnapshot {
+    pub system: SystemInfo,
+    /// In `ifIndex` order.
+    pub interfaces: Vec<InterfaceRow>,
+}
+
+/// GET the `system` scalars, then bulk-walk `ifTable` and `ifXTable`.
+pub(super) fn device_snapshot(session: &mut SyncSession) -> Result<DeviceSnapshot> {
+    Ok(DeviceSnapshot {
+        system: system_info(session)?,
+        interfaces: interfaces::interface_table(session)?,
+    })
+}
+
+/// Timeticks per `u32` wrap of sysUpTime (about 497 days).
+const UPTIME_WRAP: u64 = 1 << 32;
+
+/// Tolerated disagreement between agent uptime and local wall clock, in
+/// timeticks: 60 seconds, or 1% of the elapsed time if that is more.
+const UPTIME_SLACK_MIN: u64 = 6000;
+const UPTIME_SLACK_DIVISOR: u64 = 100;
+
+/// GET sysUpTime.0 and extend it past the 32-bit wrap, timing the sample
+/// with `clock`.
+pub(super) fn uptime_with_wrap(
+    session: &mut SyncSession,
+    previous: Option<(u32, Instant)>,
+    clock: &dyn Clock,
+) -> Result<Duration> {
+    let oid = oid_from_arcs(&[SYSTEM, &[SYS_UP_TIME, 0][..]].concat())?;
+    let mut response = session.get(&oid)?;
+    let now = clock.now();
+    match response.varbinds.next() {
+        Some((_, Value::Timeticks(ticks))) => Ok(unwrap_uptime(previous, ticks, now)),
+        _ => Err(Error::AsnWrongType),
+    }
+}
+
+/// Add the wraps implied by the wall-clock time since `previous` to `ticks`.
+///
+/// The uptime the agent should report now is the previous sample plus the
+/// elapsed time; the number of wraps is whatever brings `ticks` closest to
+/// that. If no number of wraps brings it within the slack, the agent
+/// restarted and `ticks` is returned as is.
+fn unwrap_uptime(previous: Option<(u32, Instant)>, ticks: u32, now: Instant) -> Duration {
+    let ticks = u64::from(ticks);
+    let total = match previous {
+        Some((prev_ticks, at)) => {
+            let elapsed = (now.saturating_duration_since(at).as_millis() / 10) as u64;
+            let expected = u64::from(prev_ticks) + elapsed;
+            let wraps = (expected.saturating_sub(ticks) + UPTIME_WRAP / 2) / UPTIME_WRAP;
+            let total = ticks + wraps * UPTIME_WRAP;
+            let slack = UPTIME_SLACK_MIN.max(elapsed / UPTIME_SLACK_DIVISOR);
+            if total.abs_diff(expected) <= slack {
+                total
+            } else {
+                ticks
+            }
+        }
+        None => ticks,
+    };
+    Duration::from_millis(total * 10)
+}
+
+/// List the fields that differ between two `SystemInfo` snapshots.
+///
+/// Returns `(field, old, new)` with the MIB object name as the field, for
+/// config drift checks such as "device renamed". `sysUpTime` is left out
+/// since it changes on every poll. Missing values compare and render as an
+/// empty string.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{system_info_diff, SystemInfo};
+///
+/// let old = SystemInfo { name: Some("ap-01".into()), ..SystemInfo::default() };
+/// let new = SystemInfo { name: Some("ap-01-north".into()), ..SystemInfo::default() };
+/// assert_eq!(
+///     system_info_diff(&old, &new),
+///     vec![("sysName", "ap-01".to_string(), "ap-01-north".to_string())]
+/// );
+/// ```
+pub fn system_info_diff(old: &SystemInfo, new: &SystemInfo) -> Vec<(&'static str, String, String)> {
+    [
+        ("sysDescr", &old.descr, &new.descr),
+        ("sysObjectID", &old.object_id, &new.object_id),
+        ("sysContact", &old.contact, &new.contact),
+        ("sysName", &old.name, &new.name),
+        ("sysLocation", &old.location, &new.location),
+    ]
+    .into_iter()
+    .map(|(field, old, new)| {
+        (
+            field,
+            old.clone().unwrap_or_default(),
+            new.clone().unwrap_or_default(),
+        )
+    })
+    .filter(|(_, old, new)| old != new)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::clock::FakeClock;
+    use crate::helpers::mock::MockAgent;
+    use crate::helpers::{OwnedValue, SessionExt};
+    use crate::oid;
+
+    fn snapshot(location: &str, uptime: u32) -> SystemInfo {
+        SystemInfo {
+            descr: Some("AirOS XW.v6.3.6".to_string()),
+            object_id: Some("1.3.6.1.4.1.41112".to_string()),
+            uptime: Some(uptime),
+            contact: Some("noc@example.net".to_string()),
+            name: Some("ap-01".to_string()),
+            location: Some(location.to_string()),
+            services: vec![2, 3],
+        }
+    }
+
+    #[test]
+    fn test_system_info_diff_location_only() {
+        let old = snapshot("Tower 3", 1000);
+        let new = snapshot("Tower 3, sector B", 5000);
+        assert_eq!(
+            system_info_diff(&old, &new),
+            vec![(
+                "sysLocation",
+                "Tower 3".to_string(),
+                "Tower 3, sector B".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_system_info_diff_unchanged_and_missing() {
+        let info = snapshot("Tower 3", 1000);
+        assert!(system_info_diff(&info, &info).is_empty());
+
+        let cleared = SystemInfo {
+            contact: None,
+            ..info.clone()
+        };
+        assert_eq!(
+            system_info_diff(&info, &cleared),
+            vec![("sysContact", "noc@example.net".to_string(), String::new())]
+        );
+    }
+
+    #[test]
+    fn test_decode_sys_services() {
+        assert_eq!(decode_sys_services(72), vec![4, 7]);
+        assert_eq!(decode_sys_services(2), vec![2]);
+        assert_eq!(decode_sys_services(6), vec![2, 3]);
+        assert_eq!(decode_sys_services(127), vec![1, 2, 3, 4, 5, 6, 7]);
+        assert!(decode_sys_services(0).is_empty());
+        assert!(decode_sys_services(128).is_empty());
+        assert!(decode_sys_services(-1).is_empty());
+    }
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn test_unwrap_uptime_no_wrap() {
+        let start = Instant::now();
+        assert_eq!(unwrap_uptime(None, 12_300, start), Duration::from_secs(123));
+        let later = start + Duration::from_secs(3600);
+        assert_eq!(
+            unwrap_uptime(Some((12_300, start)), 12_300 + 360_000, later),
+            Duration::from_secs(123 + 3600)
+        );
+    }
+
+    #[test]
+    fn test_unwrap_uptime_single_wrap() {
+        let start = Instant::now();
+        let before = u32::MAX - 99; // one second before the wrap
+        let later = start + Duration::from_secs(10);
+        assert_eq!(
+            unwrap_uptime(Some((before, start)), 899, later),
+            Duration::from_millis((UPTIME_WRAP + 899) * 10)
+        );
+    }
+
+    #[test]
+    fn test_unwrap_uptime_multiple_wraps_from_baseline() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(1000 * DAY);
+        // 1000 days is 8_640_000_000 ticks, two wraps and then some
+        let expected = 1000 * DAY * 100;
+        let raw = (expected % UPTIME_WRAP) as u32;
+        assert_eq!(
+            unwrap_uptime(Some((0, start)), raw, later),
+            Duration::from_millis(expected * 10)
+        );
+    }
+
+    #[test]
+    fn test_unwrap_uptime_reboot() {
+        let start = Instant::now();
+        let later = start + Duration::from_secs(DAY);
+        // 30 days up, then restarted two minutes ago
+        let uptime = 30 * DAY as u32 * 100;
+        assert_eq!(
+            unwrap_uptime(Some((uptime, start)), 12_000, later),
+            Duration::from_secs(120)
+        );
+    }
+
+    #[test]
+    fn test_uptime_with_wrap_fetch() {
+        let agent = MockAgent::start(vec![(
+            oid!("1.3.6.1.2.1.1.3.0"),
+            OwnedValue::Timeticks(500),
+        )]);
+        let mut session = agent.session();
+        let clock = FakeClock::new();
+        let before = Some((u32::MAX - 500, clock.now()));
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(
+            session.uptime_with_wrap(before, &clock).unwrap(),
+            Duration::from_millis((UPTIME_WRAP + 500) * 10)
+        );
+        assert_eq!(
+            session.uptime_with_wrap(None, &clock).unwrap(),
+            Duration::from_secs(5)
+        );
+        // a month later the same reading means the agent restarted
+        clock.advance(Duration::from_secs(30 * DAY));
+        assert_eq!(
+            session.uptime_with_wrap(before, &clock).unwrap(),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn test_device_snapshot() {
+        let agent = MockAgent::start(vec![
+            (
+                oid!("1.3.6.1.2.1.1.5.0"),
+                OwnedValue::OctetString(b"ap-01".to_vec()),
+            ),
+            (oid!("1.3.6.1.2.1.1.3.0"), OwnedValue::Timeticks(1000)),
+            (oid!("1.3.6.1.2.1.2.2.1.1.1"), OwnedValue::Integer(1)),
+            (oid!("1.3.6.1.2.1.2.2.1.1.5"), OwnedValue::Integer(5)),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.1"),
+                OwnedValue::OctetString(b"eth0".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.2.2.1.2.5"),
+                OwnedValue::OctetString(b"ath0".to_vec()),
+            ),
+            (oid!("1.3.6.1.2.1.2.2.1.8.5"), OwnedValue::Integer(1)),
+            (
+                oid!("1.3.6.1.2.1.31.1.1.1.6.5"),
+                OwnedValue::Counter64(7_000_000_000),
+            ),
+        ]);
+        let mut session = agent.session();
+
+        let snapshot = device_snapshot(&mut session).unwrap();
+        assert_eq!(snapshot.system.name.as_deref(), Some("ap-01"));
+        assert_eq!(snapshot.system.uptime, Some(1000));
+        assert_eq!(snapshot.system.location, None);
+        assert_eq!(
+            snapshot.interfaces,
+            vec![
+                InterfaceRow {
+                    if_index: 1,
+                    descr: Some("eth0".to_string()),
+                    ..InterfaceRow::default()
+                },
+                InterfaceRow {
+                    if_index: 5,
+                    descr: Some("ath0".to_string()),
+                    oper_status: Some(1),
+                    in_octets: Some(7_000_000_000),
+                    ..InterfaceRow::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_system_info_fetch() {
+        let agent = MockAgent::start(vec![
+            (
+                oid!("1.3.6.1.2.1.1.1.0"),
+                OwnedValue::OctetString(b"AirOS XW.v6.3.6".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.1.2.0"),
+                OwnedValue::ObjectIdentifier("1.3.6.1.4.1.41112".to_string()),
+            ),
+            (oid!("1.3.6.1.2.1.1.3.0"), OwnedValue::Timeticks(1000)),
+            (
+                oid!("1.3.6.1.2.1.1.4.0"),
+                OwnedValue::OctetString(b"noc@example.net".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.1.5.0"),
+                OwnedValue::OctetString(b"ap-01".to_vec()),
+            ),
+            (
+                oid!("1.3.6.1.2.1.1.6.0"),
+                OwnedValue::OctetString(b"Tower 3".to_vec()),
+            ),
+            (oid!("1.3.6.1.2.1.1.7.0"), OwnedValue::Integer(6)),
+        ]);
+        let mut session = agent.session();
+        assert_eq!(
+            system_info(&mut session).unwrap(),
+            snapshot("Tower 3", 1000)
+        );
+    }
+}
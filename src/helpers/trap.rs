@@ -0,0 +1,564 @@
+//! Trap receiver for SNMPv1 and SNMPv2c notifications.
+//!
+//! v1 traps carry the enterprise, agent address, generic/specific codes and
+//! uptime in the PDU header; v2c traps carry uptime and the trap OID as the
+//! first two varbinds (`sysUpTime.0`, `snmpTrapOID.0`). Both are normalized
+//! into [`Trap`].
+
+use std::{
+    io,
+    net::{IpAddr, SocketAddr, ToSocketAddrs, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use crate::{Error, MessageType, Oid, Pdu, Result, Value, BUFFER_SIZE};
+
+use super::value::Varbind;
+
+/// Standard port agents send traps to.
+pub const TRAP_PORT: u16 = 162;
+
+/// A decoded trap, normalized across protocol versions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trap {
+    /// SNMPv1 Trap-PDU.
+    V1 {
+        enterprise: Oid<'static>,
+        agent_addr: IpAddr,
+        /// 0-5 for the standard traps (coldStart .. egpNeighborLoss),
+        /// 6 for enterpriseSpecific.
+        generic: i64,
+        specific: i64,
+        /// Agent uptime in hundredths of a second.
+        uptime: u32,
+        varbinds: Vec<Varbind>,
+    },
+    /// SNMPv2c SNMPv2-Trap-PDU.
+    V2c {
+        trap_oid: Oid<'static>,
+        /// Agent uptime in hundredths of a second.
+        uptime: u32,
+        /// Varbinds following `sysUpTime.0` and `snmpTrapOID.0`.
+        varbinds: Vec<Varbind>,
+    },
+}
+
+impl Trap {
+    /// Decode a trap datagram, tolerating the BER deviations described in
+    /// [`AsnReader`](crate::AsnReader).
+    ///
+    /// Fails with `Error::AsnWrongType` for PDUs that are not traps and with
+    /// `Error::AsnParse` for a v2c trap missing its uptime/trap OID varbinds.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        Self::from_pdu(Pdu::from_bytes_lenient(bytes)?)
+    }
+
+    /// Normalize an already parsed trap PDU.
+    pub fn from_pdu(mut pdu: Pdu) -> Result<Self> {
+        match pdu.message_type {
+            MessageType::TrapV1 => {
+                let info = pdu.v1_trap_info.take().ok_or(Error::AsnParse)?;
+                Ok(Trap::V1 {
+                    enterprise: info.enterprise.to_owned(),
+                    agent_addr: info.agent_addr,
+                    generic: info.generic_trap,
+                    specific: info.specific_trap,
+                    uptime: info.timestamp,
+                    varbinds: pdu.into_owned_varbinds(),
+                })
+            }
+            MessageType::Trap => {
+                let uptime = match pdu.varbinds.next() {
+                    Some((_, Value::Timeticks(ticks))) => ticks,
+                    _ => return Err(Error::AsnParse),
+                };
+                let trap_oid = match pdu.varbinds.next() {
+                    Some((_, Value::ObjectIdentifier(oid))) => oid.to_owned(),
+                    _ => return Err(Error::AsnParse),
+                };
+                Ok(Trap::V2c {
+                    trap_oid,
+                    uptime,
+                    varbinds: pdu.into_owned_varbinds(),
+                })
+            }
+            _ => Err(Error::AsnWrongType),
+        }
+    }
+
+    /// Agent uptime in hundredths of a second.
+    pub fn uptime(&self) -> u32 {
+        match self {
+            Trap::V1 { uptime, .. } | Trap::V2c { uptime, .. } => *uptime,
+        }
+    }
+
+    /// Varbinds carried by the trap, excluding the v2c header varbinds.
+    pub fn varbinds(&self) -> &[Varbind] {
+        match self {
+            Trap::V1 { varbinds, .. } | Trap::V2c { varbinds, .. } => varbinds,
+        }
+    }
+}
+
+/// Blocking UDP listener that decodes incoming v1 and v2c traps.
+///
+/// Community strings are not checked; compare against the raw PDU yourself
+/// if you need to. Sources can be restricted to managed subnets with
+/// [`with_source_filter`](Self::with_source_filter), and trap storms capped
+/// with [`with_rate_limit`](Self::with_rate_limit).
+///
+/// # Examples
+/// ```no_run
+/// use std::net::Ipv4Addr;
+/// use snmp2::helpers::{Trap, TrapListener};
+///
+/// let mut listener = TrapListener::bind_default(Ipv4Addr::UNSPECIFIED)?;
+/// loop {
+///     match listener.recv() {
+///         Ok((source, Trap::V1 { generic, specific, .. })) => {
+///             println!("{}: v1 trap {}/{}", source, generic, specific)
+///         }
+///         Ok((source, Trap::V2c { trap_oid, .. })) => println!("{}: {}", source, trap_oid),
+///         Err(e) => eprintln!("bad trap: {}", e),
+///     }
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct TrapListener {
+    socket: UdpSocket,
+    buf: Vec<u8>,
+    source_filter: Vec<Cidr>,
+    rate_limit: Option<RateLimiter>,
+}
+
+impl TrapListener {
+    /// Bind to `addr`, IPv4 or IPv6; traps are normally sent to port
+    /// [`TRAP_PORT`].
+    ///
+    /// Port 162 is privileged: on Unix, binding it needs root or (on Linux)
+    /// the `CAP_NET_BIND_SERVICE` capability. Without them the error has kind
+    /// `PermissionDenied` and says so. Unprivileged receivers can bind a high
+    /// port and have agents, or a firewall redirect, send traps there.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs()?.collect();
+        let socket = UdpSocket::bind(&addrs[..]).map_err(|e| explain_bind_error(e, &addrs))?;
+        Ok(Self {
+            socket,
+            buf: vec![0; BUFFER_SIZE],
+            source_filter: Vec::new(),
+            rate_limit: None,
+        })
+    }
+
+    /// Bind to [`TRAP_PORT`] on `ip`, e.g. `Ipv6Addr::UNSPECIFIED` to receive
+    /// on all interfaces. See [`bind`](Self::bind) for the privilege caveat.
+    pub fn bind_default<I: Into<IpAddr>>(ip: I) -> io::Result<Self> {
+        Self::bind(default_trap_addr(ip.into()))
+    }
+
+    /// Only accept traps from sources inside `cidr`, e.g. `"10.20.0.0/16"`;
+    /// call again to allow more subnets. A bare address allows that host.
+    ///
+    /// `recv()` silently drops datagrams from any other source. IPv4 senders
+    /// seen as IPv4-mapped IPv6 addresses on a dual-stack socket match IPv4
+    /// subnets.
+    ///
+    /// # Errors
+    /// Fails with `InvalidInput` if `cidr` is not an address with an optional
+    /// `/prefix` no longer than the address.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::Ipv4Addr;
+    /// use snmp2::helpers::TrapListener;
+    ///
+    /// let mut listener = TrapListener::bind_default(Ipv4Addr::UNSPECIFIED)?
+    ///     .with_source_filter("10.20.0.0/16")?
+    ///     .with_source_filter("192.168.1.1")?;
+    /// let (source, trap) = listener.recv()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_source_filter(mut self, cidr: &str) -> io::Result<Self> {
+        let cidr = Cidr::parse(cidr).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid CIDR: {:?}", cidr),
+            )
+        })?;
+        self.source_filter.push(cidr);
+        Ok(self)
+    }
+
+    /// Pass at most `max_per_sec` datagrams per second on to decoding and
+    /// drop the rest, so a trap storm (a flapping link, say) does not bury
+    /// the consumer.
+    ///
+    /// This is lossy by design: dropped traps are gone, only their number
+    /// is kept (see [`dropped_count`](Self::dropped_count)). The limit
+    /// applies per one-second window, after the source filter and before
+    /// decoding. A limit of 0 drops everything.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::net::Ipv4Addr;
+    /// use snmp2::helpers::TrapListener;
+    ///
+    /// let mut listener = TrapListener::bind_default(Ipv4Addr::UNSPECIFIED)?.with_rate_limit(500);
+    /// loop {
+    ///     let (source, trap) = listener.recv()?;
+    ///     println!("{}: {:?} ({} dropped so far)", source, trap, listener.dropped_count());
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_rate_limit(mut self, max_per_sec: u32) -> Self {
+        self.rate_limit = Some(RateLimiter::new(max_per_sec));
+        self
+    }
+
+    /// Number of datagrams dropped by the rate limit so far; always 0
+    /// without one.
+    pub fn dropped_count(&self) -> u64 {
+        self.rate_limit.map_or(0, |limiter| limiter.dropped)
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Wait for the next datagram and decode it as a trap.
+    ///
+    /// A datagram that is not a trap is reported as an error; call `recv()`
+    /// again to keep listening.
+    pub fn recv(&mut self) -> Result<(SocketAddr, Trap)> {
+        loop {
+            let (len, source) = self
+                .socket
+                .recv_from(&mut self.buf)
+                .map_err(|_| Error::Receive)?;
+            if !source_allowed(&self.source_filter, source.ip()) {
+                continue;
+            }
+            if let Some(limiter) = &mut self.rate_limit {
+                if !limiter.allow(Instant::now()) {
+                    continue;
+                }
+            }
+            return Ok((source, Trap::parse(&self.buf[..len])?));
+        }
+    }
+}
+
+/// Admits at most `max_per_sec` events per one-second window and counts
+/// the rest.
+#[derive(Debug, Clone, Copy)]
+struct RateLimiter {
+    max_per_sec: u32,
+    window_start: Option<Instant>,
+    admitted: u32,
+    dropped: u64,
+}
+
+impl RateLimiter {
+    fn new(max_per_sec: u32) -> Self {
+        Self {
+            max_per_sec,
+            window_start: None,
+            admitted: 0,
+            dropped: 0,
+        }
+    }
+
+    fn allow(&mut self, now: Instant) -> bool {
+        match self.window_start {
+            Some(start) if now.saturating_duration_since(start) < Duration::from_secs(1) => {}
+            _ => {
+                self.window_start = Some(now);
+                self.admitted = 0;
+            }
+        }
+        if self.admitted < self.max_per_sec {
+            self.admitted += 1;
+            true
+        } else {
+            self.dropped += 1;
+            false
+        }
+    }
+}
+
+/// An IPv4 or IPv6 subnet, `network/prefix`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    fn parse(s: &str) -> Option<Self> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let network: IpAddr = addr.parse().ok()?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.parse().ok()?,
+            None => max,
+        };
+        (prefix <= max).then_some(Cidr { network, prefix })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            IpAddr::V4(_) => ip,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether `filter` lets a trap from `ip` through; an empty filter allows all.
+fn source_allowed(filter: &[Cidr], ip: IpAddr) -> bool {
+    filter.is_empty() || filter.iter().any(|cidr| cidr.contains(ip))
+}
+
+fn default_trap_addr(ip: IpAddr) -> SocketAddr {
+    SocketAddr::new(ip, TRAP_PORT)
+}
+
+fn explain_bind_error(e: io::Error, addrs: &[SocketAddr]) -> io::Error {
+    match addrs.iter().find(|addr| addr.port() < 1024) {
+        Some(addr) if e.kind() == io::ErrorKind::PermissionDenied => io::Error::new(
+            e.kind(),
+            format!(
+                "cannot bind {}: ports below 1024 need root or CAP_NET_BIND_SERVICE ({})",
+                addr, e
+            ),
+        ),
+        _ => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use super::*;
+    use crate::helpers::OwnedValue;
+    use crate::oid;
+
+    /// enterpriseSpecific trap 1 from 1.3.6.1.4.1.41112, agent 10.0.0.1,
+    /// uptime 12345, carrying ifIndex.2 = 2.
+    const V1_TRAP: &[u8] = &[
+        0x30, 0x3a, 0x02, 0x01, 0x00, 0x04, 0x06, 0x70, 0x75, 0x62, 0x6c, 0x69, 0x63, 0xa4, 0x2d,
+        0x06, 0x08, 0x2b, 0x06, 0x01, 0x04, 0x01, 0x82, 0xc1, 0x18, 0x40, 0x04, 0x0a, 0x00, 0x00,
+        0x01, 0x02, 0x01, 0x06, 0x02, 0x01, 0x01, 0x43, 0x02, 0x30, 0x39, 0x30, 0x11, 0x30, 0x0f,
+        0x06, 0x0a, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x02, 0x02, 0x01, 0x01, 0x02, 0x02, 0x01, 0x02,
+    ];
+
+    /// linkUp with uptime 12345, carrying ifIndex.2 = 2.
+    const V2C_TRAP: &[u8] = &[
+        0x30, 0x52, 0x02, 0x01, 0x01, 0x04, 0x06, 0x70, 0x75, 0x62, 0x6c, 0x69, 0x63, 0xa7, 0x45,
+        0x02, 0x01, 0x01, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00, 0x30, 0x3a, 0x30, 0x0e, 0x06, 0x08,
+        0x2b, 0x06, 0x01, 0x02, 0x01, 0x01, 0x03, 0x00, 0x43, 0x02, 0x30, 0x39, 0x30, 0x17, 0x06,
+        0x0a, 0x2b, 0x06, 0x01, 0x06, 0x03, 0x01, 0x01, 0x04, 0x01, 0x00, 0x06, 0x09, 0x2b, 0x06,
+        0x01, 0x06, 0x03, 0x01, 0x01, 0x05, 0x04, 0x30, 0x0f, 0x06, 0x0a, 0x2b, 0x06, 0x01, 0x02,
+        0x01, 0x02, 0x02, 0x01, 0x01, 0x02, 0x02, 0x01, 0x02,
+    ];
+
+    fn if_index_2() -> Vec<Varbind> {
+        vec![Varbind {
+            oid: oid!("1.3.6.1.2.1.2.2.1.1.2"),
+            value: OwnedValue::Integer(2),
+        }]
+    }
+
+    #[test]
+    fn test_parse_v1_trap() {
+        assert_eq!(
+            Trap::parse(V1_TRAP).unwrap(),
+            Trap::V1 {
+                enterprise: oid!("1.3.6.1.4.1.41112"),
+                agent_addr: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+                generic: 6,
+                specific: 1,
+                uptime: 12345,
+                varbinds: if_index_2(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_v2c_trap() {
+        let trap = Trap::parse(V2C_TRAP).unwrap();
+        assert_eq!(
+            trap,
+            Trap::V2c {
+                trap_oid: oid!("1.3.6.1.6.3.1.1.5.4"),
+                uptime: 12345,
+                varbinds: if_index_2(),
+            }
+        );
+        assert_eq!(trap.uptime(), 12345);
+        assert_eq!(trap.varbinds(), &if_index_2()[..]);
+    }
+
+    #[test]
+    fn test_parse_not_a_trap() {
+        // same packet as a GetRequest
+        let mut request = V2C_TRAP.to_vec();
+        request[13] = 0xa0;
+        assert!(matches!(Trap::parse(&request), Err(Error::AsnWrongType)));
+    }
+
+    #[test]
+    fn test_default_trap_addr() {
+        assert_eq!(
+            default_trap_addr(Ipv4Addr::UNSPECIFIED.into()),
+            "0.0.0.0:162".parse().unwrap()
+        );
+        assert_eq!(
+            default_trap_addr(Ipv6Addr::LOCALHOST.into()),
+            "[::1]:162".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_explain_bind_error() {
+        let denied = || io::Error::from(io::ErrorKind::PermissionDenied);
+        let privileged = ["0.0.0.0:162".parse().unwrap()];
+        let err = explain_bind_error(denied(), &privileged);
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("CAP_NET_BIND_SERVICE"), "{}", err);
+
+        let high = ["0.0.0.0:1162".parse().unwrap()];
+        assert!(!explain_bind_error(denied(), &high)
+            .to_string()
+            .contains("CAP_NET_BIND_SERVICE"));
+        let in_use = io::Error::from(io::ErrorKind::AddrInUse);
+        assert_eq!(
+            explain_bind_error(in_use, &privileged).kind(),
+            io::ErrorKind::AddrInUse
+        );
+    }
+
+    #[test]
+    fn test_cidr_parse() {
+        assert_eq!(
+            Cidr::parse("10.20.0.0/16"),
+            Some(Cidr {
+                network: Ipv4Addr::new(10, 20, 0, 0).into(),
+                prefix: 16
+            })
+        );
+        assert_eq!(Cidr::parse("192.168.1.1").map(|c| c.prefix), Some(32));
+        assert_eq!(Cidr::parse("2001:db8::/32").map(|c| c.prefix), Some(32));
+        assert_eq!(Cidr::parse("::1").map(|c| c.prefix), Some(128));
+        assert_eq!(Cidr::parse("10.0.0.0/33"), None);
+        assert_eq!(Cidr::parse("10.0.0.0/"), None);
+        assert_eq!(Cidr::parse("ap-01/24"), None);
+    }
+
+    #[test]
+    fn test_source_filter() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        let filter = [
+            Cidr::parse("10.20.0.0/16").unwrap(),
+            Cidr::parse("192.168.1.1").unwrap(),
+            Cidr::parse("2001:db8::/32").unwrap(),
+        ];
+        assert!(source_allowed(&filter, ip("10.20.255.1")));
+        assert!(!source_allowed(&filter, ip("10.21.0.1")));
+        assert!(source_allowed(&filter, ip("192.168.1.1")));
+        assert!(!source_allowed(&filter, ip("192.168.1.2")));
+        assert!(source_allowed(&filter, ip("2001:db8:1::5")));
+        assert!(!source_allowed(&filter, ip("2001:db9::5")));
+        assert!(source_allowed(&filter, ip("::ffff:10.20.3.4")));
+        assert!(source_allowed(&[], ip("172.16.0.1")));
+        assert!(source_allowed(
+            &[Cidr::parse("0.0.0.0/0").unwrap()],
+            ip("8.8.8.8")
+        ));
+    }
+
+    #[test]
+    fn test_with_source_filter_invalid() {
+        let listener = TrapListener::bind("127.0.0.1:0").unwrap();
+        let err = listener.with_source_filter("10.0.0.0/40").err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_rate_limiter_burst() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::new(3);
+        let burst: Vec<bool> = (0..10).map(|_| limiter.allow(start)).collect();
+        assert_eq!(burst.iter().filter(|&&allowed| allowed).count(), 3);
+        assert!(burst[..3].iter().all(|&allowed| allowed));
+        assert_eq!(limiter.dropped, 7);
+
+        // same window
+        assert!(!limiter.allow(start + Duration::from_millis(999)));
+        // next window
+        assert!(limiter.allow(start + Duration::from_secs(1)));
+        assert_eq!(limiter.dropped, 8);
+
+        let mut closed = RateLimiter::new(0);
+        assert!(!closed.allow(start));
+        assert_eq!(closed.dropped, 1);
+    }
+
+    #[test]
+    fn test_listener_rate_limit() {
+        let mut listener = TrapListener::bind("127.0.0.1:0")
+            .unwrap()
+            .with_rate_limit(1);
+        listener
+            .socket
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        for _ in 0..3 {
+            sender
+                .send_to(V2C_TRAP, listener.local_addr().unwrap())
+                .unwrap();
+        }
+
+        assert!(listener.recv().is_ok());
+        assert!(matches!(listener.recv(), Err(Error::Receive)));
+        assert_eq!(listener.dropped_count(), 2);
+    }
+
+    #[test]
+    fn test_listener_recv() {
+        let mut listener = TrapListener::bind("127.0.0.1:0").unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender
+            .send_to(V1_TRAP, listener.local_addr().unwrap())
+            .unwrap();
+        sender
+            .send_to(V2C_TRAP, listener.local_addr().unwrap())
+            .unwrap();
+
+        let (source, trap) = listener.recv().unwrap();
+        assert_eq!(source, sender.local_addr().unwrap());
+        assert!(matches!(trap, Trap::V1 { generic: 6, .. }));
+        let (_, trap) = listener.recv().unwrap();
+        assert!(matches!(trap, Trap::V2c { .. }));
+    }
+}
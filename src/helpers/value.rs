@@ -1,4 +1,10 @@
-use crate::Value;
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    net::Ipv4Addr,
+};
+
+use crate::{asn1, AsnReader, Oid, Pdu, Value};
 
 /// Owned necessary for operations like walk where each GETNEXT call
 /// overwrites the session's receive buffer, invalidating any borrowed Values.
@@ -64,6 +70,9 @@ impl OwnedValue {
     }
 
     /// Extract as signed integer if possible
+    ///
+    /// Returns `None` for a Counter64 above `i64::MAX`; use `as_u64` or
+    /// `as_u128` for counters.
     pub fn as_i64(&self) -> Option<i64> {
         match self {
             OwnedValue::Integer(i) => Some(*i),
@@ -87,6 +96,11 @@ impl OwnedValue {
         }
     }
 
+    /// Extract as unsigned integer, widened to `u128`; see `ValueExt::as_u128`.
+    pub fn as_u128(&self) -> Option<u128> {
+        self.as_u64().map(u128::from)
+    }
+
     /// Extract raw bytes if this is an OctetString or Opaque
     pub fn as_bytes(&self) -> Option<&[u8]> {
         match self {
@@ -114,6 +128,20 @@ impl OwnedValue {
         }
     }
 
+    /// Numeric value for metrics pipelines; see `ValueExt::as_metric`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_metric(&self) -> Option<f64> {
+        match self {
+            OwnedValue::Integer(i) => Some(*i as f64),
+            OwnedValue::Counter32(c) => Some(f64::from(*c)),
+            OwnedValue::Unsigned32(u) => Some(f64::from(*u)),
+            OwnedValue::Timeticks(t) => Some(f64::from(*t) / 100.0),
+            OwnedValue::Counter64(c) => Some(*c as f64),
+            OwnedValue::Opaque(o) => opaque_float(o),
+            _ => None,
+        }
+    }
+
     /// Check if this value indicates end-of-mib or no-such-object/instance
     pub fn is_error(&self) -> bool {
         matches!(
@@ -121,6 +149,128 @@ impl OwnedValue {
             OwnedValue::EndOfMibView | OwnedValue::NoSuchObject | OwnedValue::NoSuchInstance
         )
     }
+
+    /// SNMP type name, e.g. `"Counter64"`; the same names as for `Value`.
+    pub fn type_name(&self) -> &'static str {
+        // only the variant matters to `value_type_name`, so the OID payload
+        // is not parsed back
+        let value = match self {
+            OwnedValue::Boolean(b) => Value::Boolean(*b),
+            OwnedValue::Null => Value::Null,
+            OwnedValue::Integer(i) => Value::Integer(*i),
+            OwnedValue::OctetString(s) => Value::OctetString(s),
+            OwnedValue::ObjectIdentifier(_) => {
+                Value::ObjectIdentifier(Oid::new(Cow::Borrowed(&[])))
+            }
+            OwnedValue::IpAddress(ip) => Value::IpAddress(*ip),
+            OwnedValue::Counter32(c) => Value::Counter32(*c),
+            OwnedValue::Unsigned32(u) => Value::Unsigned32(*u),
+            OwnedValue::Timeticks(t) => Value::Timeticks(*t),
+            OwnedValue::Opaque(o) => Value::Opaque(o),
+            OwnedValue::Counter64(c) => Value::Counter64(*c),
+            OwnedValue::EndOfMibView => Value::EndOfMibView,
+            OwnedValue::NoSuchObject => Value::NoSuchObject,
+            OwnedValue::NoSuchInstance => Value::NoSuchInstance,
+        };
+        value_type_name(&value)
+    }
+}
+
+/// A single (OID, value) pair that no longer borrows the receive buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Varbind {
+    pub oid: Oid<'static>,
+    pub value: OwnedValue,
+}
+
+impl<'a> Pdu<'a> {
+    /// Consume the PDU and return all its varbinds as owned values.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?;
+    /// let varbinds = session.get(&oid!("1.3.6.1.2.1.1.1.0"))?.into_owned_varbinds();
+    /// for varbind in &varbinds {
+    ///     println!("{} = {}", varbind.oid, varbind.value.to_string_lossy());
+    /// }
+    /// # Ok::<(), snmp2::Error>(())
+    /// ```
+    pub fn into_owned_varbinds(self) -> Vec<Varbind> {
+        self.varbinds
+            .map(|(oid, value)| Varbind {
+                oid: oid.to_owned(),
+                value: OwnedValue::from_value(&value),
+            })
+            .collect()
+    }
+}
+
+/// Decode an Opaque payload that tunnels a BER SEQUENCE of values.
+///
+/// Some proprietary agents wrap several values in one `Value::Opaque`. This
+/// returns the values of the outer sequence, or `None` if the payload is not a
+/// sequence. Decoding stops at the first element that cannot be parsed, so the
+/// result may be a prefix of the sequence. Nested constructed values come back
+/// as `OwnedValue::Null`.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{decode_opaque_sequence, OwnedValue};
+///
+/// let payload = [0x30, 0x03, 0x02, 0x01, 0x2a];
+/// assert_eq!(decode_opaque_sequence(&payload), Some(vec![OwnedValue::Integer(42)]));
+/// assert_eq!(decode_opaque_sequence(&[0x02, 0x01, 0x2a]), None);
+/// ```
+pub fn decode_opaque_sequence(bytes: &[u8]) -> Option<Vec<OwnedValue>> {
+    let mut reader = AsnReader::from_bytes(bytes);
+    let seq = reader.read_raw(asn1::TYPE_SEQUENCE).ok()?;
+    Some(
+        reader
+            .nested(seq)
+            .map(|value| OwnedValue::from_value(&value))
+            .collect(),
+    )
+}
+
+/// Interpret an OctetString of up to 8 bytes as an unsigned integer.
+///
+/// For vendor MIBs that pack counters into OctetStrings instead of using
+/// Counter64. `big_endian` selects network byte order (most significant
+/// byte first), otherwise the first byte is the least significant. Shorter
+/// inputs are zero-extended; an empty one is 0. Returns `None` for more
+/// than 8 bytes.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::octet_string_to_uint;
+///
+/// assert_eq!(octet_string_to_uint(&[0x01, 0x00], true), Some(256));
+/// assert_eq!(octet_string_to_uint(&[0x01, 0x00], false), Some(1));
+/// assert_eq!(octet_string_to_uint(&[0; 9], true), None);
+/// ```
+pub fn octet_string_to_uint(bytes: &[u8], big_endian: bool) -> Option<u64> {
+    if bytes.len() > 8 {
+        return None;
+    }
+    let mut buf = [0u8; 8];
+    if big_endian {
+        buf[8 - bytes.len()..].copy_from_slice(bytes);
+        Some(u64::from_be_bytes(buf))
+    } else {
+        buf[..bytes.len()].copy_from_slice(bytes);
+        Some(u64::from_le_bytes(buf))
+    }
+}
+
+/// Decode the Net-SNMP Opaque float/double encoding (`9f 78 04 ..` / `9f 79 08 ..`).
+fn opaque_float(bytes: &[u8]) -> Option<f64> {
+    match bytes {
+        [0x9f, 0x78, 0x04, rest @ ..] => Some(f64::from(f32::from_be_bytes(rest.try_into().ok()?))),
+        [0x9f, 0x79, 0x08, rest @ ..] => Some(f64::from_be_bytes(rest.try_into().ok()?)),
+        _ => None,
+    }
 }
 
 /// Extension trait for Value with convenience extraction methods
@@ -132,12 +282,26 @@ pub trait ValueExt {
     fn to_string_lossy(&self) -> String;
 
     /// Extract as signed integer if possible (works for Integer, Counter32, etc.)
+    ///
+    /// Returns `None` for a Counter64 above `i64::MAX`. Don't fall back to
+    /// parsing `to_string_lossy()` in that case; use `as_u64` or `as_u128`.
     fn as_i64(&self) -> Option<i64>;
 
     /// Extract as unsigned integer if possible
     fn as_u64(&self) -> Option<u64>;
 
-    /// Extract as byte slice if this is an OctetString
+    /// Extract as unsigned integer, widened to `u128`
+    ///
+    /// Covers Counter32, Unsigned32 (Gauge32), Timeticks, Counter64 and
+    /// non-negative Integer without loss, so a value that is an integer at all
+    /// always has this extraction path. Returns `None` for negative Integers
+    /// and non-numeric values.
+    fn as_u128(&self) -> Option<u128> {
+        self.as_u64().map(u128::from)
+    }
+
+    /// Extract as byte slice if this is an OctetString, or the inner bytes of
+    /// an Opaque
     fn as_bytes(&self) -> Option<&[u8]>;
 
     /// Extract as UTF-8 string if possible
@@ -145,6 +309,37 @@ pub trait ValueExt {
 
     /// Extract as IPv4 address if this is an IpAddress
     fn as_ipv4(&self) -> Option<std::net::Ipv4Addr>;
+
+    /// Numeric value for metrics pipelines such as Prometheus exporters
+    ///
+    /// Covers Integer, Counter32/64, Unsigned32 (Gauge32), Timeticks (converted
+    /// to seconds) and Opaque-wrapped float/double. Strings, OIDs, null and the
+    /// exception values give `None`. Counter64 values above 2^53 are rounded to
+    /// the nearest representable `f64`, so the low bits are lost.
+    ///
+    /// Defaults to `None`, for implementors with no numeric values.
+    fn as_metric(&self) -> Option<f64> {
+        None
+    }
+
+    /// True for the exception values NoSuchObject, NoSuchInstance and EndOfMibView
+    ///
+    /// Defaults to `false`, for implementors whose values carry no exceptions.
+    fn is_exception(&self) -> bool {
+        false
+    }
+
+    /// The value itself, or `None` for an exception value
+    ///
+    /// Lets callers skip exceptions while extracting, as in
+    /// `value.ok().and_then(|v| v.as_u64())`.
+    fn ok(&self) -> Option<&Self> {
+        if self.is_exception() {
+            None
+        } else {
+            Some(self)
+        }
+    }
 }
 
 impl<'a> ValueExt for Value<'a> {
@@ -175,15 +370,19 @@ impl<'a> ValueExt for Value<'a> {
     }
 
     fn as_bytes(&self) -> Option<&[u8]> {
-        if let Value::OctetString(s) = self {
-            Some(s)
-        } else {
-            None
+        match self {
+            Value::OctetString(s) => Some(s),
+            Value::Opaque(o) => Some(o),
+            _ => None,
         }
     }
 
     fn as_str(&self) -> Option<&str> {
-        self.as_bytes().and_then(|b| std::str::from_utf8(b).ok())
+        if let Value::OctetString(s) = self {
+            std::str::from_utf8(s).ok()
+        } else {
+            None
+        }
     }
 
     fn as_ipv4(&self) -> Option<std::net::Ipv4Addr> {
@@ -193,29 +392,358 @@ impl<'a> ValueExt for Value<'a> {
             None
         }
     }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn as_metric(&self) -> Option<f64> {
+        match self {
+            Value::Integer(i) => Some(*i as f64),
+            Value::Counter32(c) => Some(f64::from(*c)),
+            Value::Unsigned32(u) => Some(f64::from(*u)),
+            Value::Timeticks(t) => Some(f64::from(*t) / 100.0),
+            Value::Counter64(c) => Some(*c as f64),
+            Value::Opaque(o) => opaque_float(o),
+            _ => None,
+        }
+    }
+
+    fn is_exception(&self) -> bool {
+        matches!(
+            self,
+            Value::NoSuchObject | Value::NoSuchInstance | Value::EndOfMibView
+        )
+    }
+}
+
+/// Summary statistics over the numeric values of a walk, see `numeric_stats`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumericStats {
+    /// Number of numeric values that went into the statistics.
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub sum: f64,
+}
+
+/// Count, min, max, mean and sum of the numeric entries in `values`.
+///
+/// Uses the same conversion as `ValueExt::as_metric` (so Timeticks count in
+/// seconds) and skips everything else. Returns `None` if nothing is numeric.
+///
+/// # Examples
+/// ```
+/// use snmp2::{helpers::numeric_stats, Value};
+///
+/// let errors = [Value::Counter32(4), Value::NoSuchInstance, Value::Counter32(0)];
+/// let stats = numeric_stats(&errors).unwrap();
+/// assert_eq!((stats.count, stats.max, stats.mean), (2, 4.0, 2.0));
+/// ```
+pub fn numeric_stats(values: &[Value]) -> Option<NumericStats> {
+    let mut numbers = values.iter().filter_map(ValueExt::as_metric);
+    let first = numbers.next()?;
+    let mut stats = NumericStats {
+        count: 1,
+        min: first,
+        max: first,
+        mean: first,
+        sum: first,
+    };
+    for n in numbers {
+        stats.count += 1;
+        stats.min = stats.min.min(n);
+        stats.max = stats.max.max(n);
+        stats.sum += n;
+    }
+    #[allow(clippy::cast_precision_loss)]
+    {
+        stats.mean = stats.sum / stats.count as f64;
+    }
+    Some(stats)
+}
+
+/// Count the varbinds of each SNMP type, keyed by type name.
+///
+/// A quick profile of an unknown subtree before writing a decoder for it.
+///
+/// # Examples
+/// ```
+/// use snmp2::{oid, helpers::{type_histogram, OwnedValue, Varbind}};
+///
+/// let varbinds = [
+///     Varbind { oid: oid!("1.3.6.1.4.1.9999.1.1"), value: OwnedValue::Counter64(10) },
+///     Varbind { oid: oid!("1.3.6.1.4.1.9999.1.2"), value: OwnedValue::Counter64(20) },
+///     Varbind { oid: oid!("1.3.6.1.4.1.9999.2.1"), value: OwnedValue::Integer(1) },
+/// ];
+/// let histogram = type_histogram(&varbinds);
+/// assert_eq!(histogram["Counter64"], 2);
+/// assert_eq!(histogram["Integer"], 1);
+/// ```
+pub fn type_histogram(varbinds: &[Varbind]) -> BTreeMap<&'static str, usize> {
+    let mut histogram = BTreeMap::new();
+    for varbind in varbinds {
+        *histogram.entry(varbind.value.type_name()).or_insert(0) += 1;
+    }
+    histogram
+}
+
+/// One table row put together by `assemble_rows`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Row {
+    /// The row's index arcs, e.g. `[5]` for ifIndex 5.
+    pub index: Vec<u32>,
+    /// Cells by column name; columns without a value at this index are absent.
+    pub cells: HashMap<String, OwnedValue>,
+}
+
+/// Line up column walks into rows by index.
+///
+/// Each `(name, cells)` in `columns` holds one column's values keyed by the
+/// index arcs below the column OID. A row is produced for every index found
+/// in any column, in index order. Fetching is left to the caller, so the
+/// columns can come from fresh walks or from cached data.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{assemble_rows, OwnedValue};
+///
+/// let descr = vec![(vec![1], OwnedValue::OctetString(b"lo".to_vec()))];
+/// let in_octets = vec![
+///     (vec![1], OwnedValue::Counter32(1200)),
+///     (vec![2], OwnedValue::Counter32(3400)),
+/// ];
+/// let rows = assemble_rows(&[("ifDescr", descr), ("ifInOctets", in_octets)]);
+/// assert_eq!(rows.len(), 2);
+/// assert_eq!(rows[1].index, vec![2]);
+/// assert!(!rows[1].cells.contains_key("ifDescr"));
+/// ```
+pub fn assemble_rows(columns: &[(&str, Vec<(Vec<u32>, OwnedValue)>)]) -> Vec<Row> {
+    let mut rows: BTreeMap<&[u32], HashMap<String, OwnedValue>> = BTreeMap::new();
+    for (name, cells) in columns {
+        for (index, value) in cells {
+            rows.entry(index.as_slice())
+                .or_default()
+                .insert((*name).to_string(), value.clone());
+        }
+    }
+    rows.into_iter()
+        .map(|(index, cells)| Row {
+            index: index.to_vec(),
+            cells,
+        })
+        .collect()
+}
+
+/// Placeholders and formatting choices for `value_to_string_with_opts`.
+///
+/// The default reproduces `value_to_string`. For CSV/TSV exports, set the
+/// placeholders to empty strings or a sentinel the downstream parser expects.
+///
+/// # Examples
+/// ```
+/// use snmp2::{helpers::{value_to_string_with_opts, ValueStringOpts}, Value};
+///
+/// let opts = ValueStringOpts {
+///     null: String::new(),
+///     no_such: Some("-".to_string()),
+///     hex_binary: true,
+///     ..ValueStringOpts::default()
+/// };
+/// assert_eq!(value_to_string_with_opts(&Value::Null, &opts), "");
+/// assert_eq!(value_to_string_with_opts(&Value::NoSuchInstance, &opts), "-");
+/// assert_eq!(value_to_string_with_opts(&Value::OctetString(&[0xde, 0xad]), &opts), "de ad");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueStringOpts {
+    /// Rendering of NULL (default: `"null"`).
+    pub null: String,
+    /// Rendering of value types with no text form (default: `"<unknown>"`).
+    pub unknown: String,
+    /// Rendering of EndOfMibView/NoSuchObject/NoSuchInstance; `None` (the
+    /// default) uses the exception's name.
+    pub no_such: Option<String>,
+    /// Render OctetStrings that are not valid UTF-8 as space-separated
+    /// lowercase hex instead of lossy text. Opaque payloads are always hex.
+    pub hex_binary: bool,
+}
+
+impl Default for ValueStringOpts {
+    fn default() -> Self {
+        Self {
+            null: String::from("null"),
+            unknown: String::from("<unknown>"),
+            no_such: None,
+            hex_binary: false,
+        }
+    }
+}
+
+pub(super) fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decode net-snmp's `Hex-STRING` rendering, `AA BB CC`, back into bytes.
+///
+/// The `Hex-STRING:` tag is optional, digits may be upper- or lowercase, and
+/// any run of whitespace (including the line breaks of a multi-line value)
+/// separates bytes. Every byte must be exactly two hex digits; anything else
+/// gives `None`. An empty value decodes to no bytes.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{parse_hex_string_display, parse_walk_line};
+///
+/// let line = ".1.3.6.1.2.1.2.2.1.6.2 = Hex-STRING: 00 27 22 AA BB CC ";
+/// let (_, _, value) = parse_walk_line(line).unwrap();
+/// let mac = parse_hex_string_display(&value).unwrap();
+/// assert_eq!(mac, [0x00, 0x27, 0x22, 0xaa, 0xbb, 0xcc]);
+/// assert_eq!(parse_hex_string_display("Hex-STRING: de ad"), Some(vec![0xde, 0xad]));
+/// assert_eq!(parse_hex_string_display("0x1f"), None);
+/// ```
+pub fn parse_hex_string_display(s: &str) -> Option<Vec<u8>> {
+    let s = s.trim_start();
+    s.strip_prefix("Hex-STRING:")
+        .unwrap_or(s)
+        .split_whitespace()
+        .map(|byte| {
+            if byte.len() != 2 || !byte.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            u8::from_str_radix(byte, 16).ok()
+        })
+        .collect()
 }
 
 /// Convert an SNMP Value to a string representation
+///
+/// Opaque payloads are rendered as space-separated lowercase hex.
 pub fn value_to_string(value: &Value) -> String {
+    value_to_string_with_opts(value, &ValueStringOpts::default())
+}
+
+/// Convert an SNMP Value to a string using custom placeholders
+pub fn value_to_string_with_opts(value: &Value, opts: &ValueStringOpts) -> String {
+    let exception = |name: &str| opts.no_such.clone().unwrap_or_else(|| name.to_string());
     match value {
         Value::Integer(i) => i.to_string(),
-        Value::OctetString(s) => String::from_utf8_lossy(s).to_string(),
+        Value::OctetString(s) => match std::str::from_utf8(s) {
+            Ok(text) => text.to_string(),
+            Err(_) if opts.hex_binary => hex_bytes(s),
+            Err(_) => String::from_utf8_lossy(s).to_string(),
+        },
         Value::ObjectIdentifier(oid) => oid.to_string(),
         Value::IpAddress(ip) => format!("{}.{}.{}.{}", ip[0], ip[1], ip[2], ip[3]),
         Value::Counter32(c) => c.to_string(),
         Value::Counter64(c) => c.to_string(),
         Value::Timeticks(t) => t.to_string(),
         Value::Unsigned32(u) => u.to_string(),
-        Value::Null => String::from("null"),
-        Value::EndOfMibView => String::from("EndOfMibView"),
-        Value::NoSuchObject => String::from("NoSuchObject"),
-        Value::NoSuchInstance => String::from("NoSuchInstance"),
+        Value::Null => opts.null.clone(),
+        Value::EndOfMibView => exception("EndOfMibView"),
+        Value::NoSuchObject => exception("NoSuchObject"),
+        Value::NoSuchInstance => exception("NoSuchInstance"),
         Value::Boolean(b) => b.to_string(),
-        Value::Opaque(bytes) => format!("Opaque({} bytes)", bytes.len()),
-        _ => String::from("<unknown>"),
+        Value::Opaque(bytes) => hex_bytes(bytes),
+        _ => opts.unknown.clone(),
     }
 }
 
+/// SNMP type name of `value`, e.g. `"OctetString"` or `"NoSuchObject"`.
+pub(super) fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Boolean(_) => "Boolean",
+        Value::Null => "Null",
+        Value::Integer(_) => "Integer",
+        Value::OctetString(_) => "OctetString",
+        Value::ObjectIdentifier(_) => "ObjectIdentifier",
+        Value::Sequence(_) => "Sequence",
+        Value::Set(_) => "Set",
+        Value::Constructed(..) => "Constructed",
+        Value::IpAddress(_) => "IpAddress",
+        Value::Counter32(_) => "Counter32",
+        Value::Unsigned32(_) => "Unsigned32",
+        Value::Timeticks(_) => "Timeticks",
+        Value::Opaque(_) => "Opaque",
+        Value::Counter64(_) => "Counter64",
+        Value::EndOfMibView => "EndOfMibView",
+        Value::NoSuchObject => "NoSuchObject",
+        Value::NoSuchInstance => "NoSuchInstance",
+        Value::GetRequest(_) => "GetRequest",
+        Value::GetNextRequest(_) => "GetNextRequest",
+        Value::GetBulkRequest(_) => "GetBulkRequest",
+        Value::Response(_) => "Response",
+        Value::SetRequest(_) => "SetRequest",
+        Value::InformRequest(_) => "InformRequest",
+        Value::Trap(_) => "Trap",
+        Value::Report(_) => "Report",
+    }
+}
+
+/// INTEGER value for `SyncSession::set`.
+///
+/// # Examples
+/// ```no_run
+/// use snmp2::{oid, SyncSession, helpers::{set_integer, set_string}};
+///
+/// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"private", None, 0)?;
+/// let admin_status = oid!("1.3.6.1.2.1.2.2.1.7.3");
+/// let location = oid!("1.3.6.1.2.1.1.6.0");
+/// session.set(&[(&admin_status, set_integer(2)), (&location, set_string("Tower 3"))])?;
+/// # Ok::<(), snmp2::Error>(())
+/// ```
+pub fn set_integer(i: i64) -> Value<'static> {
+    Value::Integer(i)
+}
+
+/// OCTET STRING value for `SyncSession::set`, borrowing the bytes of `s`.
+pub fn set_string(s: &str) -> Value<'_> {
+    Value::OctetString(s.as_bytes())
+}
+
+/// OBJECT IDENTIFIER value for `SyncSession::set`.
+pub fn set_oid(oid: &Oid) -> Value<'static> {
+    Value::ObjectIdentifier(oid.to_owned())
+}
+
+/// IpAddress value for `SyncSession::set`.
+pub fn set_ip(ip: Ipv4Addr) -> Value<'static> {
+    Value::IpAddress(ip.octets())
+}
+
+/// Opaque value for `SyncSession::set`, borrowing `bytes`.
+///
+/// The bytes are sent as they are inside the Opaque wrapper. Opaque exists to
+/// tunnel values SNMP has no type for, and the agent expects them in its own
+/// encoding (usually a nested BER TLV, as for Net-SNMP's float); producing
+/// that encoding is up to the caller.
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{set_opaque, ValueExt};
+///
+/// // Net-SNMP opaque float 1.5
+/// let value = set_opaque(&[0x9f, 0x78, 0x04, 0x3f, 0xc0, 0x00, 0x00]);
+/// assert_eq!(value.as_metric(), Some(1.5));
+/// assert_eq!(value.as_bytes().map(<[u8]>::len), Some(7));
+/// ```
+pub fn set_opaque(bytes: &[u8]) -> Value<'_> {
+    Value::Opaque(bytes)
+}
+
+/// Opaque value wrapping `bytes`. Same as [`set_opaque`].
+///
+/// # Examples
+/// ```
+/// use snmp2::helpers::{opaque, value_to_string};
+///
+/// assert_eq!(value_to_string(&opaque(&[0x9f, 0x78])), "9f 78");
+/// ```
+pub fn opaque(bytes: &[u8]) -> Value<'_> {
+    set_opaque(bytes)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -256,6 +784,94 @@ mod tests {
         assert!(!OwnedValue::Integer(42).is_error());
     }
 
+    #[test]
+    fn test_into_owned_varbinds() {
+        let sys_descr = Oid::from(&[1, 3, 6, 1, 2, 1, 1, 1, 0]).unwrap();
+        let sys_uptime = Oid::from(&[1, 3, 6, 1, 2, 1, 1, 3, 0]).unwrap();
+        let varbinds = {
+            let mut buf = crate::pdu::Buf::default();
+            crate::pdu::build(
+                crate::Version::V2C,
+                b"public",
+                crate::snmp::MSG_RESPONSE,
+                1,
+                &[
+                    (&sys_descr, Value::OctetString(b"router")),
+                    (&sys_uptime, Value::Timeticks(4200)),
+                ],
+                0,
+                0,
+                &mut buf,
+                #[cfg(feature = "v3")]
+                None,
+            )
+            .unwrap();
+            Pdu::from_bytes(&buf).unwrap().into_owned_varbinds()
+        };
+        assert_eq!(
+            varbinds,
+            vec![
+                Varbind {
+                    oid: sys_descr,
+                    value: OwnedValue::OctetString(b"router".to_vec()),
+                },
+                Varbind {
+                    oid: sys_uptime,
+                    value: OwnedValue::Timeticks(4200),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_opaque_sequence() {
+        let payload = [
+            0x30, 0x0b, // SEQUENCE
+            0x02, 0x01, 0x2a, // INTEGER 42
+            0x04, 0x03, b'a', b'b', b'c', // OCTET STRING "abc"
+            0x41, 0x01, 0x07, // Counter32 7
+        ];
+        assert_eq!(
+            decode_opaque_sequence(&payload),
+            Some(vec![
+                OwnedValue::Integer(42),
+                OwnedValue::OctetString(b"abc".to_vec()),
+                OwnedValue::Counter32(7),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_octet_string_to_uint_byte_order() {
+        let packed = [0x00, 0x00, 0x01, 0x2c];
+        assert_eq!(octet_string_to_uint(&packed, true), Some(300));
+        assert_eq!(octet_string_to_uint(&packed, false), Some(0x2c01_0000));
+        let full = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+        assert_eq!(
+            octet_string_to_uint(&full, true),
+            Some(0x0102_0304_0506_0708)
+        );
+        assert_eq!(
+            octet_string_to_uint(&full, false),
+            Some(0x0807_0605_0403_0201)
+        );
+        assert_eq!(octet_string_to_uint(&[0xff; 8], true), Some(u64::MAX));
+        assert_eq!(octet_string_to_uint(&[], false), Some(0));
+    }
+
+    #[test]
+    fn test_octet_string_to_uint_too_long() {
+        assert_eq!(octet_string_to_uint(&[0; 9], true), None);
+        assert_eq!(octet_string_to_uint(&[1; 16], false), None);
+    }
+
+    #[test]
+    fn test_decode_opaque_sequence_not_a_sequence() {
+        assert_eq!(decode_opaque_sequence(&[0x02, 0x01, 0x2a]), None);
+        assert_eq!(decode_opaque_sequence(&[]), None);
+        assert_eq!(decode_opaque_sequence(&[0x30, 0x05, 0x02]), None);
+    }
+
     #[test]
     fn test_value_ext_integer() {
         let val = Value::Integer(42);
@@ -285,6 +901,27 @@ mod tests {
         assert_eq!(val.as_i64(), None);
     }
 
+    #[test]
+    fn test_as_u128_counter64_max() {
+        let val = Value::Counter64(u64::MAX);
+        assert_eq!(val.as_i64(), None);
+        assert_eq!(val.as_u128(), Some(u128::from(u64::MAX)));
+        assert_eq!(
+            OwnedValue::from_value(&val).as_u128(),
+            Some(18_446_744_073_709_551_615)
+        );
+    }
+
+    #[test]
+    fn test_as_u128_other_types() {
+        assert_eq!(Value::Counter32(7).as_u128(), Some(7));
+        assert_eq!(Value::Timeticks(100).as_u128(), Some(100));
+        assert_eq!(Value::Integer(5).as_u128(), Some(5));
+        assert_eq!(Value::Integer(-5).as_u128(), None);
+        assert_eq!(Value::OctetString(b"5").as_u128(), None);
+        assert_eq!(OwnedValue::Unsigned32(9).as_u128(), Some(9));
+    }
+
     #[test]
     fn test_value_ext_octet_string() {
         let val = Value::OctetString(b"test");
@@ -303,11 +940,306 @@ mod tests {
     #[test]
     fn test_value_ext_ip() {
         let val = Value::IpAddress([192, 168, 1, 1]);
+        assert_eq!(val.as_ipv4(), Some(std::net::Ipv4Addr::new(192, 168, 1, 1)));
+        assert_eq!(val.to_string_lossy(), "192.168.1.1");
+    }
+
+    #[test]
+    fn test_set_value_helpers() {
+        let oid = crate::oid!("1.3.6.1.4.1.41112");
+        assert_eq!(value_to_string(&set_integer(-5)), "-5");
+        assert_eq!(value_to_string(&set_string("Tower 3")), "Tower 3");
+        assert_eq!(value_to_string(&set_oid(&oid)), "1.3.6.1.4.1.41112");
         assert_eq!(
-            val.as_ipv4(),
-            Some(std::net::Ipv4Addr::new(192, 168, 1, 1))
+            value_to_string(&set_ip(Ipv4Addr::new(10, 0, 0, 1))),
+            "10.0.0.1"
         );
-        assert_eq!(val.to_string_lossy(), "192.168.1.1");
+        assert!(matches!(set_string(""), Value::OctetString(&[])));
+    }
+
+    #[test]
+    fn test_set_opaque_round_trip() {
+        let payload = [0x30, 0x03, 0x02, 0x01, 0x2a];
+        let oid = Oid::from(&[1, 3, 6, 1, 4, 1, 9999, 1, 0]).unwrap();
+        let mut buf = crate::pdu::Buf::default();
+        crate::pdu::build_set(
+            crate::Version::V2C,
+            b"private",
+            1,
+            &[(&oid, set_opaque(&payload))],
+            &mut buf,
+            #[cfg(feature = "v3")]
+            None,
+        )
+        .unwrap();
+        let pdu = Pdu::from_bytes(&buf).unwrap();
+        let (_, value) = pdu.varbinds.clone().next().unwrap();
+        assert_eq!(value_type_name(&value), "Opaque");
+        assert_eq!(value.as_bytes(), Some(&payload[..]));
+        assert_eq!(value.as_str(), None);
+        assert_eq!(
+            OwnedValue::from_value(&value),
+            OwnedValue::Opaque(payload.to_vec())
+        );
+    }
+
+    #[test]
+    fn test_as_metric_integers() {
+        assert_eq!(Value::Integer(-7).as_metric(), Some(-7.0));
+        assert_eq!(Value::Counter32(u32::MAX).as_metric(), Some(4294967295.0));
+        assert_eq!(Value::Unsigned32(65).as_metric(), Some(65.0));
+        assert_eq!(Value::Counter64(1 << 40).as_metric(), Some(1099511627776.0));
+    }
+
+    #[test]
+    fn test_as_metric_counter64_precision_loss() {
+        assert_eq!(
+            Value::Counter64(u64::MAX).as_metric(),
+            Some(18446744073709551616.0)
+        );
+    }
+
+    #[test]
+    fn test_as_metric_timeticks_seconds() {
+        assert_eq!(Value::Timeticks(12345).as_metric(), Some(123.45));
+        assert_eq!(OwnedValue::Timeticks(100).as_metric(), Some(1.0));
+    }
+
+    #[test]
+    fn test_as_metric_opaque_float() {
+        let float = [0x9f, 0x78, 0x04, 0x40, 0x49, 0x0f, 0xdb];
+        assert_eq!(
+            Value::Opaque(&float).as_metric(),
+            Some(f64::from(3.1415927f32))
+        );
+        let double = [0x9f, 0x79, 0x08, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0];
+        assert_eq!(OwnedValue::Opaque(double.to_vec()).as_metric(), Some(1.5));
+        assert_eq!(Value::Opaque(&[0x9f, 0x78, 0x04, 0x40]).as_metric(), None);
+        assert_eq!(Value::Opaque(&[0x30, 0x00]).as_metric(), None);
+    }
+
+    #[test]
+    fn test_as_metric_non_numeric() {
+        assert_eq!(Value::OctetString(b"42").as_metric(), None);
+        assert_eq!(Value::Null.as_metric(), None);
+        assert_eq!(Value::IpAddress([10, 0, 0, 1]).as_metric(), None);
+        assert_eq!(Value::NoSuchObject.as_metric(), None);
+        assert_eq!(
+            OwnedValue::ObjectIdentifier("1.3.6.1".into()).as_metric(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_value_to_string_default_opts() {
+        assert_eq!(value_to_string(&Value::Null), "null");
+        assert_eq!(value_to_string(&Value::NoSuchObject), "NoSuchObject");
+        assert_eq!(value_to_string(&Value::Opaque(&[1, 2])), "01 02");
+        assert_eq!(
+            value_to_string(&Value::OctetString(&[0xff, b'a'])),
+            "\u{fffd}a"
+        );
+    }
+
+    #[test]
+    fn test_value_to_string_custom_placeholders() {
+        let opts = ValueStringOpts {
+            null: String::new(),
+            unknown: String::from("?"),
+            no_such: Some(String::from("N/A")),
+            hex_binary: false,
+        };
+        assert_eq!(value_to_string_with_opts(&Value::Null, &opts), "");
+        assert_eq!(
+            value_to_string_with_opts(&Value::EndOfMibView, &opts),
+            "N/A"
+        );
+        assert_eq!(
+            value_to_string_with_opts(&Value::NoSuchObject, &opts),
+            "N/A"
+        );
+        assert_eq!(
+            value_to_string_with_opts(&Value::NoSuchInstance, &opts),
+            "N/A"
+        );
+        let sequence = AsnReader::from_bytes(&[]);
+        assert_eq!(
+            value_to_string_with_opts(&Value::Sequence(sequence), &opts),
+            "?"
+        );
+        assert_eq!(value_to_string_with_opts(&Value::Integer(7), &opts), "7");
+    }
+
+    #[test]
+    fn test_value_to_string_hex_binary() {
+        let opts = ValueStringOpts {
+            hex_binary: true,
+            ..ValueStringOpts::default()
+        };
+        let mac = Value::OctetString(&[0x00, 0x27, 0x22, 0xab, 0xcd, 0xef]);
+        assert_eq!(value_to_string_with_opts(&mac, &opts), "00 27 22 ab cd ef");
+        assert_eq!(
+            value_to_string_with_opts(&Value::OctetString(b"eth0"), &opts),
+            "eth0"
+        );
+        assert_eq!(
+            value_to_string_with_opts(&Value::Opaque(&[0x9f, 0x78]), &opts),
+            "9f 78"
+        );
+    }
+
+    #[test]
+    fn test_parse_hex_string_display_prefixed() {
+        assert_eq!(
+            parse_hex_string_display("Hex-STRING:  00 27\t22  aa BB cC \n"),
+            Some(vec![0x00, 0x27, 0x22, 0xaa, 0xbb, 0xcc])
+        );
+        assert_eq!(parse_hex_string_display("Hex-STRING: "), Some(vec![]));
+    }
+
+    #[test]
+    fn test_parse_hex_string_display_unprefixed() {
+        assert_eq!(
+            parse_hex_string_display("  DE AD\n BE EF "),
+            Some(vec![0xde, 0xad, 0xbe, 0xef])
+        );
+        assert_eq!(parse_hex_string_display("00 2 22"), None);
+        assert_eq!(parse_hex_string_display("00 +2 22"), None);
+        assert_eq!(parse_hex_string_display("0027"), None);
+        assert_eq!(parse_hex_string_display("STRING: \"ap\""), None);
+    }
+
+    #[test]
+    fn test_numeric_stats_mixed() {
+        let values = [
+            Value::Integer(-2),
+            Value::OctetString(b"eth0"),
+            Value::Counter32(10),
+            Value::Null,
+            Value::Unsigned32(4),
+            Value::NoSuchInstance,
+            Value::Counter64(8),
+        ];
+        assert_eq!(
+            numeric_stats(&values),
+            Some(NumericStats {
+                count: 4,
+                min: -2.0,
+                max: 10.0,
+                mean: 5.0,
+                sum: 20.0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_numeric_stats_single() {
+        let stats = numeric_stats(&[Value::Timeticks(250)]).unwrap();
+        assert_eq!(
+            (stats.count, stats.min, stats.max, stats.mean),
+            (1, 2.5, 2.5, 2.5)
+        );
+    }
+
+    #[test]
+    fn test_numeric_stats_none() {
+        assert_eq!(numeric_stats(&[]), None);
+        assert_eq!(
+            numeric_stats(&[Value::OctetString(b"x"), Value::IpAddress([10, 0, 0, 1])]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_value_ext_exceptions() {
+        for val in [
+            Value::NoSuchObject,
+            Value::NoSuchInstance,
+            Value::EndOfMibView,
+        ] {
+            assert!(val.is_exception());
+            assert!(val.ok().is_none());
+            assert_eq!(val.ok().and_then(|v| v.as_u64()), None);
+        }
+    }
+
+    #[test]
+    fn test_value_ext_ok_data() {
+        let val = Value::Counter32(42);
+        assert!(!val.is_exception());
+        assert!(matches!(val.ok(), Some(Value::Counter32(42))));
+        assert_eq!(val.ok().and_then(|v| v.as_u64()), Some(42));
+        assert!(!Value::Null.is_exception());
+        assert!(Value::Null.ok().is_some());
+    }
+
+    #[test]
+    fn test_type_histogram_mixed() {
+        let varbind = |oid: &str, value| Varbind {
+            oid: crate::helpers::parse_oid(oid).unwrap(),
+            value,
+        };
+        let varbinds = [
+            varbind("1.3.6.1.4.1.9999.1.1", OwnedValue::Counter64(1)),
+            varbind("1.3.6.1.4.1.9999.1.2", OwnedValue::Counter64(2)),
+            varbind("1.3.6.1.4.1.9999.1.3", OwnedValue::Counter64(3)),
+            varbind(
+                "1.3.6.1.4.1.9999.2.1",
+                OwnedValue::OctetString(b"a".to_vec()),
+            ),
+            varbind(
+                "1.3.6.1.4.1.9999.3.1",
+                OwnedValue::ObjectIdentifier("1.3.6.1".into()),
+            ),
+            varbind("1.3.6.1.4.1.9999.4.1", OwnedValue::NoSuchInstance),
+        ];
+        let histogram = type_histogram(&varbinds);
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            [
+                ("Counter64", 3),
+                ("NoSuchInstance", 1),
+                ("ObjectIdentifier", 1),
+                ("OctetString", 1),
+            ]
+        );
+        assert!(type_histogram(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_assemble_rows_sparse() {
+        let name = |s: &str| OwnedValue::OctetString(s.as_bytes().to_vec());
+        // ifName has no row 3, ifHCInOctets no row 2; index 10 sorts after 3
+        let rows = assemble_rows(&[
+            (
+                "ifName",
+                vec![
+                    (vec![10], name("Gi0/10")),
+                    (vec![1], name("Gi0/1")),
+                    (vec![2], name("Gi0/2")),
+                ],
+            ),
+            (
+                "ifHCInOctets",
+                vec![
+                    (vec![1], OwnedValue::Counter64(100)),
+                    (vec![3], OwnedValue::Counter64(300)),
+                    (vec![10], OwnedValue::Counter64(1000)),
+                ],
+            ),
+        ]);
+        let indexes: Vec<_> = rows.iter().map(|row| row.index.clone()).collect();
+        assert_eq!(indexes, [vec![1], vec![2], vec![3], vec![10]]);
+        assert_eq!(rows[0].cells.len(), 2);
+        assert_eq!(rows[0].cells["ifName"], name("Gi0/1"));
+        assert_eq!(rows[0].cells["ifHCInOctets"], OwnedValue::Counter64(100));
+        assert_eq!(
+            rows[1].cells,
+            HashMap::from([("ifName".to_string(), name("Gi0/2"))])
+        );
+        assert!(!rows[2].cells.contains_key("ifName"));
+        assert_eq!(rows[3].cells["ifHCInOctets"], OwnedValue::Counter64(1000));
+        assert!(assemble_rows(&[]).is_empty());
     }
 
     #[test]
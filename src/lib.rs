@@ -5,12 +5,12 @@ use std::fmt;
 
 pub mod asn1;
 pub use asn1::AsnReader;
+pub mod helpers;
 #[cfg(feature = "mibs")]
 pub mod mibs;
 pub mod pdu;
 pub mod snmp;
 mod syncsession;
-pub mod helpers;
 
 #[cfg(feature = "v3")]
 pub mod v3;
@@ -22,9 +22,8 @@ pub use asyncsession::AsyncSession;
 
 // Re-export helper utilities for convenience
 pub use helpers::{
-    parse_oid, SessionExt, ValueExt, SnmpClient, OwnedValue,
-    format_mac, format_mac_dashed, parse_mac,
-    meters_to_miles, bits_to_miles,
+    bits_to_miles, format_bps, format_bytes, format_mac, format_mac_dashed, meters_to_miles,
+    parse_mac, parse_oid, OwnedValue, SessionExt, SnmpClient, ValueExt, Varbind,
 };
 
 pub use pdu::Pdu;
@@ -96,14 +95,30 @@ pub enum Error {
     RequestIdMismatch,
     /// Invalid SNMP community string.
     CommunityMismatch,
+    /// Community string contains a NUL or ASCII control character.
+    InvalidCommunity,
+    /// OID component is not a valid number.
+    OidComponent(std::num::ParseIntError),
+    /// OID sub-identifier does not fit in 64 bits.
+    OidArcTooLarge,
     /// Value out of range.
     ValueOutOfRange,
     /// Buffer overflow.
     BufferOverflow,
+    /// A GET response carried no varbinds at all.
+    EmptyResponse,
+    /// A value did not have the expected type; carries the actual type name.
+    UnexpectedType(String),
+    /// Responses added up to more than the configured maximum size.
+    ResponseTooLarge,
+    /// A response carried more varbinds than the configured maximum.
+    TooManyVarbinds,
+    /// The agent refused the request; carries the error-status
+    /// (`snmp::ERRSTATUS_*`).
+    ErrorStatus(u32),
 
     /// Authentication failure
-    #[cfg(feature = "v3")]
-    AuthFailure(v3::AuthErrorKind),
+    AuthFailure(AuthErrorKind),
     /// OpenSSL errors
     #[cfg(feature = "v3")]
     Crypto(String),
@@ -115,8 +130,57 @@ pub enum Error {
     Send,
     /// Socket receive error.
     Receive,
+    /// No acknowledgment arrived before the timeout ran out.
+    Timeout,
     /// MIB errors
     Mib(String),
+    /// Writing walk output failed.
+    Write(String),
+}
+
+/// Reason for an `Error::AuthFailure`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AuthErrorKind {
+    UnsupportedUSM,
+    EngineBootsMismatch,
+    EngineBootsNotProvided,
+    EngineTimeMismatch,
+    NotAuthenticated,
+    UsernameMismatch,
+    EngineIdMismatch,
+    SignatureMismatch,
+    MessageIdMismatch,
+    PrivLengthMismatch,
+    KeyLengthMismatch,
+    PayloadLengthMismatch,
+    ReplyNotEncrypted,
+    SecurityNotProvided,
+    SecurityNotReady,
+    /// A v1/v2c agent explicitly rejected the community string.
+    BadCommunity,
+}
+
+impl fmt::Display for AuthErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthErrorKind::UnsupportedUSM => write!(f, "Unsupported USM"),
+            AuthErrorKind::EngineBootsMismatch => write!(f, "Engine boots counter mismatch"),
+            AuthErrorKind::EngineTimeMismatch => write!(f, "Engine time counter mismatch"),
+            AuthErrorKind::NotAuthenticated => write!(f, "Not authenticated"),
+            AuthErrorKind::EngineBootsNotProvided => write!(f, "Engine boots counter not provided"),
+            AuthErrorKind::EngineIdMismatch => write!(f, "Engine ID mismatch"),
+            AuthErrorKind::UsernameMismatch => write!(f, "Username mismatch"),
+            AuthErrorKind::SignatureMismatch => write!(f, "HMAC signature mismatch"),
+            AuthErrorKind::MessageIdMismatch => write!(f, "Message ID mismatch"),
+            AuthErrorKind::PrivLengthMismatch => write!(f, "Privacy parameters length mismatch"),
+            AuthErrorKind::KeyLengthMismatch => write!(f, "Key length mismatch"),
+            AuthErrorKind::PayloadLengthMismatch => write!(f, "Payload length mismatch"),
+            AuthErrorKind::ReplyNotEncrypted => write!(f, "Not an encrypted reply"),
+            AuthErrorKind::SecurityNotProvided => write!(f, "Security parameters not provided"),
+            AuthErrorKind::SecurityNotReady => write!(f, "Security parameters not ready"),
+            AuthErrorKind::BadCommunity => write!(f, "Community string rejected by agent"),
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -131,9 +195,20 @@ impl fmt::Display for Error {
             Error::UnsupportedVersion => write!(f, "Unsupported SNMP version"),
             Error::RequestIdMismatch => write!(f, "Request ID mismatch"),
             Error::CommunityMismatch => write!(f, "Community string mismatch"),
+            Error::InvalidCommunity => {
+                write!(f, "Community string contains a NUL or control character")
+            }
+            Error::OidComponent(e) => write!(f, "Invalid OID component: {}", e),
+            Error::OidArcTooLarge => write!(f, "OID sub-identifier exceeds 64 bits"),
             Error::ValueOutOfRange => write!(f, "Value out of range"),
             Error::BufferOverflow => write!(f, "Buffer overflow"),
-            #[cfg(feature = "v3")]
+            Error::EmptyResponse => write!(f, "Response contains no varbinds"),
+            Error::UnexpectedType(ref t) => write!(f, "Unexpected value type: {}", t),
+            Error::ResponseTooLarge => write!(f, "Response size limit exceeded"),
+            Error::TooManyVarbinds => write!(f, "Response varbind limit exceeded"),
+            Error::ErrorStatus(status) => {
+                write!(f, "Agent returned {}", snmp::error_status_name(*status))
+            }
             Error::AuthFailure(err) => write!(f, "Authentication failure: {}", err),
             #[cfg(feature = "v3")]
             Error::Crypto(e) => write!(f, "Cryptographic engine error: {}", e),
@@ -143,7 +218,9 @@ impl fmt::Display for Error {
             }
             Error::Send => write!(f, "Socket send error"),
             Error::Receive => write!(f, "Socket receive error"),
+            Error::Timeout => write!(f, "Timed out waiting for acknowledgment"),
             Error::Mib(ref s) => write!(f, "MIB error: {}", s),
+            Error::Write(ref s) => write!(f, "Output write error: {}", s),
         }
     }
 }
@@ -155,7 +232,14 @@ impl From<openssl::error::ErrorStack> for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::OidComponent(e) => Some(e),
+            _ => None,
+        }
+    }
+}
 
 impl From<std::num::TryFromIntError> for Error {
     fn from(_: std::num::TryFromIntError) -> Error {
@@ -249,10 +333,8 @@ impl<'a> Iterator for AsnReader<'a> {
                     .map(Value::ObjectIdentifier),
                 asn1::TYPE_SEQUENCE => self
                     .read_raw(ident)
-                    .map(|v| Value::Sequence(AsnReader::from_bytes(v))),
-                asn1::TYPE_SET => self
-                    .read_raw(ident)
-                    .map(|v| Value::Set(AsnReader::from_bytes(v))),
+                    .map(|v| Value::Sequence(self.nested(v))),
+                asn1::TYPE_SET => self.read_raw(ident).map(|v| Value::Set(self.nested(v))),
                 snmp::TYPE_IPADDRESS => self.read_snmp_ipaddress().map(Value::IpAddress),
                 snmp::TYPE_COUNTER32 => self.read_snmp_counter32().map(Value::Counter32),
                 snmp::TYPE_UNSIGNED32 => self.read_snmp_unsigned32().map(Value::Unsigned32),
@@ -261,31 +343,27 @@ impl<'a> Iterator for AsnReader<'a> {
                 snmp::TYPE_COUNTER64 => self.read_snmp_counter64().map(Value::Counter64),
                 snmp::MSG_GET => self
                     .read_raw(ident)
-                    .map(|v| Value::GetRequest(AsnReader::from_bytes(v))),
+                    .map(|v| Value::GetRequest(self.nested(v))),
                 snmp::MSG_GET_NEXT => self
                     .read_raw(ident)
-                    .map(|v| Value::GetNextRequest(AsnReader::from_bytes(v))),
+                    .map(|v| Value::GetNextRequest(self.nested(v))),
                 snmp::MSG_GET_BULK => self
                     .read_raw(ident)
-                    .map(|v| Value::GetBulkRequest(AsnReader::from_bytes(v))),
+                    .map(|v| Value::GetBulkRequest(self.nested(v))),
                 snmp::MSG_RESPONSE => self
                     .read_raw(ident)
-                    .map(|v| Value::Response(AsnReader::from_bytes(v))),
+                    .map(|v| Value::Response(self.nested(v))),
                 snmp::MSG_SET => self
                     .read_raw(ident)
-                    .map(|v| Value::SetRequest(AsnReader::from_bytes(v))),
+                    .map(|v| Value::SetRequest(self.nested(v))),
                 snmp::MSG_INFORM => self
                     .read_raw(ident)
-                    .map(|v| Value::InformRequest(AsnReader::from_bytes(v))),
-                snmp::MSG_TRAP => self
-                    .read_raw(ident)
-                    .map(|v| Value::Trap(AsnReader::from_bytes(v))),
-                snmp::MSG_REPORT => self
-                    .read_raw(ident)
-                    .map(|v| Value::Report(AsnReader::from_bytes(v))),
+                    .map(|v| Value::InformRequest(self.nested(v))),
+                snmp::MSG_TRAP => self.read_raw(ident).map(|v| Value::Trap(self.nested(v))),
+                snmp::MSG_REPORT => self.read_raw(ident).map(|v| Value::Report(self.nested(v))),
                 ident if ident & asn1::CONSTRUCTED == asn1::CONSTRUCTED => self
                     .read_raw(ident)
-                    .map(|v| Value::Constructed(ident, AsnReader::from_bytes(v))),
+                    .map(|v| Value::Constructed(ident, self.nested(v))),
                 _ => Err(Error::AsnUnsupportedType),
             };
             ret.ok()
@@ -346,13 +424,17 @@ impl<'a> Varbinds<'a> {
             inner: AsnReader::from_bytes(bytes),
         }
     }
+
+    pub(crate) fn from_reader(inner: AsnReader<'a>) -> Varbinds<'a> {
+        Varbinds { inner }
+    }
 }
 
 impl<'a> Iterator for Varbinds<'a> {
     type Item = (Oid<'a>, Value<'a>);
     fn next(&mut self) -> Option<Self::Item> {
         if let Ok(seq) = self.inner.read_raw(asn1::TYPE_SEQUENCE) {
-            let mut pair = AsnReader::from_bytes(seq);
+            let mut pair = self.inner.nested(seq);
             if let (Ok(name), Some(value)) = (pair.read_asn_objectidentifier(), pair.next()) {
                 return Some((name, value));
             }
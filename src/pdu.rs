@@ -2,7 +2,7 @@
 use crate::v3;
 use crate::{
     asn1::{self, AsnReader},
-    snmp, Error, MessageType, Oid, Result, Value, Varbinds, Version, BUFFER_SIZE,
+    snmp, AuthErrorKind, Error, MessageType, Oid, Result, Value, Varbinds, Version, BUFFER_SIZE,
 };
 use std::{
     fmt, mem,
@@ -10,6 +10,10 @@ use std::{
     ops, ptr,
 };
 
+/// SNMPv2-MIB counters some agents report back on a rejected community.
+const SNMP_IN_BAD_COMMUNITY_NAMES: &str = "1.3.6.1.2.1.11.4.0";
+const SNMP_IN_BAD_COMMUNITY_USES: &str = "1.3.6.1.2.1.11.5.0";
+
 pub(crate) struct Buf {
     len: usize,
     buf: [u8; BUFFER_SIZE],
@@ -170,13 +174,29 @@ impl Buf {
         self.push_byte(snmp::TYPE_OPAQUE);
     }
 
-    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
     pub fn push_counter64(&mut self, n: u64) {
-        let len = self.push_i64(n as i64);
+        let len = self.push_u64(n);
         self.push_length(len);
         self.push_byte(snmp::TYPE_COUNTER64);
     }
 
+    /// Minimal unsigned encoding of `n`, with a leading 0x00 when the high
+    /// bit is set so it does not read as negative.
+    pub fn push_u64(&mut self, n: u64) -> usize {
+        let bytes = n.to_be_bytes();
+        let start = bytes
+            .iter()
+            .position(|&b| b != 0)
+            .unwrap_or(bytes.len() - 1);
+        self.push_chunk(&bytes[start..]);
+        if bytes[start] > 127 {
+            self.push_byte(0);
+            bytes.len() - start + 1
+        } else {
+            bytes.len() - start
+        }
+    }
+
     pub fn push_i64(&mut self, mut n: i64) -> usize {
         let (null, num_null_bytes) = if n.is_negative() {
             (0xffu8, ((!n).leading_zeros() / 8) as usize)
@@ -412,6 +432,31 @@ pub(crate) fn build_getnext(
     )
 }
 
+pub(crate) fn build_get_multi(
+    version: Version,
+    community: &[u8],
+    req_id: i32,
+    oids: &[&Oid],
+    buf: &mut Buf,
+    #[cfg(feature = "v3")] security: Option<&v3::Security>,
+) -> Result<()> {
+    build(
+        version,
+        community,
+        snmp::MSG_GET,
+        req_id,
+        oids.iter()
+            .map(|&oid| (oid, Value::Null))
+            .collect::<Vec<_>>()
+            .as_slice(),
+        0,
+        0,
+        buf,
+        #[cfg(feature = "v3")]
+        security,
+    )
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn build_getbulk(
     version: Version,
@@ -500,7 +545,7 @@ impl<'a> Pdu<'a> {
         let specific_code = rdr.read_asn_integer()?;
         let timestamp = rdr.read_snmp_timeticks()?;
         let varbind_bytes = rdr.read_raw(asn1::TYPE_SEQUENCE)?;
-        let varbinds = Varbinds::from_bytes(varbind_bytes);
+        let varbinds = Varbinds::from_reader(rdr.nested(varbind_bytes));
         Ok(Pdu {
             version,
             community,
@@ -524,6 +569,17 @@ impl<'a> Pdu<'a> {
     pub fn from_bytes(bytes: &'a [u8]) -> Result<Pdu<'a>> {
         Self::from_bytes_inner(
             bytes,
+            false,
+            #[cfg(feature = "v3")]
+            None,
+        )
+    }
+
+    /// Parse a PDU tolerating the BER deviations described in [`AsnReader`].
+    pub fn from_bytes_lenient(bytes: &'a [u8]) -> Result<Pdu<'a>> {
+        Self::from_bytes_inner(
+            bytes,
+            true,
             #[cfg(feature = "v3")]
             None,
         )
@@ -535,16 +591,22 @@ impl<'a> Pdu<'a> {
         security: Option<&'a mut v3::Security>,
     ) -> Result<Pdu<'a>> {
         {
-            Self::from_bytes_inner(bytes, security)
+            Self::from_bytes_inner(bytes, false, security)
         }
     }
 
     pub(crate) fn from_bytes_inner(
         bytes: &'a [u8],
+        lenient: bool,
         #[cfg(feature = "v3")] security: Option<&'a mut v3::Security>,
     ) -> Result<Pdu<'a>> {
-        let seq = AsnReader::from_bytes(bytes).read_raw(asn1::TYPE_SEQUENCE)?;
-        let mut rdr = AsnReader::from_bytes(seq);
+        let outer = if lenient {
+            AsnReader::from_bytes_lenient(bytes)
+        } else {
+            AsnReader::from_bytes(bytes)
+        };
+        let seq = outer.clone().read_raw(asn1::TYPE_SEQUENCE)?;
+        let mut rdr = outer.nested(seq);
         let version = rdr.read_asn_integer()?;
         if version != Version::V1 as i64
             && version != Version::V2C as i64
@@ -572,7 +634,7 @@ impl<'a> Pdu<'a> {
         let ident = rdr.peek_byte()?;
         let message_type = MessageType::from_ident(ident)?;
 
-        let mut response_pdu = AsnReader::from_bytes(rdr.read_raw(ident)?);
+        let mut response_pdu = outer.nested(rdr.read_raw(ident)?);
 
         if message_type == MessageType::TrapV1 {
             return Self::parse_trap_v1(response_pdu, version, community);
@@ -594,7 +656,7 @@ impl<'a> Pdu<'a> {
         }
 
         let varbind_bytes = response_pdu.read_raw(asn1::TYPE_SEQUENCE)?;
-        let varbinds = Varbinds::from_bytes(varbind_bytes);
+        let varbinds = Varbinds::from_reader(response_pdu.nested(varbind_bytes));
 
         Ok(Pdu {
             version,
@@ -609,18 +671,56 @@ impl<'a> Pdu<'a> {
             v3_msg_id: 0,
         })
     }
+    /// Whether a v1/v2c agent explicitly rejected our community.
+    ///
+    /// RFC 1157 and RFC 3584 tell agents to silently drop requests with an
+    /// unknown community (Net-SNMP does), so a wrong community usually looks
+    /// exactly like an unreachable host and ends in `Error::Receive`. Only
+    /// some agents answer instead, in one of two shapes recognized here:
+    /// - a Report PDU whose first varbind is `snmpInBadCommunityNames.0` or
+    ///   `snmpInBadCommunityUses.0`;
+    /// - a Response with error-status `authorizationError` (v2c only, v1 has
+    ///   no such status).
+    fn is_community_rejection(&self) -> bool {
+        if self.version == Version::V3 as i64 {
+            return false;
+        }
+        match self.message_type {
+            MessageType::Report => self.varbinds.clone().next().map_or(false, |(oid, _)| {
+                let oid = oid.to_string();
+                oid == SNMP_IN_BAD_COMMUNITY_NAMES || oid == SNMP_IN_BAD_COMMUNITY_USES
+            }),
+            MessageType::Response => self.error_status == snmp::ERRSTATUS_AUTHORIZATIONERROR,
+            _ => false,
+        }
+    }
+
+    /// Fail with `Error::TooManyVarbinds` if there are more than `max`
+    /// varbinds. Stops counting at `max + 1`, so a hostile response costs no
+    /// more than that.
+    pub(crate) fn check_varbind_count(&self, max: usize) -> Result<()> {
+        if self.varbinds.clone().nth(max).is_some() {
+            return Err(Error::TooManyVarbinds);
+        }
+        Ok(())
+    }
+
     pub(crate) fn validate(
         &self,
         expected_type: MessageType,
         expected_req_id: i32,
         expected_community: &[u8],
     ) -> Result<()> {
-        if self.message_type != expected_type {
-            return Err(Error::AsnWrongType);
-        }
+        // a rejection meant for another request is just a stale packet
         if self.req_id != expected_req_id {
             return Err(Error::RequestIdMismatch);
         }
+        if self.is_community_rejection() {
+            return Err(Error::AuthFailure(AuthErrorKind::BadCommunity));
+        }
+        if self.message_type != expected_type {
+            return Err(Error::AsnWrongType);
+        }
         if self.community != expected_community {
             return Err(Error::CommunityMismatch);
         }
@@ -1,5 +1,7 @@
 #![allow(dead_code, clippy::identity_op, clippy::eq_op)]
 
+use std::borrow::Cow;
+
 use crate::asn1;
 
 pub const MSG_GET: u8 = asn1::CLASS_CONTEXTSPECIFIC | asn1::CONSTRUCTED | 0;
@@ -44,6 +46,43 @@ pub const ERRSTATUS_AUTHORIZATIONERROR: u32 = 16;
 pub const ERRSTATUS_NOTWRITABLE: u32 = 17;
 pub const ERRSTATUS_INCONSISTENTNAME: u32 = 18;
 
+/// SNMPv2 name of a PDU error-status code (RFC 3416), e.g. `"noSuchName"`.
+///
+/// Codes outside the defined range come back as `"unknown(N)"`.
+///
+/// # Examples
+/// ```
+/// use snmp2::snmp::{error_status_name, ERRSTATUS_NOTWRITABLE};
+///
+/// assert_eq!(error_status_name(ERRSTATUS_NOTWRITABLE), "notWritable");
+/// assert_eq!(error_status_name(42), "unknown(42)");
+/// ```
+pub fn error_status_name(status: u32) -> Cow<'static, str> {
+    let name = match status {
+        ERRSTATUS_NOERROR => "noError",
+        ERRSTATUS_TOOBIG => "tooBig",
+        ERRSTATUS_NOSUCHNAME => "noSuchName",
+        ERRSTATUS_BADVALUE => "badValue",
+        ERRSTATUS_READONLY => "readOnly",
+        ERRSTATUS_GENERR => "genErr",
+        ERRSTATUS_NOACCESS => "noAccess",
+        ERRSTATUS_WRONGTYPE => "wrongType",
+        ERRSTATUS_WRONGLENGTH => "wrongLength",
+        ERRSTATUS_WRONGENCODING => "wrongEncoding",
+        ERRSTATUS_WRONGVALUE => "wrongValue",
+        ERRSTATUS_NOCREATION => "noCreation",
+        ERRSTATUS_INCONSISTENTVALUE => "inconsistentValue",
+        ERRSTATUS_RESOURCEUNAVAILABLE => "resourceUnavailable",
+        ERRSTATUS_COMMITFAILED => "commitFailed",
+        ERRSTATUS_UNDOFAILED => "undoFailed",
+        ERRSTATUS_AUTHORIZATIONERROR => "authorizationError",
+        ERRSTATUS_NOTWRITABLE => "notWritable",
+        ERRSTATUS_INCONSISTENTNAME => "inconsistentName",
+        _ => return Cow::Owned(format!("unknown({})", status)),
+    };
+    Cow::Borrowed(name)
+}
+
 pub const V3_MSG_FLAGS_REPORTABLE: u8 = 0x04;
 pub const V3_MSG_FLAGS_PRIVACY: u8 = 0x02;
 pub const V3_MSG_FLAGS_AUTH: u8 = 0x01;
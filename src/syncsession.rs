@@ -2,18 +2,26 @@ use std::{
     io,
     net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket},
     num::Wrapping,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use crate::{
     pdu::{self, Pdu},
-    Error, MessageType, Oid, Result, Value, Version, BUFFER_SIZE,
+    snmp, Error, MessageType, Oid, Result, Value, Version, BUFFER_SIZE,
 };
 
 #[cfg(feature = "v3")]
 use crate::v3;
 
 /// Synchronous SNMP client
+///
+/// Each request carries a request ID that moves on to the next value only
+/// once a response to it has been received. A request repeated after a
+/// timeout (a retransmit of the same logical request, as `SnmpClient`
+/// retries are) therefore goes out with the same ID. The agent can then
+/// recognize the duplicate, and a late reply to the first transmission is
+/// still a valid answer to the second instead of being mistaken for the
+/// reply to some other request.
 pub struct SyncSession {
     version: Version,
     socket: UdpSocket,
@@ -21,10 +29,36 @@ pub struct SyncSession {
     req_id: Wrapping<i32>,
     send_pdu: pdu::Buf,
     recv_buf: [u8; BUFFER_SIZE],
+    lenient_decoding: bool,
+    received: ResponseBudget,
+    max_varbinds: usize,
+    deadline: Option<Instant>,
+    base_read_timeout: Option<Duration>,
     #[cfg(feature = "v3")]
     security: Option<v3::Security>,
 }
 
+/// Default for `with_max_varbinds_per_response`: far more than a GETBULK with
+/// a sensible `max_repetitions` returns, far fewer than fit in a datagram.
+const DEFAULT_MAX_VARBINDS: usize = 4096;
+
+/// Running total of response bytes received, against an optional cap.
+#[derive(Debug, Default, Clone, Copy)]
+struct ResponseBudget {
+    max: Option<usize>,
+    used: usize,
+}
+
+impl ResponseBudget {
+    fn charge(&mut self, len: usize) -> Result<()> {
+        self.used = self.used.saturating_add(len);
+        match self.max {
+            Some(max) if self.used > max => Err(Error::ResponseTooLarge),
+            _ => Ok(()),
+        }
+    }
+}
+
 impl SyncSession {
     pub fn new_v1<SA>(
         destination: SA,
@@ -98,6 +132,34 @@ impl SyncSession {
                 ))
             }
         };
+        Self::from_socket(
+            socket,
+            version,
+            destination,
+            community,
+            timeout,
+            starting_req_id,
+        )
+    }
+
+    /// Create a session on a socket the caller already bound.
+    ///
+    /// Use this to control the local address, socket options or lifetime of
+    /// the socket. The session takes ownership, connects the socket to
+    /// `destination` and applies `timeout` to it. For SNMPv3, pass
+    /// `Version::V3` with the user name as `community` and follow with
+    /// `with_security()`.
+    pub fn from_socket<SA>(
+        socket: UdpSocket,
+        version: Version,
+        destination: SA,
+        community: &[u8],
+        timeout: Option<Duration>,
+        starting_req_id: i32,
+    ) -> io::Result<Self>
+    where
+        SA: ToSocketAddrs,
+    {
         socket.set_read_timeout(timeout)?;
         socket.set_write_timeout(timeout)?;
         socket.connect(destination)?;
@@ -108,11 +170,106 @@ impl SyncSession {
             req_id: Wrapping(starting_req_id),
             send_pdu: pdu::Buf::default(),
             recv_buf: [0; BUFFER_SIZE],
+            lenient_decoding: true,
+            received: ResponseBudget::default(),
+            max_varbinds: DEFAULT_MAX_VARBINDS,
+            deadline: None,
+            base_read_timeout: timeout,
             #[cfg(feature = "v3")]
             security: None,
         })
     }
 
+    /// Tolerate non-minimal and indefinite BER lengths in responses.
+    ///
+    /// On by default, since older releases already accepted non-minimal
+    /// lengths; pass `false` for strict decoding. See
+    /// [`AsnReader`](crate::AsnReader) for exactly which deviations are
+    /// accepted.
+    pub fn with_lenient_decoding(mut self, lenient: bool) -> Self {
+        self.lenient_decoding = lenient;
+        self
+    }
+
+    /// Fail with `Error::ResponseTooLarge` once the responses received by
+    /// this session add up to more than `max` bytes (default: unlimited).
+    ///
+    /// A safety valve for memory-constrained pollers against agents that
+    /// return huge OctetStrings or never-ending tables. The count is of whole
+    /// datagrams and runs for the lifetime of the session, so a walk is
+    /// capped as a whole; call `reset_received_bytes()` between operations
+    /// to cap each one separately.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, helpers::SessionExt};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"public", None, 0)?
+    ///     .with_max_response_bytes(1 << 20);
+    /// let routes = session.walk_values(&oid!("1.3.6.1.2.1.4.24.4"))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_max_response_bytes(mut self, max: usize) -> Self {
+        self.received.max = Some(max);
+        self
+    }
+
+    /// Total size of the responses received since the session was created
+    /// or `reset_received_bytes()` was last called.
+    pub fn received_bytes(&self) -> usize {
+        self.received.used
+    }
+
+    /// Restart the count checked against `with_max_response_bytes`.
+    pub fn reset_received_bytes(&mut self) {
+        self.received.used = 0;
+    }
+
+    /// Fail with `Error::TooManyVarbinds` for any response with more than
+    /// `max` varbinds (default: 4096).
+    ///
+    /// Guards against hostile agents that pack thousands of tiny varbinds
+    /// into one datagram to make callers collect them all. The response is
+    /// rejected before any varbind is handed out.
+    pub fn with_max_varbinds_per_response(mut self, max: usize) -> Self {
+        self.max_varbinds = max;
+        self
+    }
+
+    /// Fail requests with `Error::Receive` once `deadline` has passed
+    /// (default: none).
+    ///
+    /// Every request checks the deadline before it is sent and waits for its
+    /// reply no longer than the time left, so a walk made of many requests
+    /// still stops in time. The socket's read timeout applies when it is
+    /// shorter and is restored when the deadline is removed with `None`.
+    pub fn set_deadline(&mut self, deadline: Option<Instant>) {
+        if self.deadline.is_none() {
+            self.base_read_timeout = self.socket.read_timeout().unwrap_or(self.base_read_timeout);
+        } else if deadline.is_none() {
+            let _ = self.socket.set_read_timeout(self.base_read_timeout);
+        }
+        self.deadline = deadline;
+    }
+
+    /// Check the deadline before a request and cap the read timeout to the
+    /// time left.
+    fn cap_to_deadline(&self) -> Result<()> {
+        let Some(deadline) = self.deadline else {
+            return Ok(());
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(Error::Receive);
+        }
+        let timeout = self
+            .base_read_timeout
+            .map_or(remaining, |timeout| timeout.min(remaining));
+        self.socket
+            .set_read_timeout(Some(timeout))
+            .map_err(|_| Error::Receive)
+    }
+
     #[cfg(feature = "v3")]
     pub fn with_security(mut self, mut security: v3::Security) -> Result<Self> {
         security.username = self.community.clone();
@@ -125,14 +282,54 @@ impl SyncSession {
         Ok(self)
     }
 
+    /// Access the underlying UDP socket, e.g. to set extra socket options.
+    ///
+    /// **Warning:** the socket is connected to the agent and shared with all
+    /// SNMP requests of this session. Sending or receiving your own datagrams
+    /// on it can consume or corrupt replies to pending SNMP exchanges, and
+    /// changing the read timeout changes how long requests wait.
+    pub fn socket(&self) -> &UdpSocket {
+        &self.socket
+    }
+
+    /// Mutable access to the underlying UDP socket. See [`SyncSession::socket`]
+    /// for the caveats.
+    pub fn socket_mut(&mut self) -> &mut UdpSocket {
+        &mut self.socket
+    }
+
+    /// Discard any datagrams already queued on the socket, without blocking.
+    ///
+    /// Every request does this before sending, so a late reply to an earlier,
+    /// timed-out request is not read as the answer to the next one (responses
+    /// are also checked against the request ID, which would turn such a
+    /// reply into `Error::RequestIdMismatch`). Call it directly after reading
+    /// from the socket through `socket_mut()`.
+    pub fn drain_stale(&mut self) {
+        Self::drain(&self.socket, &mut self.recv_buf);
+    }
+
+    fn drain(socket: &UdpSocket, buf: &mut [u8]) {
+        if socket.set_nonblocking(true).is_err() {
+            return;
+        }
+        while socket.recv(buf).is_ok() {}
+        let _ = socket.set_nonblocking(false);
+    }
+
     fn send_and_recv<'a>(
         socket: &UdpSocket,
         pdu: &pdu::Buf,
         out: &'a mut [u8],
+        received: &mut ResponseBudget,
     ) -> Result<&'a [u8]> {
+        Self::drain(socket, out);
         if let Ok(_pdu_len) = socket.send(pdu) {
             match socket.recv(out) {
-                Ok(len) => Ok(&out[..len]),
+                Ok(len) => {
+                    received.charge(len)?;
+                    Ok(&out[..len])
+                }
                 Err(_) => Err(Error::Receive),
             }
         } else {
@@ -147,6 +344,7 @@ impl SyncSession {
 
     #[cfg(feature = "v3")]
     pub fn init(&mut self) -> Result<()> {
+        self.cap_to_deadline()?;
         if let Some(ref mut security) = self.security {
             security.reset_engine_id();
             security.reset_engine_counters();
@@ -155,7 +353,13 @@ impl SyncSession {
             v3::build_init(req_id, &mut self.send_pdu);
             self.req_id += Wrapping(1);
             if let Err(e) = Pdu::from_bytes_inner(
-                Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf)?,
+                Self::send_and_recv(
+                    &self.socket,
+                    &self.send_pdu,
+                    &mut self.recv_buf,
+                    &mut self.received,
+                )?,
+                self.lenient_decoding,
                 Some(security),
             ) {
                 if e != Error::AuthUpdated {
@@ -182,6 +386,7 @@ impl SyncSession {
 
     pub fn get(&mut self, oid: &Oid) -> Result<Pdu> {
         self.prepare();
+        self.cap_to_deadline()?;
         let req_id = self.req_id.0;
         pdu::build_get(
             self.version,
@@ -193,17 +398,56 @@ impl SyncSession {
             self.security.as_ref(),
         )?;
         let resp = Pdu::from_bytes_inner(
-            Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf)?,
+            Self::send_and_recv(
+                &self.socket,
+                &self.send_pdu,
+                &mut self.recv_buf,
+                &mut self.received,
+            )?,
+            self.lenient_decoding,
+            #[cfg(feature = "v3")]
+            self.security.as_mut(),
+        )?;
+        self.req_id += Wrapping(1);
+        resp.validate(MessageType::Response, req_id, &self.community)?;
+        resp.check_varbind_count(self.max_varbinds)?;
+        Ok(resp)
+    }
+
+    /// GET several OIDs in a single request.
+    pub fn get_multi(&mut self, oids: &[&Oid]) -> Result<Pdu> {
+        self.prepare();
+        self.cap_to_deadline()?;
+        let req_id = self.req_id.0;
+        pdu::build_get_multi(
+            self.version,
+            self.community.as_slice(),
+            req_id,
+            oids,
+            &mut self.send_pdu,
+            #[cfg(feature = "v3")]
+            self.security.as_ref(),
+        )?;
+        let resp = Pdu::from_bytes_inner(
+            Self::send_and_recv(
+                &self.socket,
+                &self.send_pdu,
+                &mut self.recv_buf,
+                &mut self.received,
+            )?,
+            self.lenient_decoding,
             #[cfg(feature = "v3")]
             self.security.as_mut(),
         )?;
         self.req_id += Wrapping(1);
         resp.validate(MessageType::Response, req_id, &self.community)?;
+        resp.check_varbind_count(self.max_varbinds)?;
         Ok(resp)
     }
 
     pub fn getnext(&mut self, oid: &Oid) -> Result<Pdu> {
         self.prepare();
+        self.cap_to_deadline()?;
         let req_id = self.req_id.0;
         pdu::build_getnext(
             self.version,
@@ -215,12 +459,19 @@ impl SyncSession {
             self.security.as_ref(),
         )?;
         let resp = Pdu::from_bytes_inner(
-            Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf)?,
+            Self::send_and_recv(
+                &self.socket,
+                &self.send_pdu,
+                &mut self.recv_buf,
+                &mut self.received,
+            )?,
+            self.lenient_decoding,
             #[cfg(feature = "v3")]
             self.security.as_mut(),
         )?;
         self.req_id += Wrapping(1);
         resp.validate(MessageType::Response, req_id, &self.community)?;
+        resp.check_varbind_count(self.max_varbinds)?;
         Ok(resp)
     }
 
@@ -231,6 +482,7 @@ impl SyncSession {
         max_repetitions: u32,
     ) -> Result<Pdu> {
         self.prepare();
+        self.cap_to_deadline()?;
         let req_id = self.req_id.0;
         pdu::build_getbulk(
             self.version,
@@ -244,17 +496,159 @@ impl SyncSession {
             self.security.as_ref(),
         )?;
         let resp = Pdu::from_bytes_inner(
-            Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf)?,
+            Self::send_and_recv(
+                &self.socket,
+                &self.send_pdu,
+                &mut self.recv_buf,
+                &mut self.received,
+            )?,
+            self.lenient_decoding,
             #[cfg(feature = "v3")]
             self.security.as_mut(),
         )?;
         self.req_id += Wrapping(1);
         resp.validate(MessageType::Response, req_id, &self.community)?;
+        resp.check_varbind_count(self.max_varbinds)?;
         Ok(resp)
     }
 
+    /// Send an InformRequest and wait for the receiver to acknowledge it.
+    ///
+    /// Unlike traps, informs are confirmed: the receiver answers with a
+    /// Response carrying the same request ID. If none arrives within
+    /// `timeout`, the same inform (same request ID, so the receiver can
+    /// discard duplicates) is sent again, up to `retries` more times. Replies
+    /// with another request ID are ignored. Per RFC 3416 the first two
+    /// varbinds must be `sysUpTime.0` and `snmpTrapOID.0`.
+    ///
+    /// The session timeout is left unchanged afterwards.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use snmp2::{oid, SyncSession, Value};
+    ///
+    /// let mut session = SyncSession::new_v2c("10.0.0.2:162", b"public", None, 0)?;
+    /// let link_up = oid!("1.3.6.1.6.3.1.1.5.4");
+    /// session.send_inform(
+    ///     &[
+    ///         (&oid!("1.3.6.1.2.1.1.3.0"), Value::Timeticks(12345)),
+    ///         (&oid!("1.3.6.1.6.3.1.1.4.1.0"), Value::ObjectIdentifier(link_up)),
+    ///     ],
+    ///     Duration::from_secs(1),
+    ///     3,
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns `Error::Timeout` if no acknowledgment arrived after the last
+    /// retransmission.
+    pub fn send_inform(
+        &mut self,
+        varbinds: &[(&Oid, Value)],
+        timeout: Duration,
+        retries: u32,
+    ) -> Result<()> {
+        self.prepare();
+        let req_id = self.req_id.0;
+        pdu::build(
+            self.version,
+            self.community.as_slice(),
+            snmp::MSG_INFORM,
+            req_id,
+            varbinds,
+            0,
+            0,
+            &mut self.send_pdu,
+            #[cfg(feature = "v3")]
+            self.security.as_ref(),
+        )?;
+        self.req_id += Wrapping(1);
+        let session_timeout = self.socket.read_timeout().map_err(|_| Error::Receive)?;
+        let result = self.await_inform_ack(req_id, timeout, retries);
+        let _ = self.socket.set_read_timeout(session_timeout);
+        result
+    }
+
+    fn await_inform_ack(&mut self, req_id: i32, timeout: Duration, retries: u32) -> Result<()> {
+        Self::drain(&self.socket, &mut self.recv_buf);
+        for _ in 0..=retries {
+            self.socket.send(&self.send_pdu).map_err(|_| Error::Send)?;
+            let deadline = Instant::now() + timeout;
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                self.socket
+                    .set_read_timeout(Some(remaining))
+                    .map_err(|_| Error::Receive)?;
+                let len = match self.socket.recv(&mut self.recv_buf) {
+                    Ok(len) => len,
+                    Err(e) if is_timeout(&e) => break,
+                    Err(_) => return Err(Error::Receive),
+                };
+                self.received.charge(len)?;
+                let Ok(ack) = Pdu::from_bytes_inner(
+                    &self.recv_buf[..len],
+                    self.lenient_decoding,
+                    #[cfg(feature = "v3")]
+                    self.security.as_mut(),
+                ) else {
+                    continue;
+                };
+                if ack.message_type == MessageType::Response && ack.req_id == req_id {
+                    return ack.validate(MessageType::Response, req_id, &self.community);
+                }
+            }
+        }
+        Err(Error::Timeout)
+    }
+
+    /// Send a SET for a single OID without waiting for the response.
+    ///
+    /// For writes that take the agent down before it can answer, such as a
+    /// reboot or a config-save trigger that restarts the management plane.
+    /// A normal `set()` would report those as timeouts even though they
+    /// worked.
+    ///
+    /// **Warning:** there is no confirmation at all. The SET may have been
+    /// lost on the way, rejected (wrong type, not writable, bad community) or
+    /// applied; `Ok(())` only means the datagram was sent. A late response
+    /// is discarded by the next request on this session. Check the effect
+    /// some other way, e.g. wait for the device to come back and compare its
+    /// sysUpTime.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use snmp2::{oid, SyncSession, Value};
+    ///
+    /// let mut session = SyncSession::new_v2c("192.168.1.1:161", b"private", None, 0)?;
+    /// // vendor-specific reboot trigger
+    /// session.set_no_wait(&oid!("1.3.6.1.4.1.9.2.9.9.0"), Value::Integer(2))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_no_wait(&mut self, oid: &Oid, value: Value) -> Result<()> {
+        self.prepare();
+        let req_id = self.req_id.0;
+        pdu::build_set(
+            self.version,
+            self.community.as_slice(),
+            req_id,
+            &[(oid, value)],
+            &mut self.send_pdu,
+            #[cfg(feature = "v3")]
+            self.security.as_ref(),
+        )?;
+        self.req_id += Wrapping(1);
+        self.socket.send(&self.send_pdu).map_err(|_| Error::Send)?;
+        Ok(())
+    }
+
     pub fn set(&mut self, values: &[(&Oid, Value)]) -> Result<Pdu> {
         self.prepare();
+        self.cap_to_deadline()?;
         let req_id = self.req_id.0;
         pdu::build_set(
             self.version,
@@ -266,12 +660,28 @@ impl SyncSession {
             self.security.as_ref(),
         )?;
         let resp = Pdu::from_bytes_inner(
-            Self::send_and_recv(&self.socket, &self.send_pdu, &mut self.recv_buf)?,
+            Self::send_and_recv(
+                &self.socket,
+                &self.send_pdu,
+                &mut self.recv_buf,
+                &mut self.received,
+            )?,
+            self.lenient_decoding,
             #[cfg(feature = "v3")]
             self.security.as_mut(),
         )?;
         self.req_id += Wrapping(1);
         resp.validate(MessageType::Response, req_id, &self.community)?;
+        resp.check_varbind_count(self.max_varbinds)?;
         Ok(resp)
     }
 }
+
+/// A blocking `recv` that ran out of time reports `WouldBlock` on Unix and
+/// `TimedOut` on Windows.
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(
+        e.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
@@ -1,7 +1,7 @@
-use crate::{MessageType, Pdu, Value};
+use crate::{MessageType, Pdu, Value, ValueExt};
 
-use super::{pdu, snmp, Oid};
-use super::{AsnReader, Error, Version};
+use super::{asn1, pdu, snmp, Oid};
+use super::{AsnReader, AuthErrorKind, Error, Version};
 
 #[test]
 fn build_getnext_pdu() {
@@ -78,6 +78,325 @@ fn build_reply_pdu() {
     assert_eq!(pdu.error_index, 1);
 }
 
+/// GETNEXT from `asn_parse_getnext_pdu` with the outer length encoded as `0x81 0x2b`.
+const NON_MINIMAL_LEN_PDU: &[u8] = &[
+    0x30, 0x81, 0x2b, 0x02, 0x01, 0x01, 0x04, 0x08, 0x74, 0x79, 0x53, 0x30, 0x6e, 0x34, 0x33, 0x64,
+    0xa1, 0x1c, 0x02, 0x04, 0x4a, 0x9b, 0x6b, 0xa2, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00, 0x30, 0x0e,
+    0x30, 0x0c, 0x06, 0x08, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00, 0x05, 0x00,
+];
+
+#[test]
+fn parse_non_minimal_length_strict() {
+    assert_eq!(
+        Pdu::from_bytes(NON_MINIMAL_LEN_PDU).unwrap_err(),
+        Error::AsnInvalidLen
+    );
+}
+
+#[test]
+fn parse_non_minimal_length_lenient() {
+    let pdu = Pdu::from_bytes_lenient(NON_MINIMAL_LEN_PDU).unwrap();
+    assert_eq!(pdu.message_type, MessageType::GetNextRequest);
+    assert_eq!(pdu.req_id, 1_251_699_618);
+    assert_eq!(pdu.community, b"tyS0n43d");
+}
+
+#[test]
+fn parse_indefinite_length_lenient() {
+    // varbind list and outer sequence use indefinite lengths
+    let bytes = &[
+        0x30, 0x80, 0x02, 0x01, 0x01, 0x04, 0x08, 0x74, 0x79, 0x53, 0x30, 0x6e, 0x34, 0x33, 0x64,
+        0xa2, 0x1f, 0x02, 0x04, 0x4a, 0x9b, 0x6b, 0xa2, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00, 0x30,
+        0x80, 0x30, 0x0d, 0x06, 0x08, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00, 0x02, 0x01,
+        0x2a, 0x00, 0x00, 0x00, 0x00,
+    ];
+    assert_eq!(Pdu::from_bytes(bytes).unwrap_err(), Error::AsnInvalidLen);
+    let mut pdu = Pdu::from_bytes_lenient(bytes).unwrap();
+    assert_eq!(pdu.message_type, MessageType::Response);
+    let (name, value) = pdu.varbinds.next().unwrap();
+    assert_eq!(name, Oid::from(&[1, 3, 6, 1, 2, 1, 1, 1, 0]).unwrap());
+    assert!(matches!(value, Value::Integer(42)));
+}
+
+#[test]
+fn parse_deeply_nested_indefinite_length_lenient() {
+    // `30 80` nested far beyond the limit must fail, not overflow the stack
+    let mut bytes = [0x30, 0x80].repeat(100_000);
+    bytes.extend(std::iter::repeat(0x00).take(200_000));
+    assert_eq!(
+        Pdu::from_bytes_lenient(&bytes).unwrap_err(),
+        Error::AsnInvalidLen
+    );
+    let mut reader = AsnReader::from_bytes_lenient(&bytes);
+    assert_eq!(
+        reader.read_raw(asn1::TYPE_SEQUENCE),
+        Err(Error::AsnInvalidLen)
+    );
+}
+
+/// v2c Response with high-bit-set unsigned values: Counter32 0xffffffff,
+/// an out-of-range 33-bit Counter32 (`01 00 00 00 05`) and Counter64 2^63.
+const HIGH_BIT_UNSIGNED_RESPONSE: &[u8] = &[
+    0x30, 0x5c, 0x02, 0x01, 0x01, 0x04, 0x06, 0x70, 0x75, 0x62, 0x6c, 0x69, 0x63, 0xa2, 0x4f, 0x02,
+    0x01, 0x01, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00, 0x30, 0x44, 0x30, 0x13, 0x06, 0x0a, 0x2b, 0x06,
+    0x01, 0x02, 0x01, 0x02, 0x02, 0x01, 0x0a, 0x01, 0x41, 0x05, 0x00, 0xff, 0xff, 0xff, 0xff, 0x30,
+    0x13, 0x06, 0x0a, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x02, 0x02, 0x01, 0x10, 0x01, 0x41, 0x05, 0x01,
+    0x00, 0x00, 0x00, 0x05, 0x30, 0x18, 0x06, 0x0b, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x1f, 0x01, 0x01,
+    0x01, 0x06, 0x01, 0x46, 0x09, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+#[test]
+fn parse_high_bit_unsigned_values() {
+    let pdu = Pdu::from_bytes(HIGH_BIT_UNSIGNED_RESPONSE).unwrap();
+    let values: Vec<_> = pdu.varbinds.map(|(_, value)| value).collect();
+    assert_eq!(values[0].as_u64(), Some(4_294_967_295));
+    // the out-of-range Counter32 is truncated and does not end the varbinds
+    assert!(matches!(values[1], Value::Counter32(5)));
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[2].as_u64(), Some(1 << 63));
+}
+
+#[test]
+fn counter64_high_bit_round_trip() {
+    let oid = Oid::from(&[1, 3, 6, 1, 2, 1, 31, 1, 1, 1, 6, 1]).unwrap();
+    for n in [0, 1 << 63, u64::MAX] {
+        let mut buf = pdu::Buf::default();
+        pdu::build(
+            Version::V2C,
+            b"public",
+            snmp::MSG_RESPONSE,
+            1,
+            &[(&oid, Value::Counter64(n))],
+            0,
+            0,
+            &mut buf,
+            #[cfg(feature = "v3")]
+            None,
+        )
+        .unwrap();
+        let mut pdu = Pdu::from_bytes(&buf).unwrap();
+        let (_, value) = pdu.varbinds.next().unwrap();
+        assert_eq!(value.as_u64(), Some(n));
+    }
+}
+
+#[test]
+fn asn_unsigned_without_leading_zero() {
+    // Counter32 4294967295 as sent by agents that treat it as signed
+    let mut reader = AsnReader::from_bytes(&[0x41, 0x04, 0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(reader.read_snmp_counter32(), Ok(u32::MAX));
+    let mut reader =
+        AsnReader::from_bytes(&[0x46, 0x08, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+    assert_eq!(reader.read_snmp_counter64(), Ok(u64::MAX));
+    let mut reader = AsnReader::from_bytes(&[0x46, 0x01, 0x00]);
+    assert_eq!(reader.read_snmp_counter64(), Ok(0));
+}
+
+/// v2c Report carrying `snmpInBadCommunityNames.0 = 5`, as sent by agents
+/// that answer a wrong community instead of dropping it.
+const BAD_COMMUNITY_REPORT: &[u8] = &[
+    0x30, 0x27, 0x02, 0x01, 0x01, 0x04, 0x06, 0x70, 0x75, 0x62, 0x6c, 0x69, 0x63, 0xa8, 0x1a, 0x02,
+    0x01, 0x01, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00, 0x30, 0x0f, 0x30, 0x0d, 0x06, 0x08, 0x2b, 0x06,
+    0x01, 0x02, 0x01, 0x0b, 0x04, 0x00, 0x41, 0x01, 0x05,
+];
+
+#[test]
+fn bad_community_report_is_auth_failure() {
+    let pdu = Pdu::from_bytes(BAD_COMMUNITY_REPORT).unwrap();
+    assert_eq!(pdu.message_type, MessageType::Report);
+    assert_eq!(
+        pdu.validate(MessageType::Response, 1, b"public")
+            .unwrap_err(),
+        Error::AuthFailure(AuthErrorKind::BadCommunity)
+    );
+}
+
+#[test]
+fn authorization_error_response_is_auth_failure() {
+    let mut bytes = BAD_COMMUNITY_REPORT.to_vec();
+    bytes[13] = snmp::MSG_RESPONSE;
+    let pdu = Pdu::from_bytes(&bytes).unwrap();
+    assert!(pdu.validate(MessageType::Response, 1, b"public").is_ok());
+
+    bytes[20] = 16; // authorizationError
+    let pdu = Pdu::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        pdu.validate(MessageType::Response, 1, b"public")
+            .unwrap_err(),
+        Error::AuthFailure(AuthErrorKind::BadCommunity)
+    );
+}
+
+#[test]
+fn stale_community_rejection_is_req_id_mismatch() {
+    let pdu = Pdu::from_bytes(BAD_COMMUNITY_REPORT).unwrap();
+    assert_eq!(
+        pdu.validate(MessageType::Response, 2, b"public")
+            .unwrap_err(),
+        Error::RequestIdMismatch
+    );
+
+    let mut bytes = BAD_COMMUNITY_REPORT.to_vec();
+    bytes[13] = snmp::MSG_RESPONSE;
+    bytes[20] = 16; // authorizationError
+    let pdu = Pdu::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        pdu.validate(MessageType::Response, 2, b"public")
+            .unwrap_err(),
+        Error::RequestIdMismatch
+    );
+}
+
+#[test]
+fn unrelated_report_is_wrong_type() {
+    let mut bytes = BAD_COMMUNITY_REPORT.to_vec();
+    bytes[36] = 0x03; // snmpInBadVersions.0
+    let pdu = Pdu::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        pdu.validate(MessageType::Response, 1, b"public")
+            .unwrap_err(),
+        Error::AsnWrongType
+    );
+}
+
+fn send_test_inform(
+    session: &mut crate::SyncSession,
+    timeout_ms: u64,
+    retries: u32,
+) -> crate::Result<()> {
+    let uptime = Oid::from(&[1, 3, 6, 1, 2, 1, 1, 3, 0]).unwrap();
+    let trap_oid = Oid::from(&[1, 3, 6, 1, 6, 3, 1, 1, 4, 1, 0]).unwrap();
+    let link_up = Oid::from(&[1, 3, 6, 1, 6, 3, 1, 1, 5, 4]).unwrap();
+    session.send_inform(
+        &[
+            (&uptime, Value::Timeticks(100)),
+            (&trap_oid, Value::ObjectIdentifier(link_up)),
+        ],
+        std::time::Duration::from_millis(timeout_ms),
+        retries,
+    )
+}
+
+#[test]
+fn inform_retransmitted_until_acknowledged() {
+    use std::net::UdpSocket;
+
+    let manager = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = manager.local_addr().unwrap();
+    let receiver = std::thread::spawn(move || {
+        let mut buf = [0u8; 1500];
+        let mut req_ids = Vec::new();
+        // drop the first copy, acknowledge the retransmission
+        for _ in 0..2 {
+            let (len, peer) = manager.recv_from(&mut buf).unwrap();
+            let inform = Pdu::from_bytes(&buf[..len]).unwrap();
+            assert_eq!(inform.message_type, MessageType::InformRequest);
+            req_ids.push((inform.req_id, peer));
+        }
+        let (req_id, peer) = req_ids[1];
+        let mut ack = pdu::Buf::default();
+        pdu::build(
+            Version::V2C,
+            b"public",
+            snmp::MSG_RESPONSE,
+            req_id,
+            &[],
+            0,
+            0,
+            &mut ack,
+            #[cfg(feature = "v3")]
+            None,
+        )
+        .unwrap();
+        manager.send_to(&ack, peer).unwrap();
+        req_ids
+    });
+
+    let mut session = crate::SyncSession::new_v2c(addr, b"public", None, 7).unwrap();
+    send_test_inform(&mut session, 200, 2).unwrap();
+    let req_ids = receiver.join().unwrap();
+    assert_eq!(req_ids[0].0, 7);
+    assert_eq!(req_ids[1].0, 7);
+}
+
+#[test]
+fn inform_times_out_without_ack() {
+    use std::net::UdpSocket;
+
+    let manager = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut session =
+        crate::SyncSession::new_v2c(manager.local_addr().unwrap(), b"public", None, 0).unwrap();
+    assert_eq!(send_test_inform(&mut session, 50, 1), Err(Error::Timeout));
+
+    manager.set_nonblocking(true).unwrap();
+    let mut buf = [0u8; 1500];
+    let mut copies = 0;
+    while manager.recv_from(&mut buf).is_ok() {
+        copies += 1;
+    }
+    assert_eq!(copies, 2);
+}
+
+#[test]
+fn set_no_wait_does_not_block() {
+    use std::{net::UdpSocket, time::Duration, time::Instant};
+
+    // never answers
+    let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut session = crate::SyncSession::new_v2c(
+        agent.local_addr().unwrap(),
+        b"private",
+        Some(Duration::from_secs(5)),
+        3,
+    )
+    .unwrap();
+    let reboot = Oid::from(&[1, 3, 6, 1, 4, 1, 9, 2, 9, 9, 0]).unwrap();
+    let start = Instant::now();
+    session.set_no_wait(&reboot, Value::Integer(2)).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    let mut buf = [0u8; 1500];
+    let len = agent.recv(&mut buf).unwrap();
+    let set = Pdu::from_bytes(&buf[..len]).unwrap();
+    assert_eq!(set.message_type, MessageType::SetRequest);
+    assert_eq!(set.req_id, 3);
+    let mut varbinds = set.varbinds;
+    let (oid, value) = varbinds.next().unwrap();
+    assert_eq!(oid, reboot);
+    assert!(matches!(value, Value::Integer(2)));
+}
+
+#[test]
+fn error_status_names() {
+    let names = [
+        "noError",
+        "tooBig",
+        "noSuchName",
+        "badValue",
+        "readOnly",
+        "genErr",
+        "noAccess",
+        "wrongType",
+        "wrongLength",
+        "wrongEncoding",
+        "wrongValue",
+        "noCreation",
+        "inconsistentValue",
+        "resourceUnavailable",
+        "commitFailed",
+        "undoFailed",
+        "authorizationError",
+        "notWritable",
+        "inconsistentName",
+    ];
+    for (code, name) in names.iter().enumerate() {
+        assert_eq!(snmp::error_status_name(code as u32), *name);
+    }
+    assert_eq!(snmp::error_status_name(snmp::ERRSTATUS_GENERR), "genErr");
+    assert_eq!(snmp::error_status_name(19), "unknown(19)");
+    assert_eq!(snmp::error_status_name(u32::MAX), "unknown(4294967295)");
+}
+
 #[test]
 fn asn_read_byte() {
     let bytes = [1, 2, 3, 4];
@@ -1,4 +1,4 @@
-use std::{fmt, time::Instant};
+use std::time::Instant;
 
 use openssl::{
     hash::{Hasher, MessageDigest},
@@ -13,49 +13,9 @@ use crate::{
     AsnReader, Error, MessageType, Oid, Pdu, Result, Value, Varbinds, Version, BUFFER_SIZE,
 };
 
-const ENGINE_TIME_WINDOW: i64 = 150;
-
-#[cfg(feature = "v3")]
-#[derive(Debug, PartialEq, Eq, Clone)]
-pub enum AuthErrorKind {
-    UnsupportedUSM,
-    EngineBootsMismatch,
-    EngineBootsNotProvided,
-    EngineTimeMismatch,
-    NotAuthenticated,
-    UsernameMismatch,
-    EngineIdMismatch,
-    SignatureMismatch,
-    MessageIdMismatch,
-    PrivLengthMismatch,
-    KeyLengthMismatch,
-    PayloadLengthMismatch,
-    ReplyNotEncrypted,
-    SecurityNotProvided,
-    SecurityNotReady,
-}
+pub use crate::AuthErrorKind;
 
-impl fmt::Display for AuthErrorKind {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            AuthErrorKind::UnsupportedUSM => write!(f, "Unsupported USM"),
-            AuthErrorKind::EngineBootsMismatch => write!(f, "Engine boots counter mismatch"),
-            AuthErrorKind::EngineTimeMismatch => write!(f, "Engine time counter mismatch"),
-            AuthErrorKind::NotAuthenticated => write!(f, "Not authenticated"),
-            AuthErrorKind::EngineBootsNotProvided => write!(f, "Engine boots counter not provided"),
-            AuthErrorKind::EngineIdMismatch => write!(f, "Engine ID mismatch"),
-            AuthErrorKind::UsernameMismatch => write!(f, "Username mismatch"),
-            AuthErrorKind::SignatureMismatch => write!(f, "HMAC signature mismatch"),
-            AuthErrorKind::MessageIdMismatch => write!(f, "Message ID mismatch"),
-            AuthErrorKind::PrivLengthMismatch => write!(f, "Privacy parameters length mismatch"),
-            AuthErrorKind::KeyLengthMismatch => write!(f, "Key length mismatch"),
-            AuthErrorKind::PayloadLengthMismatch => write!(f, "Payload length mismatch"),
-            AuthErrorKind::ReplyNotEncrypted => write!(f, "Not an encrypted reply"),
-            AuthErrorKind::SecurityNotProvided => write!(f, "Security parameters not provided"),
-            AuthErrorKind::SecurityNotReady => write!(f, "Security parameters not ready"),
-        }
-    }
-}
+const ENGINE_TIME_WINDOW: i64 = 150;
 
 #[derive(Debug, Clone)]
 pub(crate) struct AuthoritativeState {
@@ -538,7 +498,7 @@ impl<'a> Pdu<'a> {
         security: &'a mut Security,
     ) -> Result<Pdu<'a>> {
         let global_data_seq = rdr.read_raw(asn1::TYPE_SEQUENCE)?;
-        let mut global_data_rdr = AsnReader::from_bytes(global_data_seq);
+        let mut global_data_rdr = rdr.nested(global_data_seq);
         let msg_id = global_data_rdr.read_asn_integer()?;
         let max_size = global_data_rdr.read_asn_integer()?;
 
@@ -558,8 +518,8 @@ impl<'a> Pdu<'a> {
         }
 
         let security_params = rdr.read_asn_octetstring()?;
-        let security_seq = AsnReader::from_bytes(security_params).read_raw(asn1::TYPE_SEQUENCE)?;
-        let mut security_rdr = AsnReader::from_bytes(security_seq);
+        let security_seq = rdr.nested(security_params).read_raw(asn1::TYPE_SEQUENCE)?;
+        let mut security_rdr = rdr.nested(security_seq);
         let engine_id = security_rdr.read_asn_octetstring()?;
         let engine_boots = security_rdr.read_asn_integer()?;
         let engine_time = security_rdr.read_asn_integer()?;
@@ -653,11 +613,11 @@ impl<'a> Pdu<'a> {
         } else {
             let encrypted_pdu = rdr.read_asn_octetstring()?;
             security.decrypt(encrypted_pdu, priv_params)?;
-            let mut rdr = AsnReader::from_bytes(&security.plain_buf);
+            let mut rdr = rdr.nested(&security.plain_buf);
             rdr.read_raw(asn1::TYPE_SEQUENCE)?
         };
 
-        let mut scoped_pdu_rdr = AsnReader::from_bytes(scoped_pdu_seq);
+        let mut scoped_pdu_rdr = rdr.nested(scoped_pdu_seq);
 
         let _context_engine_id = scoped_pdu_rdr.read_asn_octetstring()?;
 
@@ -680,7 +640,7 @@ impl<'a> Pdu<'a> {
             }
         }
 
-        let mut response_pdu = AsnReader::from_bytes(scoped_pdu_rdr.read_raw(ident)?);
+        let mut response_pdu = rdr.nested(scoped_pdu_rdr.read_raw(ident)?);
 
         let req_id: i32 = i32::try_from(response_pdu.read_asn_integer()?)?;
 
@@ -690,7 +650,7 @@ impl<'a> Pdu<'a> {
         let error_index: u32 = u32::try_from(response_pdu.read_asn_integer()?)?;
 
         let varbind_bytes = response_pdu.read_raw(asn1::TYPE_SEQUENCE)?;
-        let varbinds = Varbinds::from_bytes(varbind_bytes);
+        let varbinds = Varbinds::from_reader(response_pdu.nested(varbind_bytes));
 
         if is_discovery {
             return Err(Error::AuthUpdated);